@@ -0,0 +1,106 @@
+use serde_json::{Map, Value, json};
+use std::collections::BTreeMap;
+use std::error::Error;
+
+use crate::quantity::{parse_u128, to_u128};
+use crate::rpc::RpcClient;
+use crate::snapshot::{self, find_field};
+use crate::stats::percentile;
+
+/// Block gas limit assumed when none is given and no node is available.
+pub const DEFAULT_BLOCK_GAS_LIMIT: u64 = 30_000_000;
+
+/// Gas limit of the latest block from `eth_getBlockByNumber`.
+pub fn fetch_block_gas_limit(client: &RpcClient) -> Result<u64, Box<dyn Error>> {
+    let block = client.call("eth_getBlockByNumber", json!(["latest", false]))?;
+    block
+        .get("gasLimit")
+        .and_then(Value::as_str)
+        .and_then(parse_u128)
+        .and_then(|gas| u64::try_from(gas).ok())
+        .filter(|gas| *gas > 0)
+        .ok_or_else(|| "Latest block has no gasLimit".into())
+}
+
+/// Report how much gas each sender has queued up for execution: the summed
+/// `gas_limit` of their consecutive pending nonces, starting at the lowest.
+/// Accounts whose chain needs more than one block's gas are listed under
+/// `metadata.account_gas.over_limit` with their transaction count, chain gas
+/// and the number of full blocks it spans. The distribution over all senders
+/// and the over-limit count are emitted as `txpool.account.chain_gas` (with a
+/// `quantile` label) and `txpool.account.over_gas_limit`.
+pub fn report(snapshot: &mut Value, block_gas_limit: u64) {
+    let mut nonces: BTreeMap<&str, Vec<(u64, u64)>> = BTreeMap::new();
+    for entry in snapshot::transactions(snapshot).filter(|entry| entry.section == "pending") {
+        let Ok(nonce) = entry.nonce.parse::<u64>() else {
+            continue;
+        };
+        let gas = find_field(entry.tx, "gas_limit")
+            .or_else(|| find_field(entry.tx, "gas"))
+            .and_then(to_u128)
+            .and_then(|gas| u64::try_from(gas).ok())
+            .unwrap_or(0);
+        nonces.entry(entry.account).or_default().push((nonce, gas));
+    }
+
+    let mut chain_gas = Vec::with_capacity(nonces.len());
+    let mut over_limit = Map::new();
+    for (account, mut txs) in nonces {
+        txs.sort_unstable();
+        // Checked and saturating, as nonces and gas limits near u64::MAX
+        // only come from broken or hostile dumps
+        let first = txs[0].0;
+        let executable = txs
+            .iter()
+            .enumerate()
+            .take_while(|(i, (nonce, _))| u64::try_from(*i).ok().and_then(|i| first.checked_add(i)) == Some(*nonce))
+            .count();
+        let gas = txs[..executable].iter().fold(0u64, |sum, (_, gas)| sum.saturating_add(*gas));
+        chain_gas.push(gas);
+        if gas > block_gas_limit {
+            over_limit.insert(
+                account.to_ascii_lowercase(),
+                json!({
+                    "executable_txs": executable,
+                    "chain_gas": gas,
+                    "blocks": gas.div_ceil(block_gas_limit),
+                }),
+            );
+        }
+    }
+    chain_gas.sort_unstable();
+
+    for (quantile, p) in [("p50", 50.0), ("p90", 90.0), ("p99", 99.0)] {
+        if let Some(gas) = percentile(&chain_gas, p) {
+            emit::emit!(
+                "{metric_agg} of {metric_name} is {metric_value}",
+                evt_kind: "metric",
+                metric_agg: "last",
+                metric_name: "txpool.account.chain_gas",
+                metric_value: gas,
+                quantile,
+            );
+        }
+    }
+    let over_limit_count = over_limit.len();
+    emit::emit!(
+        "{metric_agg} of {metric_name} is {metric_value}",
+        evt_kind: "metric",
+        metric_agg: "last",
+        metric_name: "txpool.account.over_gas_limit",
+        metric_value: over_limit_count,
+    );
+
+    snapshot::metadata_mut(snapshot).insert(
+        "account_gas".to_string(),
+        json!({
+            "block_gas_limit": block_gas_limit,
+            "accounts": chain_gas.len(),
+            "p50": percentile(&chain_gas, 50.0),
+            "p90": percentile(&chain_gas, 90.0),
+            "p99": percentile(&chain_gas, 99.0),
+            "max": chain_gas.last(),
+            "over_limit": over_limit,
+        }),
+    );
+}
@@ -0,0 +1,111 @@
+use serde_json::{Value, json};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::error::Error;
+use std::sync::{Arc, Mutex};
+
+use crate::rpc::RpcClient;
+use crate::snapshot;
+
+/// Blocks fetched per JSON-RPC batch.
+const BATCH_SIZE: usize = 20;
+
+/// Share of the window's blocks a sender must appear in to count as a
+/// persistent sender (bots, exchanges, relayers).
+const PERSISTENT_SHARE: f64 = 0.25;
+
+/// Transactions per sender of each fetched block, kept across the snapshots
+/// of a `--watch` or `--poll` run so each block is fetched once.
+#[derive(Clone, Default)]
+pub struct BlockCache(Arc<Mutex<HashMap<u64, HashMap<String, u32>>>>);
+
+/// Senders of the transactions in a full `eth_getBlockByNumber` block.
+fn block_senders(number: u64, block: &Value) -> Result<HashMap<String, u32>, Box<dyn Error>> {
+    let txs = block
+        .get("transactions")
+        .and_then(Value::as_array)
+        .ok_or_else(|| format!("Block {} has no transactions list", number))?;
+    let mut senders = HashMap::new();
+    for tx in txs {
+        if let Some(from) = tx.get("from").and_then(Value::as_str) {
+            *senders.entry(from.to_ascii_lowercase()).or_insert(0) += 1;
+        }
+    }
+    Ok(senders)
+}
+
+/// Count the transactions each pool sender had mined in the `blocks` blocks
+/// up to `head` and classify the sender as `persistent` (in at least a
+/// quarter of the blocks), `occasional` or `newcomer` (none at all), in
+/// `metadata.activity`. Blocks missing from `cache` are fetched in batches.
+pub fn annotate(
+    snapshot: &mut Value,
+    client: &RpcClient,
+    cache: &BlockCache,
+    head: u64,
+    blocks: u64,
+) -> Result<(), Box<dyn Error>> {
+    let from_block = head.saturating_sub(blocks.saturating_sub(1));
+    let window: Vec<u64> = (from_block..=head).collect();
+
+    let mut cache = cache.0.lock().map_err(|_| "Block activity cache is poisoned")?;
+    cache.retain(|number, _| *number >= from_block);
+    let missing: Vec<u64> = window.iter().copied().filter(|number| !cache.contains_key(number)).collect();
+    for chunk in missing.chunks(BATCH_SIZE) {
+        let params = chunk.iter().map(|number| json!([format!("0x{:x}", number), true])).collect();
+        let fetched = client.batch("eth_getBlockByNumber", params)?;
+        for (number, block) in chunk.iter().zip(fetched) {
+            if block.is_null() {
+                return Err(format!("Block {} is not available from the node", number).into());
+            }
+            cache.insert(*number, block_senders(*number, &block)?);
+        }
+    }
+
+    let pool_senders: HashSet<String> = snapshot::transactions(snapshot)
+        .map(|entry| entry.account.to_ascii_lowercase())
+        .collect();
+    let mut senders = BTreeMap::new();
+    let mut counts = BTreeMap::from([("persistent", 0usize), ("occasional", 0), ("newcomer", 0)]);
+    for sender in pool_senders {
+        let mut txs = 0u32;
+        let mut active_blocks = 0usize;
+        for number in &window {
+            if let Some(count) = cache[number].get(&sender) {
+                txs += count;
+                active_blocks += 1;
+            }
+        }
+        let class = if txs == 0 {
+            "newcomer"
+        } else if active_blocks as f64 >= PERSISTENT_SHARE * window.len() as f64 {
+            "persistent"
+        } else {
+            "occasional"
+        };
+        *counts.get_mut(class).expect("every class is counted") += 1;
+        senders.insert(sender, json!({ "txs": txs, "blocks": active_blocks, "class": class }));
+    }
+    drop(cache);
+
+    for (class, metric_value) in &counts {
+        emit::emit!(
+            "{metric_agg} of {metric_name} is {metric_value}",
+            evt_kind: "metric",
+            metric_agg: "last",
+            metric_name: "txpool.activity.senders",
+            metric_value,
+            class,
+        );
+    }
+    snapshot::metadata_mut(snapshot).insert(
+        "activity".to_string(),
+        json!({
+            "blocks": window.len(),
+            "from_block": from_block,
+            "to_block": head,
+            "counts": counts,
+            "senders": senders,
+        }),
+    );
+    Ok(())
+}
@@ -0,0 +1,178 @@
+use serde_json::{Value, json};
+use std::collections::HashSet;
+use std::error::Error;
+use std::fs;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use crate::creation::is_creation;
+use crate::quantity::{bytes_to_hex, format_units, hex_to_bytes, to_u128};
+use crate::rlp;
+use crate::snapshot::{self, find_field};
+
+/// Which new transactions are worth an alert, and where alerts go.
+#[derive(Clone, Default)]
+pub struct AlertRules {
+    /// Alert on transfers of at least this many wei.
+    pub min_value: Option<u128>,
+    /// Alert on transactions sent to any of these addresses (lowercase).
+    pub recipients: Vec<String>,
+    /// Alert on contract creations.
+    pub contract_creations: bool,
+    /// Webhook that receives each batch of alerts as a JSON POST.
+    pub webhook: Option<String>,
+    /// Hashes of the last snapshot evaluated in this process, shared across
+    /// the per-file option copies of watch and poll modes.
+    seen: Arc<Mutex<Option<HashSet<String>>>>,
+}
+
+impl AlertRules {
+    pub fn is_enabled(&self) -> bool {
+        self.min_value.is_some() || !self.recipients.is_empty() || self.contract_creations
+    }
+
+    fn reasons(&self, tx: &Value) -> Vec<String> {
+        let mut reasons = Vec::new();
+        let value = find_field(tx, "value").and_then(to_u128).unwrap_or(0);
+        if let Some(min_value) = self.min_value
+            && value >= min_value
+        {
+            reasons.push("value".to_string());
+        }
+        match find_field(tx, "to").and_then(Value::as_str) {
+            Some(to) if to.starts_with("0x") => {
+                if self.recipients.iter().any(|recipient| recipient.eq_ignore_ascii_case(to)) {
+                    reasons.push("recipient".to_string());
+                }
+            }
+            _ => {
+                if self.contract_creations && is_creation(tx) {
+                    reasons.push("contract_creation".to_string());
+                }
+            }
+        }
+        reasons
+    }
+}
+
+fn tx_hash(tx: &Value) -> Option<String> {
+    find_field(tx, "hash").and_then(Value::as_str).map(str::to_ascii_lowercase)
+}
+
+/// Hashes of the snapshot the current one is diffed against: the previous
+/// snapshot of this process in watch and poll modes, otherwise the
+/// `--track-inclusion` file if given. `None` means everything is new.
+fn previous_hashes(rules: &AlertRules, previous_path: Option<&str>) -> Option<HashSet<String>> {
+    if let Some(seen) = rules.seen.lock().ok().and_then(|seen| seen.clone()) {
+        return Some(seen);
+    }
+    let previous: Value = serde_json::from_str(&fs::read_to_string(previous_path?).ok()?).ok()?;
+    Some(snapshot::transactions(&previous).filter_map(|entry| tx_hash(entry.tx)).collect())
+}
+
+/// Details of one alerting transaction: who, where, how much, and the
+/// called function selector.
+fn describe(section: &str, account: &str, tx: &Value, reasons: Vec<String>) -> Value {
+    let value = find_field(tx, "value").and_then(to_u128).unwrap_or(0);
+    let to = find_field(tx, "to").and_then(Value::as_str).filter(|to| to.starts_with("0x"));
+    let input = find_field(tx, "input").and_then(Value::as_str).and_then(hex_to_bytes).unwrap_or_default();
+    let selector = (input.len() >= 4).then(|| bytes_to_hex(&input[..4]));
+    let fee_cap = find_field(tx, "max_fee_per_gas")
+        .or_else(|| find_field(tx, "gas_price"))
+        .and_then(to_u128);
+    json!({
+        "reasons": reasons,
+        "hash": tx_hash(tx),
+        "section": section,
+        "from": account.to_ascii_lowercase(),
+        "to": to.map(str::to_ascii_lowercase),
+        "creates_contract": is_creation(tx),
+        "nonce": find_field(tx, "nonce"),
+        "tx_type": rlp::tx_type(tx),
+        "value_wei": value.to_string(),
+        "value_eth": format_units(value, 18, 6),
+        "fee_cap_gwei": fee_cap.map(|wei| format_units(wei, 9, 2)),
+        "selector": selector,
+        "input_bytes": input.len(),
+    })
+}
+
+/// Diff the snapshot against the previous one and alert on new transactions
+/// that match `rules`: each alert is logged, printed to stderr, counted in
+/// `txpool.alerts` by reason, and the batch is posted to the webhook. A
+/// webhook failure is reported but does not fail the conversion.
+pub fn evaluate(snapshot: &Value, rules: &AlertRules, previous_path: Option<&str>) {
+    let previous = previous_hashes(rules, previous_path);
+    let mut alerts = Vec::new();
+    for entry in snapshot::transactions(snapshot) {
+        let hash = tx_hash(entry.tx);
+        let is_new = match (&previous, &hash) {
+            (Some(previous), Some(hash)) => !previous.contains(hash),
+            _ => true,
+        };
+        if !is_new {
+            continue;
+        }
+        let reasons = rules.reasons(entry.tx);
+        if !reasons.is_empty() {
+            alerts.push(describe(entry.section, entry.account, entry.tx, reasons));
+        }
+    }
+    if let Ok(mut seen) = rules.seen.lock() {
+        *seen = Some(snapshot::transactions(snapshot).filter_map(|entry| tx_hash(entry.tx)).collect());
+    }
+
+    for reason in ["value", "recipient", "contract_creation"] {
+        let count = alerts
+            .iter()
+            .filter(|alert| alert["reasons"].as_array().is_some_and(|r| r.iter().any(|r| r == reason)))
+            .count();
+        if count > 0 {
+            emit::emit!(
+                "{metric_agg} of {metric_name} is {metric_value}",
+                evt_kind: "metric",
+                metric_agg: "count",
+                metric_name: "txpool.alerts",
+                metric_value: count,
+                reason,
+            );
+        }
+    }
+    for alert in &alerts {
+        let reasons: Vec<&str> = alert["reasons"].as_array().into_iter().flatten().filter_map(Value::as_str).collect();
+        let line = format!(
+            "Alert [{}]: {} from {} to {} value {} ETH",
+            reasons.join(","),
+            alert["hash"].as_str().unwrap_or("-"),
+            alert["from"].as_str().unwrap_or("-"),
+            match alert["to"].as_str() {
+                Some(to) => to,
+                None if alert["creates_contract"] == true => "(create)",
+                None => "-",
+            },
+            alert["value_eth"].as_str().unwrap_or("0"),
+        );
+        emit::warn!("{line}", line: line.clone());
+        eprintln!("{}", line);
+    }
+
+    if let Some(webhook) = &rules.webhook
+        && !alerts.is_empty()
+        && let Err(err) = post(webhook, &alerts)
+    {
+        let err = err.to_string();
+        emit::error!("Alert webhook {webhook} failed: {err}", webhook, err);
+        eprintln!("Alert webhook {} failed: {}", webhook, err);
+    }
+}
+
+/// POST the alerts as `{"text": ..., "alerts": [...]}`; `text` makes the
+/// payload readable in Slack-compatible incoming webhooks.
+fn post(webhook: &str, alerts: &[Value]) -> Result<(), Box<dyn Error>> {
+    let text = format!("{} notable new txpool transaction(s)", alerts.len());
+    ureq::post(webhook)
+        .set("Content-Type", "application/json")
+        .timeout(Duration::from_secs(10))
+        .send_string(&json!({ "text": text, "alerts": alerts }).to_string())?;
+    Ok(())
+}
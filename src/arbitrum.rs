@@ -0,0 +1,67 @@
+use serde_json::{Value, json};
+use std::collections::BTreeMap;
+
+use crate::quantity::parse_u128;
+use crate::snapshot::{self, find_field};
+
+/// Arbitrum Nitro transaction types, as documented in `arbos/types`.
+const ARBITRUM_TX_TYPES: [(u64, &str); 7] = [
+    (0x64, "ArbitrumDepositTx"),
+    (0x65, "ArbitrumUnsignedTx"),
+    (0x66, "ArbitrumContractTx"),
+    (0x68, "ArbitrumRetryTx"),
+    (0x69, "ArbitrumSubmitRetryableTx"),
+    (0x6a, "ArbitrumInternalTx"),
+    (0x78, "ArbitrumLegacyTx"),
+];
+
+/// Name of an Arbitrum-specific transaction type code.
+pub fn type_name(code: u64) -> Option<&'static str> {
+    ARBITRUM_TX_TYPES
+        .iter()
+        .find(|(known, _)| *known == code)
+        .map(|(_, name)| *name)
+}
+
+/// Type code of a transaction that came through as an unknown envelope,
+/// either from its `ty` or from the raw `type` field it carries.
+pub fn envelope_type(tx: &Value) -> Option<u64> {
+    find_field(tx, "ty").and_then(Value::as_u64).or_else(|| {
+        find_field(tx, "fields")
+            .and_then(|fields| find_field(fields, "type"))
+            .and_then(Value::as_str)
+            .and_then(parse_u128)
+            .and_then(|code| u64::try_from(code).ok())
+    })
+}
+
+/// Tag every Arbitrum-specific transaction with its `tx_type` code and
+/// `tx_type_name`, emitting a count per type.
+pub fn annotate(snapshot: &mut Value) {
+    let mut counts: BTreeMap<&'static str, usize> = BTreeMap::new();
+
+    for tx in snapshot::transactions_mut(snapshot) {
+        let Some(code) = envelope_type(tx) else {
+            continue;
+        };
+        let Some(name) = type_name(code) else {
+            continue;
+        };
+        if let Some(tx) = tx.as_object_mut() {
+            tx.insert("tx_type".to_string(), json!(code));
+            tx.insert("tx_type_name".to_string(), json!(name));
+        }
+        *counts.entry(name).or_insert(0) += 1;
+    }
+
+    for (tx_type, count) in counts {
+        emit::emit!(
+            "{metric_agg} of {metric_name} is {metric_value}",
+            evt_kind: "metric",
+            metric_agg: "count",
+            metric_name: "txpool.arbitrum.txs",
+            metric_value: count,
+            tx_type,
+        );
+    }
+}
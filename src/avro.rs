@@ -0,0 +1,268 @@
+use serde_json::{Value, json};
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+
+use crate::blob;
+use crate::chain::tx_chain_id;
+use crate::quantity::{hex_to_bytes, to_u128};
+use crate::rlp;
+use crate::snapshot::{self, find_field};
+
+/// Records per container block.
+const BLOCK_RECORDS: usize = 4096;
+
+/// Avro schema of one pool transaction. Field order is the encoding order, so
+/// fields are only ever appended. Wei amounts are decimal strings because they
+/// can exceed 64 bits.
+pub fn transaction_schema() -> Value {
+    let nullable = |name: &str, ty: Value| json!({ "name": name, "type": ["null", ty], "default": null });
+    let timestamp = json!({ "type": "long", "logicalType": "timestamp-millis" });
+    json!({
+        "type": "record",
+        "name": "PoolTransaction",
+        "namespace": "io.github.sambacha.txpool",
+        "fields": [
+            nullable("snapshot_time", timestamp.clone()),
+            nullable("first_seen", timestamp),
+            { "name": "section", "type": "string" },
+            { "name": "from_address", "type": "string" },
+            { "name": "nonce", "type": "long" },
+            { "name": "tx_type", "type": "int" },
+            nullable("chain_id", json!("long")),
+            nullable("tx_hash", json!("string")),
+            nullable("to_address", json!("string")),
+            nullable("value", json!("string")),
+            nullable("gas_limit", json!("long")),
+            nullable("gas_price", json!("string")),
+            nullable("max_fee_per_gas", json!("string")),
+            nullable("max_priority_fee_per_gas", json!("string")),
+            nullable("max_fee_per_blob_gas", json!("string")),
+            { "name": "blob_count", "type": "int" },
+            nullable("input_bytes", json!("long")),
+            nullable("size_bytes", json!("long")),
+        ]
+    })
+}
+
+/// Avro object container file (uncompressed) holding every transaction of the
+/// snapshot as a [`transaction_schema`] record.
+pub fn to_container(snapshot: &Value) -> Vec<u8> {
+    let snapshot_time = snapshot::captured_at(snapshot);
+    let sync = sync_marker();
+
+    let mut out = b"Obj\x01".to_vec();
+    let schema = transaction_schema().to_string();
+    long(&mut out, 2);
+    string(&mut out, "avro.schema");
+    bytes(&mut out, schema.as_bytes());
+    string(&mut out, "avro.codec");
+    bytes(&mut out, b"null");
+    long(&mut out, 0);
+    out.extend_from_slice(&sync);
+
+    let records: Vec<Vec<u8>> = snapshot::transactions(snapshot)
+        .filter_map(|entry| {
+            let mut record = Vec::new();
+            let uint = |key: &str| find_field(entry.tx, key).and_then(to_u128);
+            let hex = |key: &str| {
+                find_field(entry.tx, key)
+                    .and_then(Value::as_str)
+                    .filter(|hex| hex.starts_with("0x") && hex_to_bytes(hex).is_some())
+                    .map(str::to_ascii_lowercase)
+            };
+            let millis = |seconds: u64| seconds as i64 * 1000;
+
+            optional(&mut record, snapshot_time.map(millis), long);
+            optional(&mut record, entry.tx.get("first_seen").and_then(Value::as_u64).map(millis), long);
+            string(&mut record, entry.section);
+            string(&mut record, &entry.account.to_ascii_lowercase());
+            long(&mut record, entry.nonce.parse().ok()?);
+            long(&mut record, i64::from(rlp::tx_type(entry.tx)));
+            optional(&mut record, tx_chain_id(entry.tx).map(|id| id as i64), long);
+            optional(&mut record, hex("hash").as_deref(), string);
+            optional(&mut record, hex("to").as_deref(), string);
+            optional(&mut record, uint("value").map(|n| n.to_string()).as_deref(), string);
+            optional(&mut record, uint("gas_limit").map(|n| n as i64), long);
+            for key in ["gas_price", "max_fee_per_gas", "max_priority_fee_per_gas", "max_fee_per_blob_gas"] {
+                optional(&mut record, uint(key).map(|n| n.to_string()).as_deref(), string);
+            }
+            long(&mut record, blob::blob_count(entry.tx) as i64);
+            let input_bytes = find_field(entry.tx, "input")
+                .and_then(Value::as_str)
+                .and_then(hex_to_bytes)
+                .map(|input| input.len() as i64);
+            optional(&mut record, input_bytes, long);
+            optional(&mut record, uint("size_bytes").map(|n| n as i64), long);
+            Some(record)
+        })
+        .collect();
+
+    for block in records.chunks(BLOCK_RECORDS) {
+        long(&mut out, block.len() as i64);
+        long(&mut out, block.iter().map(Vec::len).sum::<usize>() as i64);
+        for record in block {
+            out.extend_from_slice(record);
+        }
+        out.extend_from_slice(&sync);
+    }
+    out
+}
+
+/// Zig-zag varint encoding of an Avro `long` (also used for `int`).
+fn long(out: &mut Vec<u8>, value: i64) {
+    let mut n = ((value << 1) ^ (value >> 63)) as u64;
+    while n >= 0x80 {
+        out.push((n as u8 & 0x7f) | 0x80);
+        n >>= 7;
+    }
+    out.push(n as u8);
+}
+
+fn bytes(out: &mut Vec<u8>, value: &[u8]) {
+    long(out, value.len() as i64);
+    out.extend_from_slice(value);
+}
+
+fn string(out: &mut Vec<u8>, value: &str) {
+    bytes(out, value.as_bytes());
+}
+
+/// A `["null", T]` union: branch index, then the value if present.
+fn optional<T>(out: &mut Vec<u8>, value: Option<T>, encode: fn(&mut Vec<u8>, T)) {
+    match value {
+        Some(value) => {
+            long(out, 1);
+            encode(out, value);
+        }
+        None => long(out, 0),
+    }
+}
+
+/// Random 16-byte sync marker separating container blocks.
+fn sync_marker() -> [u8; 16] {
+    let mut marker = [0u8; 16];
+    for half in marker.chunks_mut(8) {
+        half.copy_from_slice(&RandomState::new().build_hasher().finish().to_le_bytes());
+    }
+    marker
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Decodes the Avro primitives written above, independently of them.
+    struct Reader<'a>(&'a [u8]);
+
+    impl Reader<'_> {
+        fn take(&mut self, len: usize) -> &[u8] {
+            let (head, rest) = self.0.split_at(len);
+            self.0 = rest;
+            head
+        }
+
+        fn long(&mut self) -> i64 {
+            let mut n = 0u64;
+            for shift in (0..).step_by(7) {
+                let byte = self.take(1)[0];
+                n |= u64::from(byte & 0x7f) << shift;
+                if byte < 0x80 {
+                    break;
+                }
+            }
+            (n >> 1) as i64 ^ -((n & 1) as i64)
+        }
+
+        fn string(&mut self) -> String {
+            let len = self.long() as usize;
+            String::from_utf8(self.take(len).to_vec()).unwrap()
+        }
+
+        fn optional_long(&mut self) -> Option<i64> {
+            (self.long() == 1).then(|| self.long())
+        }
+
+        fn optional_string(&mut self) -> Option<String> {
+            (self.long() == 1).then(|| self.string())
+        }
+    }
+
+    #[test]
+    fn zig_zag_longs() {
+        for (value, expected) in [
+            (0, vec![0x00]),
+            (-1, vec![0x01]),
+            (1, vec![0x02]),
+            (-64, vec![0x7f]),
+            (64, vec![0x80, 0x01]),
+        ] {
+            let mut out = Vec::new();
+            long(&mut out, value);
+            assert_eq!(out, expected, "{}", value);
+        }
+    }
+
+    #[test]
+    fn container_holds_header_sync_marker_and_records() {
+        let snapshot = json!({
+            "metadata": { "captured_at": "2024-01-01T00:00:00Z" },
+            "pending": {
+                "0x09F1FD9D03F0A9B4553274161BBF84755D5BCA46": {
+                    "7": { "inner": { "inner": {
+                        "inner": {
+                            "tx": {
+                                "chain_id": 1,
+                                "nonce": 7,
+                                "gas_limit": 21000,
+                                "max_fee_per_gas": 30000000000u64,
+                                "max_priority_fee_per_gas": 1000000000,
+                                "to": "0xff9b273f422863872601349fef087eb05753f8ef",
+                                "value": "1000000000000000000000000",
+                                "access_list": [],
+                                "input": "0x1234"
+                            },
+                            "hash": "0xAB"
+                        },
+                        "signer": "0x09f1fd9d03f0a9b4553274161bbf84755d5bca46"
+                    } } }
+                }
+            }
+        });
+        let container = to_container(&snapshot);
+        let mut reader = Reader(&container);
+
+        assert_eq!(reader.take(4), b"Obj\x01");
+        assert_eq!(reader.long(), 2);
+        assert_eq!(reader.string(), "avro.schema");
+        let schema: Value = serde_json::from_str(&reader.string()).unwrap();
+        assert_eq!(schema, transaction_schema());
+        assert_eq!(reader.string(), "avro.codec");
+        assert_eq!(reader.string(), "null");
+        assert_eq!(reader.long(), 0);
+        let sync = reader.take(16).to_vec();
+
+        assert_eq!(reader.long(), 1);
+        let size = reader.long() as usize;
+        assert_eq!(size, reader.0.len() - 16);
+        assert_eq!(reader.optional_long(), Some(1_704_067_200_000));
+        assert_eq!(reader.optional_long(), None);
+        assert_eq!(reader.string(), "pending");
+        assert_eq!(reader.string(), "0x09f1fd9d03f0a9b4553274161bbf84755d5bca46");
+        assert_eq!(reader.long(), 7);
+        assert_eq!(reader.long(), 2);
+        assert_eq!(reader.optional_long(), Some(1));
+        assert_eq!(reader.optional_string().as_deref(), Some("0xab"));
+        assert_eq!(reader.optional_string().as_deref(), Some("0xff9b273f422863872601349fef087eb05753f8ef"));
+        assert_eq!(reader.optional_string().as_deref(), Some("1000000000000000000000000"));
+        assert_eq!(reader.optional_long(), Some(21000));
+        assert_eq!(reader.optional_string(), None);
+        assert_eq!(reader.optional_string().as_deref(), Some("30000000000"));
+        assert_eq!(reader.optional_string().as_deref(), Some("1000000000"));
+        assert_eq!(reader.optional_string(), None);
+        assert_eq!(reader.long(), 0);
+        assert_eq!(reader.optional_long(), Some(2));
+        assert_eq!(reader.optional_long(), None);
+        assert_eq!(reader.take(16), sync);
+        assert!(reader.0.is_empty());
+    }
+}
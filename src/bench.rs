@@ -0,0 +1,65 @@
+use std::error::Error;
+use std::time::Instant;
+
+use crate::input_format::InputFormat;
+use crate::parse_debug_format;
+use crate::snapshot;
+use crate::synth::{self, SynthSpec};
+use crate::unknown_fields::UnknownFields;
+
+/// Parse a synthetic dump of `txs` transactions `iterations` times and print
+/// throughput for each run plus the best run.
+pub fn run(txs: usize, iterations: usize) -> Result<(), Box<dyn Error>> {
+    let spec = SynthSpec::with_txs(txs);
+    let dump = synth::content_dump(&spec);
+    let megabytes = dump.len() as f64 / (1024.0 * 1024.0);
+
+    println!(
+        "Generated synthetic dump: {} txs, {:.2} MB",
+        txs, megabytes
+    );
+
+    let mut best_secs = f64::MAX;
+    for iteration in 1..=iterations {
+        let start = Instant::now();
+        let (parsed, _) = parse_debug_format(&dump, InputFormat::Content, UnknownFields::default(), None)?;
+        let secs = start.elapsed().as_secs_f64().max(f64::EPSILON);
+
+        let parsed_txs = snapshot::transactions(&parsed).count();
+        if parsed_txs != txs {
+            return Err(format!("Parsed {} txs but generated {}", parsed_txs, txs).into());
+        }
+
+        println!(
+            "run {}: {:.3}s, {:.2} MB/s, {:.0} txs/s",
+            iteration,
+            secs,
+            megabytes / secs,
+            txs as f64 / secs
+        );
+        best_secs = best_secs.min(secs);
+    }
+
+    let mb_per_sec = megabytes / best_secs;
+    let txs_per_sec = txs as f64 / best_secs;
+    println!("best: {:.2} MB/s, {:.0} txs/s", mb_per_sec, txs_per_sec);
+
+    emit::emit!(
+        "{metric_agg} of {metric_name} is {metric_value}",
+        evt_kind: "metric",
+        metric_agg: "last",
+        metric_name: "txpool.bench.mb_per_sec",
+        metric_value: mb_per_sec,
+        bench_txs: txs,
+    );
+    emit::emit!(
+        "{metric_agg} of {metric_name} is {metric_value}",
+        evt_kind: "metric",
+        metric_agg: "last",
+        metric_name: "txpool.bench.txs_per_sec",
+        metric_value: txs_per_sec,
+        bench_txs: txs,
+    );
+
+    Ok(())
+}
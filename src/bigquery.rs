@@ -0,0 +1,140 @@
+use serde_json::{Value, json};
+use std::error::Error;
+use std::time::Duration;
+
+use crate::blob;
+use crate::chain::tx_chain_id;
+use crate::quantity::{hex_to_bytes, to_u128};
+use crate::rlp;
+use crate::snapshot::{self, find_field};
+use crate::time::rfc3339;
+
+/// Rows per `tabledata.insertAll` request, as recommended by BigQuery.
+const INSERT_BATCH: usize = 500;
+
+/// BigQuery table schema of the exported rows, in the JSON form accepted by
+/// `bq mk --schema` (printed by `rust-txpool schema bigquery`). Wei amounts are
+/// `BIGNUMERIC` so 256-bit values fit.
+pub fn table_schema() -> Value {
+    let field = |name: &str, ty: &str, mode: &str| json!({ "name": name, "type": ty, "mode": mode });
+    json!([
+        field("snapshot_time", "TIMESTAMP", "REQUIRED"),
+        field("first_seen", "TIMESTAMP", "NULLABLE"),
+        field("section", "STRING", "REQUIRED"),
+        field("chain_id", "INT64", "NULLABLE"),
+        field("tx_hash", "STRING", "NULLABLE"),
+        field("from_address", "STRING", "REQUIRED"),
+        field("to_address", "STRING", "NULLABLE"),
+        field("nonce", "INT64", "REQUIRED"),
+        field("tx_type", "INT64", "REQUIRED"),
+        field("value", "BIGNUMERIC", "NULLABLE"),
+        field("gas_limit", "INT64", "NULLABLE"),
+        field("gas_price", "BIGNUMERIC", "NULLABLE"),
+        field("max_fee_per_gas", "BIGNUMERIC", "NULLABLE"),
+        field("max_priority_fee_per_gas", "BIGNUMERIC", "NULLABLE"),
+        field("max_fee_per_blob_gas", "BIGNUMERIC", "NULLABLE"),
+        field("blob_count", "INT64", "REQUIRED"),
+        field("input_bytes", "INT64", "NULLABLE"),
+        field("size_bytes", "INT64", "NULLABLE"),
+    ])
+}
+
+/// One row per transaction matching [`table_schema`]. Numeric columns are
+/// written as decimal strings, which BigQuery accepts for every numeric type.
+pub fn rows(snapshot: &Value) -> Vec<Value> {
+    let snapshot_time = snapshot::captured_at(snapshot).map(rfc3339);
+    let uint = |tx: &Value, key: &str| find_field(tx, key).and_then(to_u128).map(|n| n.to_string());
+    let hex = |tx: &Value, key: &str| {
+        find_field(tx, key)
+            .and_then(Value::as_str)
+            .filter(|hex| hex.starts_with("0x") && hex_to_bytes(hex).is_some())
+            .map(str::to_ascii_lowercase)
+    };
+
+    snapshot::transactions(snapshot)
+        .map(|entry| {
+            let tx = entry.tx;
+            json!({
+                "snapshot_time": snapshot_time,
+                "first_seen": tx.get("first_seen").and_then(Value::as_u64).map(rfc3339),
+                "section": entry.section,
+                "chain_id": tx_chain_id(tx).map(|id| id.to_string()),
+                "tx_hash": hex(tx, "hash"),
+                "from_address": entry.account.to_ascii_lowercase(),
+                "to_address": hex(tx, "to"),
+                "nonce": entry.nonce,
+                "tx_type": rlp::tx_type(tx).to_string(),
+                "value": uint(tx, "value"),
+                "gas_limit": uint(tx, "gas_limit"),
+                "gas_price": uint(tx, "gas_price"),
+                "max_fee_per_gas": uint(tx, "max_fee_per_gas"),
+                "max_priority_fee_per_gas": uint(tx, "max_priority_fee_per_gas"),
+                "max_fee_per_blob_gas": uint(tx, "max_fee_per_blob_gas"),
+                "blob_count": blob::blob_count(tx).to_string(),
+                "input_bytes": find_field(tx, "input")
+                    .and_then(Value::as_str)
+                    .and_then(hex_to_bytes)
+                    .map(|input| input.len().to_string()),
+                "size_bytes": uint(tx, "size_bytes"),
+            })
+        })
+        .collect()
+}
+
+/// Newline-delimited JSON of [`rows`], ready for `bq load --source_format=NEWLINE_DELIMITED_JSON`.
+pub fn to_ndjson(snapshot: &Value) -> Result<String, Box<dyn Error>> {
+    let mut out = String::new();
+    for row in rows(snapshot) {
+        out.push_str(&serde_json::to_string(&row)?);
+        out.push('\n');
+    }
+    Ok(out)
+}
+
+/// Stream the rows into `table` (`project.dataset.table`) with the
+/// `tabledata.insertAll` API, authenticating with an OAuth access token.
+/// Returns the number of rows inserted.
+pub fn stream(snapshot: &Value, table: &str, access_token: &str) -> Result<usize, Box<dyn Error>> {
+    let parts: Vec<&str> = table.split('.').collect();
+    let [project, dataset, table] = parts[..] else {
+        return Err(format!("BigQuery table must be project.dataset.table, got {}", table).into());
+    };
+    let url = format!(
+        "https://bigquery.googleapis.com/bigquery/v2/projects/{}/datasets/{}/tables/{}/insertAll",
+        project, dataset, table
+    );
+
+    let rows = rows(snapshot);
+    for batch in rows.chunks(INSERT_BATCH) {
+        // Snapshot time, sender and nonce identify a row, so BigQuery can drop
+        // duplicates from retried requests.
+        let batch: Vec<Value> = batch
+            .iter()
+            .map(|row| {
+                let key = |column: &str| row[column].as_str().unwrap_or_default().to_string();
+                let insert_id = format!("{}-{}-{}", key("snapshot_time"), key("from_address"), key("nonce"));
+                json!({ "insertId": insert_id, "json": row })
+            })
+            .collect();
+        let body = ureq::post(&url)
+            .set("Authorization", &format!("Bearer {}", access_token))
+            .set("Content-Type", "application/json")
+            .timeout(Duration::from_secs(30))
+            .send_string(&json!({ "rows": batch }).to_string())?
+            .into_string()?;
+        let response: Value = serde_json::from_str(&body)?;
+        if let Some(errors) = response.get("insertErrors") {
+            return Err(format!("BigQuery rejected rows: {}", errors).into());
+        }
+    }
+
+    let inserted = rows.len();
+    emit::emit!(
+        "{metric_agg} of {metric_name} is {metric_value}",
+        evt_kind: "metric",
+        metric_agg: "count",
+        metric_name: "txpool.bigquery.rows",
+        metric_value: inserted,
+    );
+    Ok(inserted)
+}
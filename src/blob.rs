@@ -0,0 +1,171 @@
+use serde_json::{Value, json};
+use std::collections::HashMap;
+use std::error::Error;
+
+use crate::quantity::{parse_u128, to_json, to_u128};
+use crate::rpc::RpcClient;
+use crate::snapshot::{self, find_field};
+use crate::stats::percentile;
+
+/// Blob pool policy senders are checked against, as clients enforce it.
+#[derive(Clone, Copy)]
+pub struct BlobLimits {
+    /// Blob transactions one sender may have pooled; geth's blob pool allows 16.
+    pub max_txs_per_sender: usize,
+    /// Blobs one sender may have pooled, when the policy caps them.
+    pub max_blobs_per_sender: Option<usize>,
+}
+
+impl Default for BlobLimits {
+    fn default() -> Self {
+        BlobLimits {
+            max_txs_per_sender: 16,
+            max_blobs_per_sender: None,
+        }
+    }
+}
+
+/// Number of blobs carried by a transaction (zero for non-4844 transactions).
+pub fn blob_count(tx: &Value) -> usize {
+    find_field(tx, "blob_versioned_hashes")
+        .and_then(Value::as_array)
+        .map_or(0, Vec::len)
+}
+
+/// `max_fee_per_blob_gas` of a blob transaction.
+pub fn max_fee_per_blob_gas(tx: &Value) -> Option<u128> {
+    find_field(tx, "max_fee_per_blob_gas").and_then(to_u128)
+}
+
+/// Current blob base fee from `eth_blobBaseFee`.
+pub fn fetch_blob_base_fee(client: &RpcClient) -> Result<u128, Box<dyn Error>> {
+    let result = client.call("eth_blobBaseFee", json!([]))?;
+    result
+        .as_str()
+        .and_then(parse_u128)
+        .ok_or_else(|| format!("eth_blobBaseFee returned {}", result).into())
+}
+
+/// Summarize blob demand in the pool: blobs pending, the distribution of
+/// `max_fee_per_blob_gas` per blob, how many pending blobs would clear at
+/// `blob_base_fee`, and blob transactions and blobs per sender, flagging
+/// senders over `limits`. Records `metadata.blob_market`; does nothing for
+/// pools without blob transactions.
+pub fn analyze(snapshot: &mut Value, blob_base_fee: Option<u128>, limits: &BlobLimits) {
+    let mut blob_txs = 0usize;
+    let mut total_blobs = 0usize;
+    let mut pending_blobs = 0usize;
+    let mut clearing_blobs = 0usize;
+    // One entry per blob so the distribution is weighted by blob count.
+    let mut fees: Vec<u128> = Vec::new();
+    // (blob transactions, blobs) per sender
+    let mut senders: HashMap<String, (usize, usize)> = HashMap::new();
+
+    for entry in snapshot::transactions(snapshot) {
+        let blobs = blob_count(entry.tx);
+        if blobs == 0 {
+            continue;
+        }
+        blob_txs += 1;
+        total_blobs += blobs;
+        let sender = senders.entry(entry.account.to_ascii_lowercase()).or_default();
+        sender.0 += 1;
+        sender.1 += blobs;
+
+        let fee = max_fee_per_blob_gas(entry.tx).unwrap_or(0);
+        fees.extend(std::iter::repeat_n(fee, blobs));
+
+        if entry.section == "pending" {
+            pending_blobs += blobs;
+            if blob_base_fee.is_some_and(|base| fee >= base) {
+                clearing_blobs += blobs;
+            }
+        }
+    }
+
+    if blob_txs == 0 {
+        return;
+    }
+    fees.sort_unstable();
+
+    emit::emit!(
+        "{metric_agg} of {metric_name} is {metric_value}",
+        evt_kind: "metric",
+        metric_agg: "last",
+        metric_name: "txpool.blobs.pending",
+        metric_value: pending_blobs,
+    );
+    if blob_base_fee.is_some() {
+        emit::emit!(
+            "{metric_agg} of {metric_name} is {metric_value}",
+            evt_kind: "metric",
+            metric_agg: "last",
+            metric_name: "txpool.blobs.clearing",
+            metric_value: clearing_blobs,
+        );
+    }
+
+    let mut senders: Vec<(String, (usize, usize))> = senders.into_iter().collect();
+    senders.sort_by(|(a, (a_txs, a_blobs)), (b, (b_txs, b_blobs))| {
+        (b_blobs, b_txs).cmp(&(a_blobs, a_txs)).then_with(|| a.cmp(b))
+    });
+    let mut over_limit = 0usize;
+    let senders: Vec<Value> = senders
+        .into_iter()
+        .map(|(sender, (txs, blobs))| {
+            let mut exceeds = Vec::new();
+            if txs > limits.max_txs_per_sender {
+                exceeds.push("max_blob_txs_per_sender");
+            }
+            if limits.max_blobs_per_sender.is_some_and(|max| blobs > max) {
+                exceeds.push("max_blobs_per_sender");
+            }
+            if !exceeds.is_empty() {
+                over_limit += 1;
+            }
+            json!({ "sender": sender, "blob_txs": txs, "blobs": blobs, "exceeds": exceeds })
+        })
+        .collect();
+    emit::emit!(
+        "{metric_agg} of {metric_name} is {metric_value}",
+        evt_kind: "metric",
+        metric_agg: "last",
+        metric_name: "txpool.blobs.senders",
+        metric_value: senders.len(),
+    );
+    emit::emit!(
+        "{metric_agg} of {metric_name} is {metric_value}",
+        evt_kind: "metric",
+        metric_agg: "last",
+        metric_name: "txpool.blobs.over_limit_senders",
+        metric_value: over_limit,
+    );
+
+    let distribution = json!({
+        "min": fees.first().copied().map(to_json),
+        "p25": percentile(&fees, 25.0).map(to_json),
+        "p50": percentile(&fees, 50.0).map(to_json),
+        "p75": percentile(&fees, 75.0).map(to_json),
+        "p90": percentile(&fees, 90.0).map(to_json),
+        "max": fees.last().copied().map(to_json),
+    });
+
+    let metadata = snapshot::metadata_mut(snapshot);
+    metadata.insert(
+        "blob_market".to_string(),
+        json!({
+            "blob_txs": blob_txs,
+            "total_blobs": total_blobs,
+            "pending_blobs": pending_blobs,
+            "max_fee_per_blob_gas": distribution,
+            "blob_base_fee": blob_base_fee.map(to_json),
+            "clearing_blobs": blob_base_fee.map(|_| clearing_blobs),
+            "limits": {
+                "max_blob_txs_per_sender": limits.max_txs_per_sender,
+                "max_blobs_per_sender": limits.max_blobs_per_sender,
+            },
+            "over_limit_senders": over_limit,
+            "senders": senders,
+        }),
+    );
+}
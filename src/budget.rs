@@ -0,0 +1,140 @@
+use serde_json::{Value, json};
+use std::error::Error;
+use std::io::{self, Read};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Input read under `--max-input-bytes`.
+pub struct Input {
+    pub text: String,
+    /// The source was longer than the budget and `text` stops at it.
+    pub cut: bool,
+}
+
+/// Read `reader` to the end, or only its first `max_bytes` when it is longer,
+/// so an accidental multi-gigabyte capture is never loaded whole. A character
+/// split by the budget is dropped.
+pub fn read(reader: impl Read, max_bytes: Option<usize>) -> io::Result<Input> {
+    let mut bytes = Vec::new();
+    reader
+        .take(max_bytes.map_or(u64::MAX, |max| max as u64 + 1))
+        .read_to_end(&mut bytes)?;
+    let cut = max_bytes.is_some_and(|max| bytes.len() > max);
+    if let Some(max) = max_bytes.filter(|_| cut) {
+        bytes.truncate(max);
+        if let Err(err) = std::str::from_utf8(&bytes)
+            && err.error_len().is_none()
+        {
+            bytes.truncate(err.valid_up_to());
+        }
+    }
+    let text = String::from_utf8(bytes).map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+    Ok(Input { text, cut })
+}
+
+/// The `--timeout` of one snapshot, counted from when reading the pool began.
+pub struct Deadline {
+    timeout: Option<Duration>,
+    expires: Option<Instant>,
+    skipped: Vec<&'static str>,
+}
+
+impl Deadline {
+    pub fn new(start: Instant, timeout: Option<Duration>) -> Self {
+        Deadline { timeout, expires: timeout.map(|timeout| start + timeout), skipped: Vec::new() }
+    }
+
+    fn remaining(&self) -> Option<Duration> {
+        self.expires.map(|expires| expires.saturating_duration_since(Instant::now()))
+    }
+
+    /// Whether `stage` may still run. Once the deadline has passed, the stage
+    /// is recorded as skipped instead.
+    pub fn allows(&mut self, stage: &'static str) -> bool {
+        if self.remaining().is_some_and(|remaining| remaining.is_zero()) {
+            self.skipped.push(stage);
+            return false;
+        }
+        true
+    }
+
+    /// Run `work` on a worker thread and give up when the deadline passes
+    /// first. A thread cannot be stopped, so the worker finishes in the
+    /// background and its result is dropped; the caller moves on.
+    pub fn run<T, F>(&self, stage: &str, work: F) -> Result<T, Box<dyn Error>>
+    where
+        T: Send + 'static,
+        F: FnOnce() -> T + Send + 'static,
+    {
+        let Some(remaining) = self.remaining() else {
+            return Ok(work());
+        };
+        let (sender, receiver) = mpsc::channel();
+        thread::spawn(move || {
+            let _ = sender.send(work());
+        });
+        receiver.recv_timeout(remaining).map_err(|_| {
+            report_timeout(stage);
+            format!("{} did not finish within --timeout {}s", stage, self.timeout.unwrap_or_default().as_secs()).into()
+        })
+    }
+
+    /// Run `work` on a worker thread that sends results as it goes, and keep
+    /// those that arrive before the deadline. Returns them with whether the
+    /// deadline cut the work short; the worker's next send then fails, which
+    /// is its cue to stop.
+    pub fn collect<T, F>(&self, stage: &str, work: F) -> (Vec<T>, bool)
+    where
+        T: Send + 'static,
+        F: FnOnce(mpsc::Sender<T>) + Send + 'static,
+    {
+        let (sender, receiver) = mpsc::channel();
+        thread::spawn(move || work(sender));
+        let mut results = Vec::new();
+        loop {
+            let received = match self.remaining() {
+                Some(remaining) => receiver.recv_timeout(remaining),
+                None => receiver.recv().map_err(|_| RecvTimeoutError::Disconnected),
+            };
+            match received {
+                Ok(result) => results.push(result),
+                Err(RecvTimeoutError::Disconnected) => return (results, false),
+                Err(RecvTimeoutError::Timeout) => {
+                    report_timeout(stage);
+                    return (results, true);
+                }
+            }
+        }
+    }
+
+    /// `metadata.partial` when stages were skipped for the deadline.
+    pub fn to_metadata(&self) -> Option<Value> {
+        if self.skipped.is_empty() {
+            return None;
+        }
+        emit::emit!(
+            "{metric_agg} of {metric_name} is {metric_value}",
+            evt_kind: "metric",
+            metric_agg: "count",
+            metric_name: "txpool.budget.skipped_stages",
+            metric_value: self.skipped.len(),
+        );
+        Some(json!({
+            "reason": "timeout",
+            "timeout_seconds": self.timeout.map(|timeout| timeout.as_secs()),
+            "skipped": self.skipped,
+        }))
+    }
+}
+
+fn report_timeout(stage: &str) {
+    emit::emit!(
+        "{metric_agg} of {metric_name} is {metric_value}",
+        evt_kind: "metric",
+        metric_agg: "count",
+        metric_name: "txpool.budget.timeouts",
+        metric_value: 1,
+        stage,
+    );
+}
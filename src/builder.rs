@@ -0,0 +1,57 @@
+use serde_json::{Value, json};
+use std::collections::BTreeMap;
+
+use crate::fees::FeeCaps;
+use crate::quantity::{bytes_to_hex, to_json};
+use crate::rlp;
+use crate::snapshot::{self, find_field};
+
+/// Order the pending pool the way a simple block builder would consume it:
+/// sender chains sorted by the effective priority fee of their first
+/// transaction (highest first), each chain kept contiguous in nonce order.
+///
+/// Transactions that cannot be re-encoded are skipped, and a chain stops at
+/// the first one so nonces never have gaps.
+pub fn priority_ordering(snapshot: &Value, base_fee: Option<u128>) -> Value {
+    let mut chains: BTreeMap<&str, Vec<(u64, &Value)>> = BTreeMap::new();
+    for entry in snapshot::transactions(snapshot).filter(|entry| entry.section == "pending") {
+        if let Ok(nonce) = entry.nonce.parse::<u64>() {
+            chains.entry(entry.account).or_default().push((nonce, entry.tx));
+        }
+    }
+
+    let mut ordered: Vec<(i128, &str, Vec<Value>)> = Vec::new();
+    for (sender, mut txs) in chains {
+        txs.sort_by_key(|(nonce, _)| *nonce);
+
+        let mut chain = Vec::new();
+        let mut head_tip = None;
+        for (nonce, tx) in txs {
+            let (Some(raw), Some(fees)) = (rlp::encode_signed(tx), FeeCaps::of(tx)) else {
+                break;
+            };
+            let tip = fees.effective_priority_fee(base_fee);
+            head_tip.get_or_insert(tip);
+            chain.push(json!({
+                "from": sender,
+                "nonce": nonce,
+                "hash": find_field(tx, "hash"),
+                "effective_priority_fee": tip,
+                "raw": bytes_to_hex(&raw),
+            }));
+        }
+        if let Some(tip) = head_tip {
+            ordered.push((tip, sender, chain));
+        }
+    }
+
+    // Highest tip first; ties broken by sender for a deterministic order.
+    ordered.sort_by(|a, b| b.0.cmp(&a.0).then(a.1.cmp(b.1)));
+
+    let transactions: Vec<Value> = ordered.into_iter().flat_map(|(_, _, chain)| chain).collect();
+    json!({
+        "base_fee": base_fee.map(to_json),
+        "count": transactions.len(),
+        "transactions": transactions,
+    })
+}
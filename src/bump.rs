@@ -0,0 +1,133 @@
+use serde_json::{Map, Value, json};
+
+use crate::fees::FeeCaps;
+use crate::filter::{TxFilter, tx_to};
+use crate::quantity::{bytes_to_hex, to_json, to_u128};
+use crate::rlp;
+use crate::snapshot::{self, find_field, find_field_mut};
+
+/// Fee bump geth and reth require of a replacement, in percent.
+pub const DEFAULT_BUMP_PERCENT: u128 = 10;
+
+/// Fee bump the blob pools require of a blob transaction replacement.
+const BLOB_BUMP_PERCENT: u128 = 100;
+
+/// `value` raised by `percent`, rounded up.
+fn bumped(value: u128, percent: u128) -> u128 {
+    value.saturating_mul(100 + percent).div_ceil(100)
+}
+
+/// Replacement fees for `tx`: every cap raised by the bump, and the fee cap
+/// also high enough to pay `base_fee` plus the new tip.
+fn replacement_fees(tx: &Value, base_fee: u128, percent: u128) -> Map<String, Value> {
+    let ty = rlp::tx_type(tx);
+    let percent = if ty == 3 { percent.max(BLOB_BUMP_PERCENT) } else { percent };
+    let field = |key: &str| find_field(tx, key).and_then(to_u128);
+    let mut fees = Map::new();
+    if ty < 2 {
+        let gas_price = bumped(field("gas_price").unwrap_or(0), percent).max(base_fee);
+        fees.insert("gas_price".to_string(), to_json(gas_price));
+        return fees;
+    }
+    let tip = bumped(field("max_priority_fee_per_gas").unwrap_or(0), percent);
+    let max_fee = bumped(field("max_fee_per_gas").unwrap_or(0), percent).max(base_fee.saturating_add(tip));
+    fees.insert("max_priority_fee_per_gas".to_string(), to_json(tip));
+    fees.insert("max_fee_per_gas".to_string(), to_json(max_fee));
+    if ty == 3 {
+        let blob_fee = bumped(field("max_fee_per_blob_gas").unwrap_or(0), percent);
+        fees.insert("max_fee_per_blob_gas".to_string(), to_json(blob_fee));
+    }
+    fees
+}
+
+/// `cast send` command resending `tx` with `fees`; `None` for blob and
+/// set-code transactions, which need their sidecar or authorizations.
+fn cast_command(from: &str, nonce: &str, tx: &Value, fees: &Map<String, Value>) -> Option<String> {
+    let ty = rlp::tx_type(tx);
+    if ty > 2 {
+        return None;
+    }
+    let quantity = |value: Option<&Value>| value.and_then(to_u128).unwrap_or(0);
+    let input = find_field(tx, "input").and_then(Value::as_str).unwrap_or("0x");
+    let mut command = format!(
+        "cast send --from {} --nonce {} --gas-limit {} --value {}",
+        from,
+        nonce,
+        quantity(find_field(tx, "gas_limit").or_else(|| find_field(tx, "gas"))),
+        quantity(find_field(tx, "value")),
+    );
+    if ty < 2 {
+        command.push_str(&format!(" --legacy --gas-price {}", quantity(fees.get("gas_price"))));
+    } else {
+        command.push_str(&format!(
+            " --gas-price {} --priority-gas-price {}",
+            quantity(fees.get("max_fee_per_gas")),
+            quantity(fees.get("max_priority_fee_per_gas")),
+        ));
+    }
+    match tx_to(tx) {
+        Some(to) => command.push_str(&format!(" {} {}", to, input)),
+        None => command.push_str(&format!(" --create {}", input)),
+    }
+    Some(command)
+}
+
+/// Suggested replacements with the minimum compliant fee bump.
+///
+/// With a `filter` (e.g. `from=<sender>`) every matching transaction gets a
+/// suggestion; otherwise only stuck ones do: pending transactions whose fee
+/// cap is below `base_fee`. Caps are raised by `percent` (at least 100% for
+/// blob transactions, as the blob pools require) and the fee cap is raised
+/// further if needed to cover `base_fee` plus the new tip. Each suggestion
+/// carries the current and suggested fees, a `cast send` command and the
+/// unsigned replacement as raw hex for an offline signer. The suggestion
+/// count is emitted as `txpool.bump.suggestions`.
+pub fn suggestions(snapshot: &Value, base_fee: u128, filter: &TxFilter, percent: u128) -> Value {
+    let mut suggestions = Vec::new();
+    for entry in snapshot::transactions(snapshot) {
+        let Some(caps) = FeeCaps::of(entry.tx) else {
+            continue;
+        };
+        let stuck = entry.section == "pending" && caps.max_fee < base_fee;
+        let selected = if filter.is_empty() { stuck } else { filter.matches(&entry) };
+        if !selected {
+            continue;
+        }
+
+        let fees = replacement_fees(entry.tx, base_fee, percent);
+        let mut replacement = entry.tx.clone();
+        if let Some(fields) = find_field_mut(&mut replacement, "tx").and_then(Value::as_object_mut) {
+            fields.extend(fees.clone());
+        }
+        let current: Map<String, Value> = fees
+            .keys()
+            .map(|key| (key.clone(), find_field(entry.tx, key).cloned().unwrap_or(Value::Null)))
+            .collect();
+        suggestions.push(json!({
+            "from": entry.account.to_ascii_lowercase(),
+            "nonce": entry.nonce,
+            "hash": find_field(entry.tx, "hash"),
+            "section": entry.section,
+            "stuck": stuck,
+            "current": current,
+            "suggested": fees,
+            "cast": cast_command(&entry.account.to_ascii_lowercase(), entry.nonce, entry.tx, &fees),
+            "unsigned_raw": rlp::encode_unsigned(&replacement).map(|raw| bytes_to_hex(&raw)),
+        }));
+    }
+
+    let count = suggestions.len();
+    emit::emit!(
+        "{metric_agg} of {metric_name} is {metric_value}",
+        evt_kind: "metric",
+        metric_agg: "last",
+        metric_name: "txpool.bump.suggestions",
+        metric_value: count,
+    );
+
+    json!({
+        "base_fee": base_fee.to_string(),
+        "bump_percent": percent,
+        "suggestions": suggestions,
+    })
+}
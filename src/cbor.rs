@@ -0,0 +1,222 @@
+use serde_json::{Number, Value};
+
+use crate::quantity::to_be_bytes;
+
+const UNSIGNED: u8 = 0;
+const NEGATIVE: u8 = 1;
+const BYTES: u8 = 2;
+const TEXT: u8 = 3;
+const ARRAY: u8 = 4;
+const MAP: u8 = 5;
+const TAG: u8 = 6;
+const SIMPLE: u8 = 7;
+
+/// Tag 2: unsigned bignum (RFC 8949 §3.4.3).
+const TAG_BIGNUM: u64 = 2;
+
+/// Encode the snapshot as CBOR (RFC 8949).
+///
+/// Integers beyond 64 bits (e.g. signature `r`/`s`) become tag 2 bignums. In
+/// deterministic mode the output follows the core deterministic encoding
+/// rules (§4.2.1): map keys sorted by their encoded bytes and floats in their
+/// shortest exact form, so equal snapshots always hash the same.
+pub fn encode(value: &Value, deterministic: bool) -> Vec<u8> {
+    let mut out = Vec::new();
+    write_value(&mut out, value, deterministic);
+    out
+}
+
+fn write_value(out: &mut Vec<u8>, value: &Value, deterministic: bool) {
+    match value {
+        Value::Null => out.push(SIMPLE << 5 | 22),
+        Value::Bool(false) => out.push(SIMPLE << 5 | 20),
+        Value::Bool(true) => out.push(SIMPLE << 5 | 21),
+        Value::Number(n) => write_number(out, n, deterministic),
+        Value::String(s) => {
+            header(out, TEXT, s.len() as u64);
+            out.extend_from_slice(s.as_bytes());
+        }
+        Value::Array(items) => {
+            header(out, ARRAY, items.len() as u64);
+            for item in items {
+                write_value(out, item, deterministic);
+            }
+        }
+        Value::Object(map) => {
+            let mut entries: Vec<(Vec<u8>, &Value)> = map
+                .iter()
+                .map(|(key, value)| {
+                    let mut encoded = Vec::new();
+                    header(&mut encoded, TEXT, key.len() as u64);
+                    encoded.extend_from_slice(key.as_bytes());
+                    (encoded, value)
+                })
+                .collect();
+            if deterministic {
+                entries.sort_by(|a, b| a.0.cmp(&b.0));
+            }
+            header(out, MAP, entries.len() as u64);
+            for (key, value) in entries {
+                out.extend(key);
+                write_value(out, value, deterministic);
+            }
+        }
+    }
+}
+
+fn write_number(out: &mut Vec<u8>, n: &Number, deterministic: bool) {
+    if let Some(v) = n.as_u64() {
+        header(out, UNSIGNED, v);
+    } else if let Some(v) = n.as_i64() {
+        header(out, NEGATIVE, !(v as u64));
+    } else if let Some(bytes) = to_be_bytes(&Value::Number(n.clone())) {
+        header(out, TAG, TAG_BIGNUM);
+        header(out, BYTES, bytes.len() as u64);
+        out.extend(bytes);
+    } else {
+        write_float(out, n.as_f64().unwrap_or(f64::NAN), deterministic);
+    }
+}
+
+fn write_float(out: &mut Vec<u8>, v: f64, deterministic: bool) {
+    if deterministic {
+        if let Some(half) = f16_bits(v) {
+            out.push(SIMPLE << 5 | 25);
+            out.extend(half.to_be_bytes());
+            return;
+        }
+        if f64::from(v as f32) == v || v.is_nan() {
+            out.push(SIMPLE << 5 | 26);
+            out.extend((v as f32).to_be_bytes());
+            return;
+        }
+    }
+    out.push(SIMPLE << 5 | 27);
+    out.extend(v.to_be_bytes());
+}
+
+/// Half-precision bits of `v` if the conversion is exact (NaN maps to the
+/// canonical `0x7e00`).
+fn f16_bits(v: f64) -> Option<u16> {
+    if v.is_nan() {
+        return Some(0x7e00);
+    }
+    let sign = if v.is_sign_negative() { 0x8000u16 } else { 0 };
+    let abs = v.abs();
+    if abs == 0.0 {
+        return Some(sign);
+    }
+    if abs.is_infinite() {
+        return Some(sign | 0x7c00);
+    }
+    // Smallest subnormal is 2^-24; the largest finite value is 65504.
+    let scaled = abs * f64::from(1u32 << 24);
+    if abs < 6.103_515_625e-5 {
+        return (scaled.fract() == 0.0).then_some(sign | scaled as u16);
+    }
+    let exponent = abs.log2().floor() as i32;
+    if exponent > 15 {
+        return None;
+    }
+    let mantissa = abs / 2f64.powi(exponent) - 1.0;
+    let bits = mantissa * 1024.0;
+    if bits.fract() != 0.0 {
+        return None;
+    }
+    Some(sign | (((exponent + 15) as u16) << 10) | bits as u16)
+}
+
+/// Initial byte(s) of a data item with the shortest argument encoding.
+fn header(out: &mut Vec<u8>, major: u8, argument: u64) {
+    let major = major << 5;
+    match argument {
+        0..=23 => out.push(major | argument as u8),
+        24..=0xff => out.extend([major | 24, argument as u8]),
+        0x100..=0xffff => {
+            out.push(major | 25);
+            out.extend((argument as u16).to_be_bytes());
+        }
+        0x1_0000..=0xffff_ffff => {
+            out.push(major | 26);
+            out.extend((argument as u32).to_be_bytes());
+        }
+        _ => {
+            out.push(major | 27);
+            out.extend(argument.to_be_bytes());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn hex(value: &Value, deterministic: bool) -> String {
+        encode(value, deterministic).iter().map(|b| format!("{:02x}", b)).collect()
+    }
+
+    fn number(text: &str) -> Value {
+        serde_json::from_str(text).unwrap()
+    }
+
+    /// Vectors from RFC 8949 Appendix A.
+    #[test]
+    fn integers_use_the_shortest_header() {
+        for (value, expected) in [
+            ("0", "00"),
+            ("23", "17"),
+            ("24", "1818"),
+            ("100", "1864"),
+            ("1000", "1903e8"),
+            ("1000000", "1a000f4240"),
+            ("1000000000000", "1b000000e8d4a51000"),
+            ("18446744073709551615", "1bffffffffffffffff"),
+            ("18446744073709551616", "c249010000000000000000"),
+            ("-1", "20"),
+            ("-1000", "3903e7"),
+        ] {
+            assert_eq!(hex(&number(value), true), expected, "{}", value);
+        }
+        assert_eq!(hex(&json!("a".repeat(24)), true), format!("7818{}", "61".repeat(24)));
+        assert_eq!(hex(&json!([1, [2, 3]]), true), "8201820203");
+    }
+
+    #[test]
+    fn floats_use_the_shortest_exact_width() {
+        for (value, expected) in [
+            ("0.0", "f90000"),
+            ("-0.0", "f98000"),
+            ("1.5", "f93e00"),
+            ("-4.0", "f9c400"),
+            ("65504.0", "f97bff"),
+            ("0.00006103515625", "f90400"),
+            ("5.960464477539063e-8", "f90001"),
+            ("100000.0", "fa47c35000"),
+            ("3.4028234663852886e+38", "fa7f7fffff"),
+            ("1.1", "fb3ff199999999999a"),
+            ("1.0e+300", "fb7e37e43c8800759c"),
+        ] {
+            assert_eq!(hex(&number(value), true), expected, "{}", value);
+        }
+        // Outside deterministic mode floats keep their full width.
+        assert_eq!(hex(&number("1.5"), false), "fb3ff8000000000000");
+    }
+
+    #[test]
+    fn map_keys_sort_by_encoded_bytes() {
+        // Shorter keys sort first because their length is in the header, so
+        // "b" comes before "aa" even though it does not as a string.
+        let map = json!({ "aa": 3, "b": 2, "a": 1 });
+        assert_eq!(hex(&map, true), "a361610161620262616103");
+    }
+
+    #[test]
+    fn u256_signature_values_are_bignums() {
+        let r = number("66622713665624427733710315200720396955896638749566533714623508373930515555288");
+        assert_eq!(
+            hex(&r, true),
+            "c25820934b1ea10a4b3c1757e2b0c017d0b6143ce3c9a7e6a4a49860d7a6ab210ee3d8"
+        );
+    }
+}
@@ -0,0 +1,77 @@
+use serde_json::{Map, Value, json};
+use std::collections::BTreeMap;
+use std::error::Error;
+
+use crate::quantity::parse_u128;
+use crate::rpc::RpcClient;
+use crate::snapshot::{self, find_field};
+
+/// Chain id of a transaction, if it carries one (pre-EIP-155 legacy
+/// transactions and inspect summaries do not). Unknown envelopes carry it
+/// as a raw RPC `chainId` quantity.
+pub fn tx_chain_id(tx: &Value) -> Option<u64> {
+    find_field(tx, "chain_id").and_then(Value::as_u64).or_else(|| {
+        find_field(tx, "chainId")
+            .and_then(Value::as_str)
+            .and_then(parse_u128)
+            .and_then(|id| u64::try_from(id).ok())
+    })
+}
+
+/// Record the majority chain id and per-chain counts in the snapshot
+/// metadata, warn when the dump mixes chains, and optionally drop every
+/// transaction signed for a different chain than `filter`.
+pub fn tag_and_filter(snapshot: &mut Value, filter: Option<u64>) {
+    let mut counts: BTreeMap<u64, usize> = BTreeMap::new();
+    for entry in snapshot::transactions(snapshot) {
+        if let Some(chain_id) = tx_chain_id(entry.tx) {
+            *counts.entry(chain_id).or_insert(0) += 1;
+        }
+    }
+
+    // Ties go to the lowest chain id so the result is deterministic.
+    let majority = counts
+        .iter()
+        .max_by(|a, b| a.1.cmp(b.1).then(b.0.cmp(a.0)))
+        .map(|(chain_id, _)| *chain_id);
+
+    if counts.len() > 1 {
+        let chain_ids: Vec<String> = counts.keys().map(u64::to_string).collect();
+        let chain_ids = chain_ids.join(", ");
+        emit::warn!("Dump mixes transactions from chains {chain_ids}", chain_ids);
+        eprintln!("Warning: dump mixes transactions from chains {}", chain_ids);
+    }
+
+    if let Some(wanted) = filter {
+        // Transactions without a chain id are replayable on any chain, so they are kept.
+        let removed = snapshot::retain_transactions(snapshot, |_, _, tx| {
+            tx_chain_id(tx).is_none_or(|chain_id| chain_id == wanted)
+        });
+        emit::emit!(
+            "{metric_agg} of {metric_name} is {metric_value}",
+            evt_kind: "metric",
+            metric_agg: "count",
+            metric_name: "txpool.chain_id.filtered",
+            metric_value: removed,
+            chain_id: wanted,
+        );
+    }
+
+    let counts: Map<String, Value> = counts
+        .into_iter()
+        .map(|(chain_id, count)| (chain_id.to_string(), json!(count)))
+        .collect();
+    let metadata = snapshot::metadata_mut(snapshot);
+    metadata.insert("chain_id".to_string(), json!(majority));
+    metadata.insert("chain_id_counts".to_string(), Value::Object(counts));
+}
+
+/// Number of the node's latest block from `eth_blockNumber`.
+pub fn fetch_block_number(client: &RpcClient) -> Result<u64, Box<dyn Error>> {
+    client
+        .call("eth_blockNumber", json!([]))?
+        .as_str()
+        .and_then(parse_u128)
+        .and_then(|number| u64::try_from(number).ok())
+        .ok_or_else(|| "eth_blockNumber returned no block number".into())
+}
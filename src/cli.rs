@@ -0,0 +1,642 @@
+use std::error::Error;
+use std::time::Duration;
+
+use crate::activity::BlockCache;
+use crate::alerts::AlertRules;
+use crate::blob::BlobLimits;
+use crate::encryption::Encryption;
+use crate::entities::Labels;
+use crate::eviction::PoolLimits;
+use crate::export::ExportKind;
+use crate::filter::TxFilter;
+use crate::input_format::InputFormat;
+use crate::l1_fee::L1FeeParams;
+use crate::manifest::SigningKey;
+use crate::output::OutputFormat;
+use crate::quantity::parse_u128;
+use crate::rename::RenameMap;
+use crate::rpc::RpcPolicy;
+use crate::shape::Shape;
+use crate::sinks::Sink;
+use crate::synth::{FeeDistribution, MAX_TIP_GWEI, SynthFormat, SynthSpec};
+use crate::truncate::Limits;
+use crate::unknown_fields::UnknownFields;
+use crate::value_band::ValueBands;
+
+/// What the binary was asked to do.
+pub enum Command {
+    /// Read a debug dump from stdin and write the converted JSON snapshot.
+    Convert(Box<ConvertOptions>),
+    /// Generate a synthetic pool dump, parse it and report throughput.
+    Bench { txs: usize, iterations: usize },
+    /// Explore a converted snapshot interactively.
+    Repl { path: String },
+    /// Print the JSON Schema of the snapshot format, or the BigQuery table schema.
+    Schema { bigquery: bool },
+    /// Replay a converted snapshot on an anvil fork.
+    Replay(ReplayOptions),
+    /// Check the files and signature of a snapshot manifest.
+    Verify { manifest: String, signer: Option<String> },
+    /// Compare snapshots of the same pool from different clients.
+    Compare { paths: Vec<String>, output: Option<String> },
+    /// Write a deterministic synthetic pool dump.
+    Generate { spec: SynthSpec, format: SynthFormat, output: Option<String> },
+}
+
+/// Flags of the `replay` subcommand.
+#[derive(Default)]
+pub struct ReplayOptions {
+    /// Converted snapshot to replay.
+    pub path: String,
+    /// Already running anvil node to replay against.
+    pub rpc_url: Option<String>,
+    /// Node that a spawned anvil forks from.
+    pub fork_url: Option<String>,
+    /// Spawn an anvil fork of `fork_url` for the replay.
+    pub anvil: bool,
+    /// Block to fork at; defaults to the snapshot's `metadata.block_number`.
+    pub fork_block: Option<u64>,
+    /// Transactions to replay; all when empty.
+    pub select: TxFilter,
+}
+
+/// Flags accepted by the default convert mode.
+#[derive(Clone, Default)]
+pub struct ConvertOptions {
+    /// Cross-check the parsed snapshot against an independent scan of the input.
+    pub validate: bool,
+    /// Validate the produced document against the snapshot JSON Schema before writing.
+    pub check_schema: bool,
+    /// Keep only transactions signed for this chain.
+    pub chain_id: Option<u64>,
+    /// JSON-RPC endpoint of the node the dump was taken from.
+    pub rpc_url: Option<String>,
+    /// Every `--rpc-url` given; with more than one, the pools of all nodes are
+    /// read and merged instead of reading a dump.
+    pub rpc_urls: Vec<String>,
+    /// Repeat the multi-node read at this interval until interrupted.
+    pub poll_interval: Option<Duration>,
+    /// Rate limit, retries and fallback endpoints of the RPC clients.
+    pub rpc_policy: RpcPolicy,
+    /// Reth transaction pool backup file to read instead of a stdin dump.
+    pub reth_backup: Option<String>,
+    /// IPC socket of a local node; the pool is read with `txpool_content` instead of stdin.
+    pub ipc: Option<String>,
+    /// Annotate transactions with OP-stack L1 data fee estimates.
+    pub l1_fee: bool,
+    /// L1 fee parameters given on the command line; the rest come from `rpc_url`.
+    pub l1_fee_params: L1FeeParams,
+    /// Blob base fee used to count clearing blobs; fetched from `rpc_url` if unset.
+    pub blob_base_fee: Option<u128>,
+    /// Blob pool policy that per-sender blob counts are checked against.
+    pub blob_limits: BlobLimits,
+    /// Execution base fee for effective tip calculations; fetched from `rpc_url` if unset.
+    pub base_fee: Option<u128>,
+    /// Block gas limit that per-account pending gas is compared against; fetched from `rpc_url` if unset.
+    pub block_gas_limit: Option<u64>,
+    /// Thresholds of the dust/retail/whale `value_band` labels.
+    pub value_bands: ValueBands,
+    /// Encoding of the snapshot file.
+    pub output_format: OutputFormat,
+    /// Print the completion summary without ANSI colors.
+    pub no_color: bool,
+    /// Keep only these fields of each transaction in the snapshot file.
+    pub fields: Vec<String>,
+    /// RPC shape (inspect summaries or content transactions) of the snapshot file.
+    pub shape: Option<Shape>,
+    /// Field renames applied to transactions in the snapshot file.
+    pub rename: Option<RenameMap>,
+    /// Canonicalize hex strings (case, address/hash width, quantity leading zeros).
+    pub normalize_hex: bool,
+    /// Write transaction quantities as JSON-RPC hex strings instead of numbers.
+    pub hex_quantities: bool,
+    /// Parser for stdin, or `Auto` to detect it.
+    pub input_format: InputFormat,
+    /// Handling of dump fields the parser does not know.
+    pub unknown_fields: UnknownFields,
+    /// Account and per-account transaction caps applied to dumps before parsing.
+    pub limits: Limits,
+    /// Extra files to write next to the snapshot.
+    pub exports: Vec<ExportKind>,
+    /// Check senders' on-chain nonce and balance and flag transactions that can never execute.
+    pub validate_state: bool,
+    /// Cross-reference pool senders with their transactions in this many recent blocks.
+    pub activity_blocks: Option<u64>,
+    /// Recent blocks already read for `activity_blocks`, shared by the snapshots of a run.
+    pub activity_cache: BlockCache,
+    /// Previous snapshot to compare against for inclusion tracking.
+    pub track_inclusion: Option<String>,
+    /// Previous snapshot to compare sections against for queued/pending movement.
+    pub track_movement: Option<String>,
+    /// Destinations of each snapshot; the parser defaults it to the file.
+    pub sinks: Vec<Sink>,
+    /// Convert every dump file that appears in this directory instead of reading stdin.
+    pub watch_dir: Option<String>,
+    /// Notable-transaction alerts raised on each snapshot's new entries.
+    pub alerts: AlertRules,
+    /// Fee bump (percent) of the `bump` export's replacements.
+    pub bump_percent: Option<u128>,
+    /// Transactions the `raw-batch` and `bump` exports include.
+    pub select: TxFilter,
+    /// Pool size limits the `eviction` export is computed against.
+    pub pool_limits: PoolLimits,
+    /// Address labels for the `entities` export.
+    pub labels: Option<Labels>,
+    /// Transaction hashes whose dependencies the `deps` export reports.
+    pub targets: Vec<String>,
+    /// Write a manifest with the SHA-256 of every output file.
+    pub manifest: bool,
+    /// Key that signs the manifest.
+    pub sign_key: Option<SigningKey>,
+    /// Recipients the output files are encrypted to.
+    pub encryption: Encryption,
+    /// Time budget of one snapshot, from reading the pool to writing it.
+    pub timeout: Option<Duration>,
+    /// Read at most this many bytes of a dump.
+    pub max_input_bytes: Option<usize>,
+}
+
+pub fn parse_args<I>(args: I) -> Result<Command, Box<dyn Error>>
+where
+    I: IntoIterator<Item = String>,
+{
+    let mut args = args.into_iter().peekable();
+
+    match args.peek().map(String::as_str) {
+        Some("bench") => {
+            args.next();
+            parse_bench(args)
+        }
+        Some("repl") => {
+            args.next();
+            let path = next_value(&mut args, "repl")?;
+            if let Some(other) = args.next() {
+                return Err(format!("Unknown repl argument: {}", other).into());
+            }
+            Ok(Command::Repl { path })
+        }
+        Some("replay") => {
+            args.next();
+            parse_replay(args)
+        }
+        Some("verify") => {
+            args.next();
+            parse_verify(args)
+        }
+        Some("compare") => {
+            args.next();
+            parse_compare(args)
+        }
+        Some("generate") => {
+            args.next();
+            parse_generate(args)
+        }
+        Some("schema") => {
+            args.next();
+            let bigquery = match args.next().as_deref() {
+                None => false,
+                Some("bigquery") => true,
+                Some(other) => return Err(format!("Unknown schema argument: {}", other).into()),
+            };
+            if let Some(other) = args.next() {
+                return Err(format!("Unknown schema argument: {}", other).into());
+            }
+            Ok(Command::Schema { bigquery })
+        }
+        _ => parse_convert(args),
+    }
+}
+
+fn parse_convert<I>(mut args: I) -> Result<Command, Box<dyn Error>>
+where
+    I: Iterator<Item = String>,
+{
+    let mut options = ConvertOptions::default();
+    let mut bigquery_tables = Vec::new();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--validate" => options.validate = true,
+            "--check-schema" => options.check_schema = true,
+            "--chain-id" => {
+                options.chain_id = Some(next_value(&mut args, "--chain-id")?.parse()?);
+            }
+            "--rpc-url" => {
+                let url = next_value(&mut args, "--rpc-url")?;
+                options.rpc_url.get_or_insert_with(|| url.clone());
+                options.rpc_urls.push(url);
+            }
+            "--rpc-fallback" => options.rpc_policy.fallbacks.push(next_value(&mut args, "--rpc-fallback")?),
+            "--rpc-retries" => options.rpc_policy.retries = next_value(&mut args, "--rpc-retries")?.parse()?,
+            "--rpc-rate-limit" => {
+                let rate = next_value(&mut args, "--rpc-rate-limit")?;
+                let per_second: f64 = rate.parse()?;
+                if !(per_second > 0.0 && per_second.is_finite()) {
+                    return Err("--rpc-rate-limit must be a positive number of requests per second".into());
+                }
+                let interval = Duration::try_from_secs_f64(1.0 / per_second)
+                    .map_err(|_| format!("--rpc-rate-limit {} is too low to wait between requests", rate))?;
+                options.rpc_policy.min_interval = Some(interval);
+            }
+            "--dust-below" => options.value_bands.dust_below = next_u128(&mut args, "--dust-below")?,
+            "--whale-from" => options.value_bands.whale_from = next_u128(&mut args, "--whale-from")?,
+            "--alert-min-value" => options.alerts.min_value = Some(next_u128(&mut args, "--alert-min-value")?),
+            "--alert-to" => {
+                let addresses = next_value(&mut args, "--alert-to")?;
+                options.alerts.recipients.extend(
+                    addresses
+                        .split(',')
+                        .map(str::trim)
+                        .filter(|a| !a.is_empty())
+                        .map(str::to_ascii_lowercase),
+                );
+            }
+            "--alert-creations" => options.alerts.contract_creations = true,
+            "--alert-webhook" => options.alerts.webhook = Some(next_value(&mut args, "--alert-webhook")?),
+            "--poll" => {
+                let seconds: u64 = next_value(&mut args, "--poll")?.parse()?;
+                if seconds == 0 {
+                    return Err("--poll must be at least 1 second".into());
+                }
+                options.poll_interval = Some(Duration::from_secs(seconds));
+            }
+            "--timeout" => {
+                let seconds: u64 = next_value(&mut args, "--timeout")?.parse()?;
+                if seconds == 0 {
+                    return Err("--timeout must be at least 1 second".into());
+                }
+                options.timeout = Some(Duration::from_secs(seconds));
+            }
+            "--max-input-bytes" => options.max_input_bytes = Some(next_limit(&mut args, "--max-input-bytes")?),
+            "--max-accounts" => options.limits.max_accounts = Some(next_limit(&mut args, "--max-accounts")?),
+            "--max-txs-per-account" => {
+                options.limits.max_txs_per_account = Some(next_limit(&mut args, "--max-txs-per-account")?);
+            }
+            "--max-slots" => options.pool_limits.max_slots = Some(next_limit(&mut args, "--max-slots")?),
+            "--max-per-account" => {
+                options.pool_limits.max_per_account = Some(next_limit(&mut args, "--max-per-account")?);
+            }
+            "--ipc" => options.ipc = Some(next_value(&mut args, "--ipc")?),
+            "--reth-backup" => options.reth_backup = Some(next_value(&mut args, "--reth-backup")?),
+            "--l1-fee" => options.l1_fee = true,
+            "--l1-base-fee" => {
+                options.l1_fee_params.l1_base_fee = Some(next_u128(&mut args, "--l1-base-fee")?);
+            }
+            "--l1-blob-base-fee" => {
+                options.l1_fee_params.l1_blob_base_fee = Some(next_u128(&mut args, "--l1-blob-base-fee")?);
+            }
+            "--l1-base-fee-scalar" => {
+                options.l1_fee_params.base_fee_scalar = Some(next_u128(&mut args, "--l1-base-fee-scalar")?);
+            }
+            "--base-fee" => options.base_fee = Some(next_u128(&mut args, "--base-fee")?),
+            "--bump-percent" => options.bump_percent = Some(next_u128(&mut args, "--bump-percent")?),
+            "--output-format" => {
+                options.output_format = OutputFormat::parse(&next_value(&mut args, "--output-format")?)?;
+            }
+            "--no-color" => options.no_color = true,
+            "--export" => {
+                let kind = ExportKind::parse(&next_value(&mut args, "--export")?)?;
+                if !options.exports.contains(&kind) {
+                    options.exports.push(kind);
+                }
+            }
+            "--validate-state" => options.validate_state = true,
+            "--activity-blocks" => {
+                let blocks: u64 = next_value(&mut args, "--activity-blocks")?.parse()?;
+                if blocks == 0 {
+                    return Err("--activity-blocks must be at least 1".into());
+                }
+                options.activity_blocks = Some(blocks);
+            }
+            "--track-inclusion" => {
+                options.track_inclusion = Some(next_value(&mut args, "--track-inclusion")?);
+            }
+            "--track-movement" => {
+                options.track_movement = Some(next_value(&mut args, "--track-movement")?);
+            }
+            "--sink" => options.sinks.push(Sink::parse(&next_value(&mut args, "--sink")?)?),
+            "--bigquery-table" => {
+                bigquery_tables.push(Sink::BigQuery(next_value(&mut args, "--bigquery-table")?));
+            }
+            "--watch-dir" => options.watch_dir = Some(next_value(&mut args, "--watch-dir")?),
+            "--target" => {
+                let hashes = next_value(&mut args, "--target")?;
+                options
+                    .targets
+                    .extend(hashes.split(',').map(str::trim).filter(|h| !h.is_empty()).map(String::from));
+            }
+            "--fields" => {
+                let fields = next_value(&mut args, "--fields")?;
+                options
+                    .fields
+                    .extend(fields.split(',').map(str::trim).filter(|f| !f.is_empty()).map(String::from));
+            }
+            "--normalize-hex" => options.normalize_hex = true,
+            "--hex-quantities" => options.hex_quantities = true,
+            "--format" => options.input_format = InputFormat::parse(&next_value(&mut args, "--format")?)?,
+            "--unknown-fields" => {
+                options.unknown_fields = UnknownFields::parse(&next_value(&mut args, "--unknown-fields")?)?;
+            }
+            "--select" => {
+                let conditions = next_value(&mut args, "--select")?;
+                options.select.extend(TxFilter::parse(&conditions.split(',').collect::<Vec<_>>())?);
+            }
+            "--encrypt" => options.encryption.add(&next_value(&mut args, "--encrypt")?)?,
+            "--manifest" => options.manifest = true,
+            "--sign-key" => {
+                options.sign_key = Some(SigningKey::load(&next_value(&mut args, "--sign-key")?)?);
+                options.manifest = true;
+            }
+            "--labels" => options.labels = Some(Labels::load(&next_value(&mut args, "--labels")?)?),
+            "--shape" => options.shape = Some(Shape::parse(&next_value(&mut args, "--shape")?)?),
+            "--rename-config" => {
+                options.rename = Some(RenameMap::load(&next_value(&mut args, "--rename-config")?)?);
+            }
+            "--block-gas-limit" => {
+                let limit: u64 = next_value(&mut args, "--block-gas-limit")?.parse()?;
+                if limit == 0 {
+                    return Err("--block-gas-limit must be greater than zero".into());
+                }
+                options.block_gas_limit = Some(limit);
+            }
+            "--max-blob-txs-per-sender" => {
+                options.blob_limits.max_txs_per_sender = next_value(&mut args, "--max-blob-txs-per-sender")?.parse()?;
+            }
+            "--max-blobs-per-sender" => {
+                options.blob_limits.max_blobs_per_sender = Some(next_value(&mut args, "--max-blobs-per-sender")?.parse()?);
+            }
+            "--blob-base-fee" => {
+                options.blob_base_fee = Some(next_u128(&mut args, "--blob-base-fee")?);
+            }
+            "--l1-blob-base-fee-scalar" => {
+                options.l1_fee_params.blob_base_fee_scalar =
+                    Some(next_u128(&mut args, "--l1-blob-base-fee-scalar")?);
+            }
+            other => return Err(format!("Unknown argument: {}", other).into()),
+        }
+    }
+    // `--bigquery-table` adds a sink without replacing the default file.
+    if options.sinks.is_empty() {
+        options.sinks.push(Sink::File);
+    }
+    options.sinks.append(&mut bigquery_tables);
+    if options.exports.contains(&ExportKind::Deps) && options.targets.is_empty() {
+        return Err("--export deps requires at least one --target hash".into());
+    }
+    if options.exports.contains(&ExportKind::Bump)
+        && options.base_fee.is_none()
+        && options.rpc_url.is_none()
+        && options.ipc.is_none()
+    {
+        return Err("--export bump requires --base-fee, --rpc-url or --ipc".into());
+    }
+    if options.exports.contains(&ExportKind::Entities) && options.labels.is_none() {
+        return Err("--export entities requires --labels".into());
+    }
+    if options.exports.contains(&ExportKind::Latency) && options.track_inclusion.is_none() {
+        return Err("--export latency requires --track-inclusion".into());
+    }
+    if (!options.fields.is_empty() || options.rename.is_some() || options.shape.is_some())
+        && !matches!(options.output_format, OutputFormat::Json | OutputFormat::Cbor { .. })
+    {
+        return Err("--fields, --rename-config and --shape only apply to json and cbor output".into());
+    }
+    if options.encryption.is_enabled() && options.output_format.is_printed() {
+        return Err("--encrypt writes files; table and inspect-text output go to the terminal".into());
+    }
+    if options.shape.is_some() && !options.fields.is_empty() {
+        return Err("--shape and --fields are mutually exclusive".into());
+    }
+    if options.check_schema
+        && (!options.fields.is_empty()
+            || options.rename.is_some()
+            || options.shape.is_some()
+            || options.hex_quantities
+            || !matches!(options.output_format, OutputFormat::Json | OutputFormat::Cbor { .. }))
+    {
+        return Err("--check-schema validates the snapshot as written; it cannot be combined with --fields, --rename-config, --shape, --hex-quantities or non-json/cbor output".into());
+    }
+    if options.ipc.is_some() {
+        if options.rpc_url.is_some() {
+            return Err("--ipc and --rpc-url are mutually exclusive".into());
+        }
+        if options.validate || options.watch_dir.is_some() || options.limits.is_set() {
+            return Err("--ipc reads the pool over RPC; --validate, --watch-dir and parse limits need dump files".into());
+        }
+    }
+    if options.reth_backup.is_some() {
+        if options.ipc.is_some() {
+            return Err("--reth-backup and --ipc are mutually exclusive".into());
+        }
+        if options.validate || options.watch_dir.is_some() || options.limits.is_set() {
+            return Err("--validate, --watch-dir and parse limits work on debug dumps, not --reth-backup".into());
+        }
+    }
+    if options.alerts.webhook.is_some() && !options.alerts.is_enabled() {
+        return Err("--alert-webhook needs at least one of --alert-min-value, --alert-to or --alert-creations".into());
+    }
+    if options.rpc_urls.len() > 1 {
+        if options.validate || options.watch_dir.is_some() || options.reth_backup.is_some() || options.limits.is_set() {
+            return Err("Several --rpc-url values read the pools over RPC; --validate, --watch-dir, --reth-backup and parse limits need dumps".into());
+        }
+    } else if options.poll_interval.is_some() {
+        return Err("--poll requires at least two --rpc-url values".into());
+    }
+    if !options.rpc_policy.fallbacks.is_empty() && options.rpc_url.is_none() && options.ipc.is_none() {
+        return Err("--rpc-fallback backs up --rpc-url or --ipc; give one of them".into());
+    }
+    if options.track_inclusion.is_some() && options.rpc_url.is_none() && options.ipc.is_none() {
+        return Err("--track-inclusion requires --rpc-url or --ipc to look up receipts".into());
+    }
+    if options.validate_state && options.rpc_url.is_none() && options.ipc.is_none() {
+        return Err("--validate-state requires --rpc-url or --ipc to read account state".into());
+    }
+    if options.value_bands.dust_below > options.value_bands.whale_from {
+        return Err("--dust-below must not be above --whale-from".into());
+    }
+    if options.activity_blocks.is_some() && options.rpc_url.is_none() && options.ipc.is_none() {
+        return Err("--activity-blocks requires --rpc-url or --ipc to read recent blocks".into());
+    }
+    Ok(Command::Convert(Box::new(options)))
+}
+
+fn parse_bench<I>(mut args: I) -> Result<Command, Box<dyn Error>>
+where
+    I: Iterator<Item = String>,
+{
+    let mut txs = 10_000;
+    let mut iterations = 3;
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--size" => txs = parse_size(&next_value(&mut args, "--size")?)?,
+            "--iterations" => {
+                iterations = next_value(&mut args, "--iterations")?.parse()?;
+                if iterations == 0 {
+                    return Err("--iterations must be at least 1".into());
+                }
+            }
+            other => return Err(format!("Unknown bench argument: {}", other).into()),
+        }
+    }
+    Ok(Command::Bench { txs, iterations })
+}
+
+fn parse_generate<I>(mut args: I) -> Result<Command, Box<dyn Error>>
+where
+    I: Iterator<Item = String>,
+{
+    let mut spec = SynthSpec::with_txs(10_000);
+    let mut format = SynthFormat::Content;
+    let mut output = None;
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--size" => spec.txs = parse_size(&next_value(&mut args, "--size")?)?,
+            "--txs-per-account" => {
+                spec.txs_per_account = parse_size(&next_value(&mut args, "--txs-per-account")?)?
+            }
+            "--seed" => spec.seed = next_value(&mut args, "--seed")?.parse()?,
+            "--format" => format = SynthFormat::parse(&next_value(&mut args, "--format")?)?,
+            "--types" => spec.set_type_mix(&next_value(&mut args, "--types")?)?,
+            "--tip-gwei" => {
+                let range = next_value(&mut args, "--tip-gwei")?;
+                spec.tip_gwei = range
+                    .split_once('-')
+                    .and_then(|(low, high)| Some((low.parse().ok()?, high.parse().ok()?)))
+                    .filter(|(low, high)| low <= high)
+                    .ok_or_else(|| format!("Invalid --tip-gwei range: {} (expected <low>-<high>)", range))?;
+                if spec.tip_gwei.1 > MAX_TIP_GWEI {
+                    return Err(format!("--tip-gwei high must be at most {} gwei", MAX_TIP_GWEI).into());
+                }
+            }
+            "--fees" => spec.fee_distribution = FeeDistribution::parse(&next_value(&mut args, "--fees")?)?,
+            "--gap-rate" => {
+                spec.gap_rate = next_value(&mut args, "--gap-rate")?.parse()?;
+                if !(0.0..=1.0).contains(&spec.gap_rate) {
+                    return Err("--gap-rate must be between 0 and 1".into());
+                }
+            }
+            "--output" => output = Some(next_value(&mut args, "--output")?),
+            other => return Err(format!("Unknown generate argument: {}", other).into()),
+        }
+    }
+    Ok(Command::Generate { spec, format, output })
+}
+
+fn parse_replay<I>(mut args: I) -> Result<Command, Box<dyn Error>>
+where
+    I: Iterator<Item = String>,
+{
+    let mut options = ReplayOptions {
+        path: next_value(&mut args, "replay")?,
+        ..ReplayOptions::default()
+    };
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--rpc-url" => options.rpc_url = Some(next_value(&mut args, "--rpc-url")?),
+            "--fork-url" => options.fork_url = Some(next_value(&mut args, "--fork-url")?),
+            "--anvil" => options.anvil = true,
+            "--fork-block" => options.fork_block = Some(next_value(&mut args, "--fork-block")?.parse()?),
+            "--select" => {
+                let conditions = next_value(&mut args, "--select")?;
+                options.select.extend(TxFilter::parse(&conditions.split(',').collect::<Vec<_>>())?);
+            }
+            other => return Err(format!("Unknown replay argument: {}", other).into()),
+        }
+    }
+    if options.anvil {
+        if options.fork_url.is_none() {
+            return Err("--anvil requires --fork-url".into());
+        }
+        if options.rpc_url.is_some() {
+            return Err("--anvil starts its own node; drop --rpc-url".into());
+        }
+    } else if options.fork_url.is_some() || options.fork_block.is_some() {
+        return Err("--fork-url and --fork-block only apply with --anvil".into());
+    }
+    Ok(Command::Replay(options))
+}
+
+fn parse_verify<I>(mut args: I) -> Result<Command, Box<dyn Error>>
+where
+    I: Iterator<Item = String>,
+{
+    let mut manifest = None;
+    let mut signer = None;
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--signer" => signer = Some(next_value(&mut args, "--signer")?),
+            other if !other.starts_with("--") && manifest.is_none() => manifest = Some(other.to_string()),
+            other => return Err(format!("Unknown verify argument: {}", other).into()),
+        }
+    }
+    let manifest = manifest.ok_or("verify requires a manifest path")?;
+    Ok(Command::Verify { manifest, signer })
+}
+
+fn parse_compare<I>(mut args: I) -> Result<Command, Box<dyn Error>>
+where
+    I: Iterator<Item = String>,
+{
+    let mut paths = Vec::new();
+    let mut output = None;
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--output" => output = Some(next_value(&mut args, "--output")?),
+            other if !other.starts_with("--") => paths.push(other.to_string()),
+            other => return Err(format!("Unknown compare argument: {}", other).into()),
+        }
+    }
+    if paths.len() < 2 {
+        return Err("compare requires at least two snapshots".into());
+    }
+    Ok(Command::Compare { paths, output })
+}
+
+fn next_value<I>(args: &mut I, flag: &str) -> Result<String, Box<dyn Error>>
+where
+    I: Iterator<Item = String>,
+{
+    args.next()
+        .ok_or_else(|| format!("{} requires a value", flag).into())
+}
+
+fn next_u128<I>(args: &mut I, flag: &str) -> Result<u128, Box<dyn Error>>
+where
+    I: Iterator<Item = String>,
+{
+    let value = next_value(args, flag)?;
+    parse_u128(&value).ok_or_else(|| format!("Invalid value for {}: {}", flag, value).into())
+}
+
+/// A parse limit: a positive count such as `500` or `10k`.
+fn next_limit<I>(args: &mut I, flag: &str) -> Result<usize, Box<dyn Error>>
+where
+    I: Iterator<Item = String>,
+{
+    match parse_size(&next_value(args, flag)?)? {
+        0 => Err(format!("{} must be at least 1", flag).into()),
+        limit => Ok(limit),
+    }
+}
+
+/// Parse a count such as `500`, `10k`, `1m` or `5g`.
+pub fn parse_size(size: &str) -> Result<usize, Box<dyn Error>> {
+    let lower = size.trim().to_ascii_lowercase();
+    let (digits, multiplier) = if let Some(n) = lower.strip_suffix('k') {
+        (n, 1_000)
+    } else if let Some(n) = lower.strip_suffix('m') {
+        (n, 1_000_000)
+    } else if let Some(n) = lower.strip_suffix('g') {
+        (n, 1_000_000_000)
+    } else {
+        (lower.as_str(), 1)
+    };
+
+    let count = digits
+        .replace('_', "")
+        .parse::<usize>()
+        .map_err(|_| format!("Invalid size: {}", size))?;
+    if count == 0 {
+        return Err("Size must be greater than zero".into());
+    }
+    count.checked_mul(multiplier).ok_or_else(|| format!("Size too large: {}", size).into())
+}
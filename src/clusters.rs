@@ -0,0 +1,128 @@
+use serde_json::{Value, json};
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::quantity::{bytes_to_hex, hex_to_bytes};
+use crate::snapshot::{self, find_field};
+
+/// Calldata whose simhashes differ in at most this many of 64 bits is
+/// considered the same strategy.
+const MAX_HAMMING_DISTANCE: u32 = 6;
+
+/// FNV-1a, 64-bit.
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+/// 64-bit simhash of the ABI arguments after the selector. Each feature is a
+/// 4-byte chunk together with its position, so calldata that only differs in
+/// a few argument bytes (amounts, deadlines) lands a few bits apart.
+fn simhash(args: &[u8]) -> u64 {
+    let mut weights = [0i32; 64];
+    for (position, word) in args.chunks(4).enumerate() {
+        let mut feature = (position as u32).to_be_bytes().to_vec();
+        feature.extend_from_slice(word);
+        let hash = fnv1a(&feature);
+        for (bit, weight) in weights.iter_mut().enumerate() {
+            *weight += if hash >> bit & 1 == 1 { 1 } else { -1 };
+        }
+    }
+    weights
+        .iter()
+        .enumerate()
+        .filter(|(_, weight)| **weight > 0)
+        .fold(0, |hash, (bit, _)| hash | 1 << bit)
+}
+
+struct Cluster<'a> {
+    simhash: u64,
+    hashes: Vec<&'a str>,
+    senders: BTreeSet<&'a str>,
+}
+
+/// Group transactions that call the same function selector with similar
+/// arguments, to surface bot clusters and copied strategies. Within each
+/// selector, a transaction joins the first cluster whose simhash is within
+/// `MAX_HAMMING_DISTANCE` bits of its own. Clusters of two or more are listed
+/// largest first with an `id` (`<selector>-<simhash>`), `size`, distinct
+/// `senders` and member hashes; the rest are counted as `singletons`. The
+/// cluster count and largest size are emitted as `txpool.calldata.clusters`
+/// and `txpool.calldata.largest_cluster`.
+pub fn calldata_clusters(snapshot: &Value) -> Value {
+    let mut by_selector: BTreeMap<String, Vec<Cluster>> = BTreeMap::new();
+    let mut with_calldata = 0usize;
+    for entry in snapshot::transactions(snapshot) {
+        let Some(input) = find_field(entry.tx, "input")
+            .and_then(Value::as_str)
+            .and_then(hex_to_bytes)
+            .filter(|input| input.len() >= 4)
+        else {
+            continue;
+        };
+        let Some(hash) = find_field(entry.tx, "hash").and_then(Value::as_str) else {
+            continue;
+        };
+        with_calldata += 1;
+        let fingerprint = simhash(&input[4..]);
+        let clusters = by_selector.entry(bytes_to_hex(&input[..4])).or_default();
+        let index = match clusters
+            .iter()
+            .position(|cluster| (cluster.simhash ^ fingerprint).count_ones() <= MAX_HAMMING_DISTANCE)
+        {
+            Some(index) => index,
+            None => {
+                clusters.push(Cluster {
+                    simhash: fingerprint,
+                    hashes: Vec::new(),
+                    senders: BTreeSet::new(),
+                });
+                clusters.len() - 1
+            }
+        };
+        clusters[index].hashes.push(hash);
+        clusters[index].senders.insert(entry.account);
+    }
+
+    let mut singletons = 0usize;
+    let mut report = Vec::new();
+    for (selector, clusters) in &by_selector {
+        for cluster in clusters {
+            if cluster.hashes.len() < 2 {
+                singletons += 1;
+                continue;
+            }
+            report.push(json!({
+                "id": format!("{}-{:016x}", selector, cluster.simhash),
+                "selector": selector,
+                "size": cluster.hashes.len(),
+                "senders": cluster.senders.len(),
+                "hashes": cluster.hashes,
+            }));
+        }
+    }
+    report.sort_by_key(|cluster| std::cmp::Reverse(cluster["size"].as_u64()));
+
+    let cluster_count = report.len();
+    let largest = report.first().and_then(|cluster| cluster["size"].as_u64()).unwrap_or(0);
+    emit::emit!(
+        "{metric_agg} of {metric_name} is {metric_value}",
+        evt_kind: "metric",
+        metric_agg: "last",
+        metric_name: "txpool.calldata.clusters",
+        metric_value: cluster_count,
+    );
+    emit::emit!(
+        "{metric_agg} of {metric_name} is {metric_value}",
+        evt_kind: "metric",
+        metric_agg: "last",
+        metric_name: "txpool.calldata.largest_cluster",
+        metric_value: largest,
+    );
+
+    json!({
+        "transactions": with_calldata,
+        "clusters": report,
+        "singletons": singletons,
+    })
+}
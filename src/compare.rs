@@ -0,0 +1,255 @@
+use serde_json::{Map, Value, json};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::error::Error;
+use std::fs;
+use std::path::Path;
+
+use crate::encryption::Encryption;
+use crate::fees::{self, FeeCaps};
+use crate::input_format::InputFormat;
+use crate::multinode;
+use crate::quantity::parse_u128;
+use crate::rlp;
+use crate::snapshot::{self, find_field};
+use crate::unknown_fields::UnknownFields;
+use crate::warnings;
+
+/// Capture times further apart than this make the comparison mostly about
+/// churn between the captures rather than between the clients.
+const MAX_SKEW_SECONDS: u64 = 30;
+
+/// Load a converted snapshot, or parse a dump in any `--format auto` input.
+fn load(path: &str) -> Result<Value, Box<dyn Error>> {
+    let text = fs::read_to_string(path)?;
+    if let Ok(value) = serde_json::from_str::<Value>(&text)
+        && value.get("metadata").is_some()
+    {
+        return Ok(value);
+    }
+    let debug_file = Encryption::default();
+    let (snapshot, parse_warnings) =
+        crate::parse_debug_format(&text, InputFormat::Auto, UnknownFields::default(), Some(&debug_file))
+            .map_err(|err| format!("Could not read {}: {}", path, err))?;
+    warnings::report(&parse_warnings);
+    Ok(snapshot)
+}
+
+/// Name a snapshot is reported under: the client recorded in
+/// `metadata.node`, or the file name for dumps and snapshots taken without
+/// RPC. A repeated name gets the file name appended, then the full path if
+/// that is still ambiguous, and finally its position among the inputs.
+fn labels(paths: &[String], views: &[Value]) -> Vec<String> {
+    let file_name = |path: &String| {
+        Path::new(path)
+            .file_name()
+            .map_or_else(|| path.clone(), |name| name.to_string_lossy().into_owned())
+    };
+    let clients: Vec<Option<&str>> = views
+        .iter()
+        .map(|view| view["metadata"]["node"]["client"].as_str())
+        .collect();
+    let mut labels: Vec<String> = paths
+        .iter()
+        .zip(&clients)
+        .map(|(path, client)| match client {
+            Some(client) if clients.iter().filter(|other| *other == &Some(*client)).count() == 1 => {
+                client.to_string()
+            }
+            Some(client) => format!("{} ({})", client, file_name(path)),
+            None => file_name(path),
+        })
+        .collect();
+    let repeated = |labels: &[String]| -> Vec<bool> {
+        labels
+            .iter()
+            .map(|label| labels.iter().filter(|other| *other == label).count() > 1)
+            .collect()
+    };
+    for (i, repeated) in repeated(&labels).into_iter().enumerate() {
+        if repeated {
+            labels[i] = match clients[i] {
+                Some(client) => format!("{} ({})", client, paths[i]),
+                None => paths[i].clone(),
+            };
+        }
+    }
+    for (i, repeated) in repeated(&labels).into_iter().enumerate() {
+        if repeated {
+            labels[i] = format!("{} #{}", labels[i], i + 1);
+        }
+    }
+    labels
+}
+
+fn tx_hash(tx: &Value) -> Option<String> {
+    find_field(tx, "hash").and_then(Value::as_str).map(str::to_ascii_lowercase)
+}
+
+/// Per-client counts of the report.
+#[derive(Default)]
+struct ClientCounts {
+    held: usize,
+    exclusive: usize,
+    missing: usize,
+    missing_by_type: BTreeMap<String, usize>,
+    missing_by_fee_bucket: BTreeMap<&'static str, usize>,
+}
+
+/// Compare snapshots of the same pool taken from different clients at about
+/// the same time. The snapshots are merged like `--rpc-url` views (see
+/// [`multinode::merge`]); a client holds a transaction when it has the same
+/// hash in that sender/nonce slot. Writes the JSON report to `output` when
+/// given and prints a table to stdout.
+pub fn run(paths: &[String], output: Option<&str>) -> Result<(), Box<dyn Error>> {
+    let views: Vec<Value> = paths.iter().map(|path| load(path)).collect::<Result<_, _>>()?;
+    let labels = labels(paths, &views);
+
+    let captures: Vec<u64> = views.iter().filter_map(snapshot::captured_at).collect();
+    let skew = captures.iter().max().zip(captures.iter().min()).map(|(last, first)| last - first);
+    if let Some(skew) = skew.filter(|skew| *skew > MAX_SKEW_SECONDS) {
+        emit::warn!("Snapshots were captured {skew} s apart; differences include pool churn", skew);
+        eprintln!("Snapshots were captured {} s apart; differences include pool churn", skew);
+    }
+
+    // Slots held with the same hash in different sections: one client queues
+    // what another already considers executable.
+    let mut sections: HashMap<(String, String, Option<String>), HashSet<&str>> = HashMap::new();
+    for view in &views {
+        for entry in snapshot::transactions(view) {
+            sections
+                .entry((entry.account.to_ascii_lowercase(), entry.nonce.to_string(), tx_hash(entry.tx)))
+                .or_default()
+                .insert(entry.section);
+        }
+    }
+    let section_mismatches = sections.values().filter(|held_in| held_in.len() > 1).count();
+
+    // Tips are taken at the base fee of the first snapshot that recorded one.
+    let base_fee = views
+        .iter()
+        .find_map(|view| view["metadata"]["base_fee"].as_str().and_then(parse_u128));
+
+    let merged: Vec<(&str, Option<Value>)> = labels.iter().map(String::as_str).zip(views.into_iter().map(Some)).collect();
+    let union = multinode::merge(&merged);
+
+    let mut clients: Vec<ClientCounts> = labels.iter().map(|_| ClientCounts::default()).collect();
+    let mut pairs = vec![vec![0usize; labels.len()]; labels.len()];
+    let (mut total, mut held_by_all) = (0usize, 0usize);
+    for entry in snapshot::transactions(&union) {
+        total += 1;
+        let holders: Vec<bool> = labels
+            .iter()
+            .map(|label| entry.tx["visibility"][label].as_bool().unwrap_or(false))
+            .collect();
+        let holder_count = holders.iter().filter(|held| **held).count();
+        if holder_count == labels.len() {
+            held_by_all += 1;
+        }
+        let tx_type = rlp::tx_type(entry.tx).to_string();
+        let bucket = FeeCaps::of(entry.tx).map_or("unknown", |caps| fees::fee_bucket(caps.effective_priority_fee(base_fee)));
+        for (index, counts) in clients.iter_mut().enumerate() {
+            if holders[index] {
+                counts.held += 1;
+                if holder_count == 1 {
+                    counts.exclusive += 1;
+                }
+                for (other, held) in holders.iter().enumerate().skip(index + 1) {
+                    if *held {
+                        pairs[index][other] += 1;
+                    }
+                }
+            } else {
+                counts.missing += 1;
+                *counts.missing_by_type.entry(tx_type.clone()).or_insert(0) += 1;
+                *counts.missing_by_fee_bucket.entry(bucket).or_insert(0) += 1;
+            }
+        }
+    }
+
+    let mut pairwise = Vec::new();
+    for (index, label) in labels.iter().enumerate() {
+        for (other, other_label) in labels.iter().enumerate().skip(index + 1) {
+            let shared = pairs[index][other];
+            let either = clients[index].held + clients[other].held - shared;
+            pairwise.push(json!({
+                "clients": [label, other_label],
+                "shared": shared,
+                "jaccard": if either == 0 { 1.0 } else { shared as f64 / either as f64 },
+            }));
+        }
+    }
+
+    let client_reports: Vec<Value> = labels
+        .iter()
+        .zip(paths)
+        .zip(&clients)
+        .map(|((label, path), counts)| {
+            emit::emit!(
+                "{metric_agg} of {metric_name} is {metric_value}",
+                evt_kind: "metric",
+                metric_agg: "last",
+                metric_name: "txpool.compare.exclusive_txs",
+                metric_value: counts.exclusive,
+                client: label,
+            );
+            emit::emit!(
+                "{metric_agg} of {metric_name} is {metric_value}",
+                evt_kind: "metric",
+                metric_agg: "last",
+                metric_name: "txpool.compare.missing_txs",
+                metric_value: counts.missing,
+                client: label,
+            );
+            json!({
+                "client": label,
+                "path": path,
+                "held": counts.held,
+                "exclusive": counts.exclusive,
+                "missing": counts.missing,
+                "coverage": if total == 0 { 1.0 } else { counts.held as f64 / total as f64 },
+                "missing_by_type": counts.missing_by_type,
+                "missing_by_fee_bucket": counts.missing_by_fee_bucket,
+            })
+        })
+        .collect();
+
+    let mut report = Map::new();
+    report.insert("union".to_string(), total.into());
+    report.insert("held_by_all".to_string(), held_by_all.into());
+    report.insert("conflicting_slots".to_string(), union["metadata"]["nodes"]["conflicting_slots"].clone());
+    report.insert("section_mismatches".to_string(), section_mismatches.into());
+    report.insert("capture_skew_seconds".to_string(), json!(skew));
+    report.insert("clients".to_string(), client_reports.into());
+    report.insert("pairwise".to_string(), pairwise.into());
+
+    emit::emit!(
+        "{metric_agg} of {metric_name} is {metric_value}",
+        evt_kind: "metric",
+        metric_agg: "last",
+        metric_name: "txpool.compare.shared_txs",
+        metric_value: held_by_all,
+    );
+
+    println!(
+        "{} transactions across {} clients, {} held by all, {} conflicting slots, {} in different sections",
+        total,
+        labels.len(),
+        held_by_all,
+        report["conflicting_slots"],
+        section_mismatches
+    );
+    println!("  {:<24}{:>10}{:>11}{:>10}{:>10}", "client", "held", "exclusive", "missing", "coverage");
+    for (label, counts) in labels.iter().zip(&clients) {
+        let coverage = if total == 0 { 100.0 } else { counts.held as f64 * 100.0 / total as f64 };
+        println!(
+            "  {:<24}{:>10}{:>11}{:>10}{:>9.1}%",
+            label, counts.held, counts.exclusive, counts.missing, coverage
+        );
+    }
+
+    if let Some(output) = output {
+        fs::write(output, serde_json::to_string_pretty(&Value::Object(report))?)?;
+        println!("Report written to {}", output);
+    }
+    Ok(())
+}
@@ -0,0 +1,82 @@
+use serde_json::{Value, json};
+
+use crate::keccak::keccak256;
+use crate::quantity::{bytes_to_hex, hex_to_bytes, to_be_bytes};
+use crate::rlp;
+use crate::snapshot::{self, find_field, find_field_mut};
+
+/// Whether a transaction deploys a contract: its `to` is present but empty
+/// (`null` from the dump's `Create`, or the `Create` marker of older snapshots).
+pub fn is_creation(tx: &Value) -> bool {
+    match find_field(tx, "to") {
+        Some(Value::Null) => true,
+        Some(Value::String(to)) => to == "Create",
+        _ => false,
+    }
+}
+
+/// Address a CREATE from `sender` at `nonce` deploys to:
+/// `keccak256(rlp([sender, nonce]))[12..]`.
+fn create_address(sender: &str, nonce: &Value) -> Option<String> {
+    let sender = hex_to_bytes(sender).filter(|bytes| bytes.len() == 20)?;
+    let encoded = rlp::list(&[rlp::string(&sender), rlp::string(&to_be_bytes(nonce)?)]);
+    Some(bytes_to_hex(&keccak256(&encoded)[12..]))
+}
+
+/// Mark contract deployments: `to` becomes `null`, and the transaction gets
+/// `creates_contract: true`, `init_code_bytes` (the calldata length) and the
+/// predicted `contract_address`. Pool totals go to
+/// `metadata.contract_creations` and the count is emitted as
+/// `txpool.contract.creations`.
+pub fn annotate(snapshot: &mut Value) {
+    let creations: Vec<(&'static str, String, String)> = snapshot::transactions(snapshot)
+        .filter(|entry| is_creation(entry.tx))
+        .map(|entry| (entry.section, entry.account.to_string(), entry.nonce.to_string()))
+        .collect();
+
+    let mut init_code_total = 0usize;
+    let mut init_code_max = 0usize;
+    for (section, account, nonce) in &creations {
+        let tx = &mut snapshot[*section][account.as_str()][nonce.as_str()];
+        let init_code = find_field(tx, "input").and_then(Value::as_str).and_then(hex_to_bytes);
+        let sender = find_field(tx, "signer")
+            .and_then(Value::as_str)
+            .unwrap_or(account)
+            .to_string();
+        let nonce_value = find_field(tx, "nonce").cloned().unwrap_or_else(|| json!(nonce));
+        let address = create_address(&sender, &nonce_value);
+
+        if let Some(to) = find_field_mut(tx, "to") {
+            *to = Value::Null;
+        }
+        let Some(tx) = tx.as_object_mut() else {
+            continue;
+        };
+        tx.insert("creates_contract".to_string(), json!(true));
+        if let Some(init_code) = &init_code {
+            init_code_total += init_code.len();
+            init_code_max = init_code_max.max(init_code.len());
+            tx.insert("init_code_bytes".to_string(), json!(init_code.len()));
+        }
+        if let Some(address) = address {
+            tx.insert("contract_address".to_string(), json!(address));
+        }
+    }
+
+    emit::emit!(
+        "{metric_agg} of {metric_name} is {metric_value}",
+        evt_kind: "metric",
+        metric_agg: "last",
+        metric_name: "txpool.contract.creations",
+        metric_value: creations.len(),
+    );
+
+    snapshot::metadata_mut(snapshot).insert(
+        "contract_creations".to_string(),
+        json!({
+            "count": creations.len(),
+            "init_code_bytes": init_code_total,
+            "max_init_code_bytes": init_code_max,
+        }),
+    );
+}
@@ -0,0 +1,81 @@
+use serde_json::{Value, json};
+use std::collections::{BTreeSet, HashMap};
+
+use crate::filter::tx_to;
+use crate::quantity::{format_units, to_u128};
+use crate::snapshot::{self, find_field};
+
+/// Recipients whose demand is emitted as labeled metrics.
+const METRIC_RECIPIENTS: usize = 10;
+
+#[derive(Default)]
+struct Demand {
+    txs: usize,
+    gas: u128,
+    value: u128,
+    senders: BTreeSet<String>,
+}
+
+/// Pending demand per recipient: transactions, total gas limit, total value
+/// and distinct senders for every `to` address, ranked by transaction count
+/// and then gas. Contract creations have no recipient and are counted
+/// separately. The top recipients' transaction counts are emitted as
+/// `txpool.demand.txs` with a `to` label.
+pub fn recipients(snapshot: &Value) -> Value {
+    let mut demand: HashMap<String, Demand> = HashMap::new();
+    let mut pending = 0usize;
+    let mut creations = 0usize;
+    for entry in snapshot::transactions(snapshot).filter(|entry| entry.section == "pending") {
+        pending += 1;
+        let Some(to) = tx_to(entry.tx) else {
+            creations += 1;
+            continue;
+        };
+        let field = |key: &str| find_field(entry.tx, key).and_then(to_u128);
+        let recipient = demand.entry(to).or_default();
+        recipient.txs += 1;
+        recipient.gas += field("gas_limit").or_else(|| field("gas")).unwrap_or(0);
+        recipient.value += field("value").unwrap_or(0);
+        recipient.senders.insert(entry.account.to_ascii_lowercase());
+    }
+
+    let mut ranked: Vec<(String, Demand)> = demand.into_iter().collect();
+    ranked.sort_by(|(a_to, a), (b_to, b)| {
+        b.txs.cmp(&a.txs).then(b.gas.cmp(&a.gas)).then_with(|| a_to.cmp(b_to))
+    });
+
+    for (to, recipient) in ranked.iter().take(METRIC_RECIPIENTS) {
+        let txs = recipient.txs;
+        emit::emit!(
+            "{metric_agg} of {metric_name} is {metric_value}",
+            evt_kind: "metric",
+            metric_agg: "last",
+            metric_name: "txpool.demand.txs",
+            metric_value: txs,
+            to,
+        );
+    }
+
+    let recipients: Vec<Value> = ranked
+        .into_iter()
+        .enumerate()
+        .map(|(rank, (to, recipient))| {
+            json!({
+                "rank": rank + 1,
+                "to": to,
+                "txs": recipient.txs,
+                "share": recipient.txs as f64 / pending as f64,
+                "gas": recipient.gas.to_string(),
+                "value_wei": recipient.value.to_string(),
+                "value_eth": format_units(recipient.value, 18, 6),
+                "senders": recipient.senders.len(),
+            })
+        })
+        .collect();
+
+    json!({
+        "pending_txs": pending,
+        "contract_creations": creations,
+        "recipients": recipients,
+    })
+}
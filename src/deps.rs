@@ -0,0 +1,88 @@
+use serde_json::{Value, json};
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::snapshot::{self, find_field};
+
+struct Node<'a> {
+    hash: String,
+    section: &'static str,
+    sender: &'a str,
+    nonce: u64,
+}
+
+/// Dependency graph of `targets` (transaction hashes) within the snapshot.
+///
+/// A target depends on every pool transaction from the same sender with a
+/// lower nonce, and conflicts with any other transaction occupying the same
+/// sender and nonce. Only transactions that appear in an edge become nodes;
+/// target hashes not found in the pool are listed under `missing`.
+pub fn dependency_graph(snapshot: &Value, targets: &[String]) -> Value {
+    let mut by_sender: BTreeMap<&str, Vec<Node>> = BTreeMap::new();
+    for entry in snapshot::transactions(snapshot) {
+        let (Ok(nonce), Some(hash)) = (
+            entry.nonce.parse::<u64>(),
+            find_field(entry.tx, "hash").and_then(Value::as_str),
+        ) else {
+            continue;
+        };
+        by_sender.entry(entry.account).or_default().push(Node {
+            hash: hash.to_ascii_lowercase(),
+            section: entry.section,
+            sender: entry.account,
+            nonce,
+        });
+    }
+
+    let wanted: BTreeSet<String> = targets.iter().map(|hash| hash.to_ascii_lowercase()).collect();
+    let mut found = BTreeSet::new();
+    let mut nodes: BTreeMap<&str, &Node> = BTreeMap::new();
+    let mut edges = Vec::new();
+
+    for sender_txs in by_sender.values() {
+        for target in sender_txs.iter().filter(|node| wanted.contains(&node.hash)) {
+            found.insert(target.hash.as_str());
+            nodes.insert(&target.hash, target);
+            for other in sender_txs.iter().filter(|other| other.hash != target.hash) {
+                let kind = if other.nonce < target.nonce {
+                    "depends_on"
+                } else if other.nonce == target.nonce {
+                    "conflicts_with"
+                } else {
+                    continue;
+                };
+                nodes.insert(&other.hash, other);
+                edges.push(json!({ "from": target.hash, "to": other.hash, "kind": kind }));
+            }
+        }
+    }
+
+    let missing: Vec<&String> = wanted.iter().filter(|hash| !found.contains(hash.as_str())).collect();
+    let nodes: Vec<Value> = nodes
+        .values()
+        .map(|node| {
+            json!({
+                "hash": node.hash,
+                "sender": node.sender,
+                "nonce": node.nonce,
+                "section": node.section,
+                "target": wanted.contains(&node.hash),
+            })
+        })
+        .collect();
+
+    emit::emit!(
+        "{metric_agg} of {metric_name} is {metric_value}",
+        evt_kind: "metric",
+        metric_agg: "count",
+        metric_name: "txpool.deps.edges",
+        metric_value: edges.len(),
+        targets: wanted.len(),
+    );
+
+    json!({
+        "targets": wanted,
+        "missing": missing,
+        "nodes": nodes,
+        "edges": edges,
+    })
+}
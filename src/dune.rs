@@ -0,0 +1,86 @@
+use serde_json::Value;
+use std::fmt::Write;
+
+use crate::chain::tx_chain_id;
+use crate::quantity::{hex_to_bytes, to_u128};
+use crate::rlp;
+use crate::snapshot::{self, find_field};
+
+/// Column names of the Dune upload, in file order.
+const COLUMNS: [&str; 16] = [
+    "snapshot_time",
+    "first_seen",
+    "section",
+    "chain_id",
+    "tx_hash",
+    "from_address",
+    "to_address",
+    "nonce",
+    "tx_type",
+    "value",
+    "gas_limit",
+    "gas_price",
+    "max_fee_per_gas",
+    "max_priority_fee_per_gas",
+    "input_bytes",
+    "size_bytes",
+];
+
+/// Lowercase `0x` hex of a string field, empty when absent or not hex (e.g.
+/// the `Create` marker of contract deployments).
+fn hex_field(tx: &Value, key: &str) -> String {
+    find_field(tx, key)
+        .and_then(Value::as_str)
+        .filter(|hex| hex.starts_with("0x") && hex_to_bytes(hex).is_some())
+        .map(str::to_ascii_lowercase)
+        .unwrap_or_default()
+}
+
+/// Decimal rendering of a quantity field, empty when absent.
+fn uint_field(tx: &Value, key: &str) -> String {
+    find_field(tx, key)
+        .and_then(to_u128)
+        .map(|n| n.to_string())
+        .unwrap_or_default()
+}
+
+/// CSV in the shape Dune's dataset upload expects: a header row of
+/// lowercase snake_case columns, lowercase hex strings, unix-second
+/// timestamps, and decimal integers (empty cells for missing values).
+pub fn to_csv(snapshot: &Value) -> String {
+    let snapshot_time = snapshot::captured_at(snapshot)
+        .map(|ts| ts.to_string())
+        .unwrap_or_default();
+
+    let mut out = COLUMNS.join(",");
+    out.push('\n');
+    for entry in snapshot::transactions(snapshot) {
+        let tx = entry.tx;
+        let input_bytes = find_field(tx, "input")
+            .and_then(Value::as_str)
+            .and_then(hex_to_bytes)
+            .map(|input| input.len().to_string())
+            .unwrap_or_default();
+        let row = [
+            snapshot_time.clone(),
+            tx.get("first_seen").and_then(Value::as_u64).map(|ts| ts.to_string()).unwrap_or_default(),
+            entry.section.to_string(),
+            tx_chain_id(tx).map(|id| id.to_string()).unwrap_or_default(),
+            hex_field(tx, "hash"),
+            entry.account.to_ascii_lowercase(),
+            hex_field(tx, "to"),
+            entry.nonce.to_string(),
+            rlp::tx_type(tx).to_string(),
+            uint_field(tx, "value"),
+            uint_field(tx, "gas_limit"),
+            uint_field(tx, "gas_price"),
+            uint_field(tx, "max_fee_per_gas"),
+            uint_field(tx, "max_priority_fee_per_gas"),
+            input_bytes,
+            uint_field(tx, "size_bytes"),
+        ];
+        // Every cell is hex, decimal or a section name, so none needs quoting.
+        let _ = writeln!(out, "{}", row.join(","));
+    }
+    out
+}
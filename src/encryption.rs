@@ -0,0 +1,92 @@
+use std::error::Error;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+
+use age::stream::StreamWriter;
+
+/// Recipients that output files are encrypted to at rest; files are written
+/// in the clear when there are none.
+#[derive(Clone, Default)]
+pub struct Encryption {
+    recipients: Vec<age::x25519::Recipient>,
+}
+
+impl Encryption {
+    /// Add the recipient of an `--encrypt age:<recipient>` value.
+    pub fn add(&mut self, spec: &str) -> Result<(), Box<dyn Error>> {
+        let recipient = spec
+            .strip_prefix("age:")
+            .ok_or_else(|| format!("Unsupported --encrypt value {} (expected age:<recipient>)", spec))?;
+        let recipient = recipient
+            .parse()
+            .map_err(|err| format!("Invalid age recipient {}: {}", recipient, err))?;
+        self.recipients.push(recipient);
+        Ok(())
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        !self.recipients.is_empty()
+    }
+
+    /// Name `filename` is stored under: with `.age` appended when encrypting.
+    pub fn path(&self, filename: &str) -> String {
+        if self.is_enabled() {
+            format!("{}.age", filename)
+        } else {
+            filename.to_string()
+        }
+    }
+
+    /// Create `path` (as returned by [`Encryption::path`]) for writing.
+    pub fn create(&self, path: &str) -> Result<OutputFile, Box<dyn Error>> {
+        let file = BufWriter::new(File::create(path)?);
+        if !self.is_enabled() {
+            return Ok(OutputFile::Plain(file));
+        }
+        let encryptor = age::Encryptor::with_recipients(
+            self.recipients.iter().map(|recipient| recipient as &dyn age::Recipient),
+        )?;
+        Ok(OutputFile::Age(encryptor.wrap_output(file)?))
+    }
+
+    /// Write `contents` to `path` in one go.
+    pub fn write(&self, path: &str, contents: &[u8]) -> Result<(), Box<dyn Error>> {
+        let mut file = self.create(path)?;
+        file.write_all(contents)?;
+        file.finish()?;
+        Ok(())
+    }
+}
+
+/// An output file being written, possibly through an age stream.
+pub enum OutputFile {
+    Plain(BufWriter<File>),
+    Age(StreamWriter<BufWriter<File>>),
+}
+
+impl OutputFile {
+    /// Flush the file; for age this also writes the final chunk, without which
+    /// the file cannot be decrypted.
+    pub fn finish(self) -> io::Result<()> {
+        match self {
+            OutputFile::Plain(mut file) => file.flush(),
+            OutputFile::Age(stream) => stream.finish()?.flush(),
+        }
+    }
+}
+
+impl Write for OutputFile {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self {
+            OutputFile::Plain(file) => file.write(buf),
+            OutputFile::Age(stream) => stream.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self {
+            OutputFile::Plain(file) => file.flush(),
+            OutputFile::Age(stream) => stream.flush(),
+        }
+    }
+}
@@ -0,0 +1,243 @@
+use serde_json::{Map, Value, json};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::error::Error;
+use std::fs;
+
+use crate::quantity::{bytes_to_hex, format_units, hex_to_bytes, to_json, to_u128};
+use crate::snapshot::{self, find_field};
+
+/// ERC-20 `transfer(address,uint256)`.
+const TRANSFER: [u8; 4] = [0xa9, 0x05, 0x9c, 0xbb];
+/// ERC-20 `transferFrom(address,address,uint256)`.
+const TRANSFER_FROM: [u8; 4] = [0x23, 0xb8, 0x72, 0xdd];
+
+#[derive(Clone)]
+struct Label {
+    entity: String,
+    category: Option<String>,
+}
+
+/// Address labels loaded from a JSON file mapping addresses to an entity
+/// name, or to an object with `entity` and an optional `category`:
+///
+/// ```json
+/// { "0x28c6…": { "entity": "Binance", "category": "exchange" }, "0x3ee1…": "Wormhole" }
+/// ```
+#[derive(Clone, Default)]
+pub struct Labels {
+    by_address: HashMap<String, Label>,
+}
+
+impl Labels {
+    pub fn load(path: &str) -> Result<Self, Box<dyn Error>> {
+        let config: Value = serde_json::from_str(&fs::read_to_string(path)?)
+            .map_err(|err| format!("Invalid labels file {}: {}", path, err))?;
+        let config = config
+            .as_object()
+            .ok_or_else(|| format!("Labels file {} must be a JSON object", path))?;
+
+        let mut labels = Labels::default();
+        for (address, label) in config {
+            let label = match label {
+                Value::String(entity) => Label {
+                    entity: entity.clone(),
+                    category: None,
+                },
+                Value::Object(fields) => Label {
+                    entity: fields
+                        .get("entity")
+                        .and_then(Value::as_str)
+                        .ok_or_else(|| format!("Label for {} in {} has no `entity`", address, path))?
+                        .to_string(),
+                    category: fields.get("category").and_then(Value::as_str).map(str::to_string),
+                },
+                _ => return Err(format!("Label for {} in {} must be a string or object", address, path).into()),
+            };
+            labels.by_address.insert(address.to_ascii_lowercase(), label);
+        }
+        Ok(labels)
+    }
+
+    fn get(&self, address: &str) -> Option<&Label> {
+        self.by_address.get(&address.to_ascii_lowercase())
+    }
+}
+
+/// Pending flow on one side of an entity.
+#[derive(Default)]
+struct Flow {
+    txs: usize,
+    value: u128,
+    /// Token contract -> summed ERC-20 amount.
+    tokens: BTreeMap<String, u128>,
+    counterparties: BTreeSet<String>,
+}
+
+impl Flow {
+    fn to_json(&self) -> Value {
+        let tokens: Map<String, Value> = self
+            .tokens
+            .iter()
+            .map(|(token, amount)| (token.clone(), to_json(*amount)))
+            .collect();
+        json!({
+            "txs": self.txs,
+            "value_wei": to_json(self.value),
+            "value_eth": format_units(self.value, 18, 6),
+            "tokens": tokens,
+            "counterparties": self.counterparties.len(),
+        })
+    }
+}
+
+/// One way a transaction moves value into or out of an address.
+struct Touch {
+    address: String,
+    is_inflow: bool,
+    native: u128,
+    /// Token contract and ERC-20 amount.
+    token: Option<(String, u128)>,
+    counterparty: Option<String>,
+}
+
+#[derive(Default)]
+struct Entity {
+    category: Option<String>,
+    addresses: BTreeSet<String>,
+    inflow: Flow,
+    outflow: Flow,
+}
+
+/// Address in an ABI word (the low 20 bytes).
+fn word_address(word: &[u8]) -> String {
+    bytes_to_hex(&word[12..32])
+}
+
+/// Amount in an ABI word, saturating at `u128::MAX`.
+fn word_amount(word: &[u8]) -> u128 {
+    if word[..16].iter().any(|b| *b != 0) {
+        return u128::MAX;
+    }
+    word[16..32].iter().fold(0, |amount, b| amount << 8 | u128::from(*b))
+}
+
+/// ERC-20 movement encoded in calldata: `(from, to, amount)`, where `from`
+/// is `None` for `transfer` (the sender moves its own tokens).
+fn token_transfer(input: &[u8]) -> Option<(Option<String>, String, u128)> {
+    let (selector, args) = input.split_at_checked(4)?;
+    if selector == TRANSFER && args.len() >= 64 {
+        Some((None, word_address(&args[..32]), word_amount(&args[32..64])))
+    } else if selector == TRANSFER_FROM && args.len() >= 96 {
+        Some((Some(word_address(&args[..32])), word_address(&args[32..64]), word_amount(&args[64..96])))
+    } else {
+        None
+    }
+}
+
+/// Pending inflow and outflow per labeled entity, for incident response at
+/// exchanges and bridges. A transaction counts as inflow of the entity it is
+/// sent to (native value and contract calls such as bridge deposits) or
+/// whose address receives an ERC-20 `transfer`/`transferFrom`; it counts as
+/// outflow of the entity that sends it or whose tokens it moves. Each side
+/// reports transactions, native value, token amounts per token contract and
+/// distinct counterparties. Native inflow per entity is emitted as
+/// `txpool.entity.inflow_wei` with an `entity` label.
+pub fn report(snapshot: &Value, labels: &Labels) -> Value {
+    let mut entities: BTreeMap<String, Entity> = BTreeMap::new();
+    for entry in snapshot::transactions(snapshot) {
+        let sender = entry.account.to_ascii_lowercase();
+        let to = find_field(entry.tx, "to")
+            .and_then(Value::as_str)
+            .filter(|to| to.starts_with("0x"))
+            .map(str::to_ascii_lowercase);
+        let value = find_field(entry.tx, "value").and_then(to_u128).unwrap_or(0);
+        let input = find_field(entry.tx, "input")
+            .and_then(Value::as_str)
+            .and_then(hex_to_bytes)
+            .unwrap_or_default();
+
+        let mut touches = vec![Touch {
+            address: sender.clone(),
+            is_inflow: false,
+            native: value,
+            token: None,
+            counterparty: to.clone(),
+        }];
+        if let Some(to) = &to {
+            touches.push(Touch {
+                address: to.clone(),
+                is_inflow: true,
+                native: value,
+                token: None,
+                counterparty: Some(sender.clone()),
+            });
+        }
+        if let (Some(token), Some((from, recipient, amount))) = (&to, token_transfer(&input)) {
+            let from = from.unwrap_or_else(|| sender.clone());
+            touches.push(Touch {
+                address: recipient.clone(),
+                is_inflow: true,
+                native: 0,
+                token: Some((token.clone(), amount)),
+                counterparty: Some(from.clone()),
+            });
+            touches.push(Touch {
+                address: from,
+                is_inflow: false,
+                native: 0,
+                token: Some((token.clone(), amount)),
+                counterparty: Some(recipient),
+            });
+        }
+
+        // Each transaction counts once per entity and direction.
+        let mut counted = BTreeSet::new();
+        for touch in touches {
+            let Some(label) = labels.get(&touch.address) else {
+                continue;
+            };
+            let entity = entities.entry(label.entity.clone()).or_default();
+            if entity.category.is_none() {
+                entity.category = label.category.clone();
+            }
+            entity.addresses.insert(touch.address);
+            let flow = if touch.is_inflow { &mut entity.inflow } else { &mut entity.outflow };
+            if counted.insert((label.entity.clone(), touch.is_inflow)) {
+                flow.txs += 1;
+            }
+            flow.value = flow.value.saturating_add(touch.native);
+            if let Some((token, amount)) = touch.token {
+                let total = flow.tokens.entry(token).or_insert(0);
+                *total = total.saturating_add(amount);
+            }
+            flow.counterparties.extend(touch.counterparty);
+        }
+    }
+
+    let mut report = Map::new();
+    for (name, entity) in &entities {
+        let inflow_wei = entity.inflow.value;
+        emit::emit!(
+            "{metric_agg} of {metric_name} is {metric_value}",
+            evt_kind: "metric",
+            metric_agg: "last",
+            metric_name: "txpool.entity.inflow_wei",
+            metric_value: inflow_wei,
+            entity: name,
+        );
+        report.insert(
+            name.clone(),
+            json!({
+                "category": entity.category,
+                "addresses": entity.addresses,
+                "inflow": entity.inflow.to_json(),
+                "outflow": entity.outflow.to_json(),
+            }),
+        );
+    }
+    json!({
+        "captured_at": snapshot["metadata"]["captured_at"],
+        "labeled_addresses": labels.by_address.len(),
+        "entities": report,
+    })
+}
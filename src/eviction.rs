@@ -0,0 +1,154 @@
+use serde_json::{Value, json};
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::fees::{self, FeeCaps};
+use crate::rlp;
+use crate::snapshot::{self, find_field};
+
+/// Pool capacity in slots when `--max-slots` is not given: geth's default
+/// `GlobalSlots` plus `GlobalQueue`.
+const DEFAULT_MAX_SLOTS: usize = 5120 + 1024;
+
+/// Transactions per account when `--max-per-account` is not given: geth's
+/// default `AccountQueue`.
+const DEFAULT_MAX_PER_ACCOUNT: usize = 64;
+
+/// Bytes per pool slot; larger transactions take several (geth's `txSlotSize`).
+const SLOT_SIZE: u64 = 32 * 1024;
+
+/// Pool size limits the eviction report is computed against.
+#[derive(Clone, Copy, Default)]
+pub struct PoolLimits {
+    pub max_slots: Option<usize>,
+    pub max_per_account: Option<usize>,
+}
+
+impl PoolLimits {
+    fn max_slots(&self) -> usize {
+        self.max_slots.unwrap_or(DEFAULT_MAX_SLOTS)
+    }
+
+    fn max_per_account(&self) -> usize {
+        self.max_per_account.unwrap_or(DEFAULT_MAX_PER_ACCOUNT)
+    }
+}
+
+struct Candidate<'a> {
+    account: String,
+    nonce: u64,
+    section: &'static str,
+    tx: &'a Value,
+    slots: u64,
+    tip: i128,
+    max_fee: u128,
+}
+
+/// Pool slots a transaction takes, from its signed size.
+fn slots(tx: &Value) -> u64 {
+    let size = tx
+        .get("size_bytes")
+        .and_then(Value::as_u64)
+        .or_else(|| rlp::encode_signed(tx).map(|signed| signed.len() as u64))
+        .unwrap_or(0);
+    size.div_ceil(SLOT_SIZE).max(1)
+}
+
+fn describe(candidate: &Candidate, reason: &str) -> Value {
+    json!({
+        "from": candidate.account,
+        "nonce": candidate.nonce,
+        "hash": find_field(candidate.tx, "hash"),
+        "section": candidate.section,
+        "reason": reason,
+        "slots": candidate.slots,
+        "effective_tip": candidate.tip.to_string(),
+        "fee_bucket": fees::fee_bucket(candidate.tip),
+    })
+}
+
+/// Transactions a geth/reth-like pool capped at `limits` would drop.
+///
+/// First, every account keeps only its `max_per_account` lowest nonces; the
+/// rest are `account_cap` candidates. If the remaining transactions still
+/// take more than `max_slots` slots (one per 32 KiB of signed size), the
+/// cheapest are dropped as `pool_full` until they fit: lowest effective tip
+/// at `base_fee` first, then lowest fee cap, queued before pending, and a
+/// sender's highest nonces first so the survivors stay gapless where
+/// possible. Candidate counts are emitted as `txpool.eviction.candidates`
+/// with a `reason` label.
+pub fn candidates(snapshot: &Value, limits: &PoolLimits, base_fee: Option<u128>) -> Value {
+    let mut by_account: BTreeMap<String, Vec<Candidate>> = BTreeMap::new();
+    for entry in snapshot::transactions(snapshot) {
+        let Ok(nonce) = entry.nonce.parse::<u64>() else {
+            continue;
+        };
+        let fees = FeeCaps::of(entry.tx);
+        let account = entry.account.to_ascii_lowercase();
+        by_account.entry(account.clone()).or_default().push(Candidate {
+            account,
+            nonce,
+            section: entry.section,
+            tx: entry.tx,
+            slots: slots(entry.tx),
+            tip: fees.as_ref().map_or(0, |fees| fees.effective_priority_fee(base_fee)),
+            max_fee: fees.map_or(0, |fees| fees.max_fee),
+        });
+    }
+
+    let mut evicted = Vec::new();
+    let mut capped_accounts = BTreeSet::new();
+    let mut kept = Vec::new();
+    for (account, mut txs) in by_account {
+        txs.sort_by_key(|candidate| candidate.nonce);
+        if txs.len() > limits.max_per_account() {
+            capped_accounts.insert(account);
+            for candidate in txs.split_off(limits.max_per_account()) {
+                evicted.push(describe(&candidate, "account_cap"));
+            }
+        }
+        kept.extend(txs);
+    }
+    let account_cap = evicted.len();
+
+    let slots_used: u64 = kept.iter().map(|candidate| candidate.slots).sum();
+    let mut remaining = slots_used;
+    kept.sort_by_key(|candidate| {
+        (candidate.tip, candidate.max_fee, candidate.section == "pending", Reverse(candidate.nonce))
+    });
+    for candidate in &kept {
+        if remaining <= limits.max_slots() as u64 {
+            break;
+        }
+        remaining -= candidate.slots;
+        evicted.push(describe(candidate, "pool_full"));
+    }
+    let pool_full = evicted.len() - account_cap;
+
+    for (reason, count) in [("account_cap", account_cap), ("pool_full", pool_full)] {
+        emit::emit!(
+            "{metric_agg} of {metric_name} is {metric_value}",
+            evt_kind: "metric",
+            metric_agg: "last",
+            metric_name: "txpool.eviction.candidates",
+            metric_value: count,
+            reason,
+        );
+    }
+
+    json!({
+        "limits": {
+            "max_slots": limits.max_slots(),
+            "max_per_account": limits.max_per_account(),
+        },
+        "base_fee": base_fee.map(|fee| fee.to_string()),
+        "slots_used": slots_used,
+        "slots_after_eviction": remaining,
+        "accounts_over_cap": capped_accounts,
+        "counts": {
+            "account_cap": account_cap,
+            "pool_full": pool_full,
+        },
+        "eviction_candidates": evicted,
+    })
+}
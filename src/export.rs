@@ -0,0 +1,133 @@
+use serde_json::Value;
+use std::error::Error;
+
+use crate::encryption::Encryption;
+use crate::entities::{self, Labels};
+use crate::eviction::{self, PoolLimits};
+use crate::filter::TxFilter;
+use crate::{bigquery, builder, bump, clusters, demand, deps, dune, raw_batch};
+
+/// Extra files that can be written next to the snapshot.
+#[derive(Clone, Copy, PartialEq)]
+pub enum ExportKind {
+    /// Pending transactions as raw envelopes in builder priority order.
+    Builder,
+    /// Dependency and conflict graph of the `--target` transactions.
+    Deps,
+    /// Time-to-inclusion report of `--track-inclusion`.
+    Latency,
+    /// CSV matching Dune's dataset upload format.
+    Dune,
+    /// Newline-delimited JSON rows of the BigQuery table schema.
+    BigQuery,
+    /// Transactions grouped by selector and calldata similarity.
+    Clusters,
+    /// Pending inflow and outflow per `--labels` entity.
+    Entities,
+    /// JSON-RPC batch of `eth_sendRawTransaction` calls for the `--select`ed transactions.
+    RawBatch,
+    /// Transactions a pool capped at `--max-slots`/`--max-per-account` would evict.
+    Eviction,
+    /// Replacement suggestions for stuck (or `--select`ed) transactions.
+    Bump,
+    /// Pending transactions aggregated per recipient, busiest first.
+    Demand,
+}
+
+impl ExportKind {
+    pub fn parse(name: &str) -> Result<Self, Box<dyn Error>> {
+        match name {
+            "builder" => Ok(ExportKind::Builder),
+            "deps" => Ok(ExportKind::Deps),
+            "latency" => Ok(ExportKind::Latency),
+            "dune" => Ok(ExportKind::Dune),
+            "bigquery" => Ok(ExportKind::BigQuery),
+            "clusters" => Ok(ExportKind::Clusters),
+            "entities" => Ok(ExportKind::Entities),
+            "raw-batch" => Ok(ExportKind::RawBatch),
+            "eviction" => Ok(ExportKind::Eviction),
+            "bump" => Ok(ExportKind::Bump),
+            "demand" => Ok(ExportKind::Demand),
+            other => Err(format!("Unknown export: {}", other).into()),
+        }
+    }
+
+    fn suffix(self) -> &'static str {
+        match self {
+            ExportKind::Builder => "builder.json",
+            ExportKind::Deps => "deps.json",
+            ExportKind::Latency => "latency.json",
+            ExportKind::Dune => "dune.csv",
+            ExportKind::BigQuery => "bigquery.ndjson",
+            ExportKind::Clusters => "clusters.json",
+            ExportKind::Entities => "entities.json",
+            ExportKind::RawBatch => "rawtx.json",
+            ExportKind::Eviction => "eviction.json",
+            ExportKind::Bump => "bump.json",
+            ExportKind::Demand => "demand.json",
+        }
+    }
+}
+
+/// Settings exports may need beyond the snapshot itself.
+pub struct ExportContext<'a> {
+    pub base_fee: Option<u128>,
+    pub targets: &'a [String],
+    pub labels: Option<&'a Labels>,
+    pub select: &'a TxFilter,
+    pub pool_limits: &'a PoolLimits,
+    pub bump_percent: u128,
+    pub encryption: &'a Encryption,
+}
+
+/// Write `kind` for `snapshot` to `<stem>.<suffix>` and return the file name.
+pub fn write(
+    kind: ExportKind,
+    snapshot: &Value,
+    stem: &str,
+    context: &ExportContext<'_>,
+) -> Result<String, Box<dyn Error>> {
+    let filename = context.encryption.path(&format!("{}.{}", stem, kind.suffix()));
+    let contents = match kind {
+        ExportKind::Builder => {
+            serde_json::to_string_pretty(&builder::priority_ordering(snapshot, context.base_fee))?
+        }
+        ExportKind::Deps => serde_json::to_string_pretty(&deps::dependency_graph(snapshot, context.targets))?,
+        ExportKind::Latency => serde_json::to_string_pretty(&snapshot["metadata"]["inclusion"])?,
+        ExportKind::Dune => dune::to_csv(snapshot),
+        ExportKind::BigQuery => bigquery::to_ndjson(snapshot)?,
+        ExportKind::Clusters => serde_json::to_string_pretty(&clusters::calldata_clusters(snapshot))?,
+        ExportKind::RawBatch => serde_json::to_string_pretty(&raw_batch::send_raw_batch(snapshot, context.select))?,
+        ExportKind::Demand => serde_json::to_string_pretty(&demand::recipients(snapshot))?,
+        ExportKind::Eviction => serde_json::to_string_pretty(&eviction::candidates(
+            snapshot,
+            context.pool_limits,
+            context.base_fee,
+        ))?,
+        ExportKind::Bump => {
+            let base_fee = context.base_fee.ok_or("--export bump requires --base-fee, --rpc-url or --ipc")?;
+            serde_json::to_string_pretty(&bump::suggestions(
+                snapshot,
+                base_fee,
+                context.select,
+                context.bump_percent,
+            ))?
+        }
+        ExportKind::Entities => {
+            let labels = context.labels.ok_or("--export entities requires --labels")?;
+            serde_json::to_string_pretty(&entities::report(snapshot, labels))?
+        }
+    };
+    context.encryption.write(&filename, contents.as_bytes())?;
+
+    let output_bytes = contents.len();
+    emit::emit!(
+        "{metric_agg} of {metric_name} is {metric_value}",
+        evt_kind: "metric",
+        metric_agg: "count",
+        metric_name: "txpool.export.bytes",
+        metric_value: output_bytes,
+        export: kind.suffix(),
+    );
+    Ok(filename)
+}
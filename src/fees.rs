@@ -0,0 +1,138 @@
+use serde_json::{Value, json};
+use std::collections::BTreeMap;
+use std::error::Error;
+
+use crate::quantity::{parse_u128, to_u128};
+use crate::rpc::RpcClient;
+use crate::snapshot::{self, find_field};
+
+/// Fee caps of a transaction: `gas_price` for legacy/2930, or the EIP-1559 pair.
+pub struct FeeCaps {
+    pub max_fee: u128,
+    pub max_priority_fee: u128,
+}
+
+impl FeeCaps {
+    pub fn of(tx: &Value) -> Option<Self> {
+        let field = |key: &str| find_field(tx, key).and_then(to_u128);
+        match (field("max_fee_per_gas"), field("max_priority_fee_per_gas")) {
+            (Some(max_fee), Some(max_priority_fee)) => Some(FeeCaps {
+                max_fee,
+                max_priority_fee,
+            }),
+            _ => {
+                let gas_price = field("gas_price")?;
+                Some(FeeCaps {
+                    max_fee: gas_price,
+                    max_priority_fee: gas_price,
+                })
+            }
+        }
+    }
+
+    /// Tip per gas the block producer would receive at `base_fee`; negative when
+    /// the transaction cannot pay the base fee. Without a base fee this is the
+    /// priority fee cap.
+    pub fn effective_priority_fee(&self, base_fee: Option<u128>) -> i128 {
+        // Fees of 2^127 wei and more only come from hostile dumps; they
+        // saturate instead of wrapping negative
+        let signed = |wei: u128| i128::try_from(wei).unwrap_or(i128::MAX);
+        let cap = signed(self.max_priority_fee);
+        match base_fee {
+            Some(base_fee) => cap.min(signed(self.max_fee).saturating_sub(signed(base_fee))),
+            None => cap,
+        }
+    }
+}
+
+/// Base fee of the latest block from `eth_getBlockByNumber`.
+pub fn fetch_base_fee(client: &RpcClient) -> Result<u128, Box<dyn Error>> {
+    let block = client.call("eth_getBlockByNumber", json!(["latest", false]))?;
+    block
+        .get("baseFeePerGas")
+        .and_then(Value::as_str)
+        .and_then(parse_u128)
+        .ok_or_else(|| "Latest block has no baseFeePerGas".into())
+}
+
+/// Upper bounds (exclusive, in gwei) of the tip buckets used in fee reports.
+const FEE_BUCKETS_GWEI: [(u128, &str); 5] = [
+    (1, "0-1gwei"),
+    (2, "1-2gwei"),
+    (5, "2-5gwei"),
+    (10, "5-10gwei"),
+    (50, "10-50gwei"),
+];
+
+/// Label of the tip bucket an effective priority fee (wei) falls into.
+pub fn fee_bucket(tip: i128) -> &'static str {
+    if tip < 0 {
+        return "underpriced";
+    }
+    let tip = tip as u128;
+    FEE_BUCKETS_GWEI
+        .iter()
+        .find(|(bound, _)| tip < bound * 1_000_000_000)
+        .map_or("50+gwei", |(_, label)| label)
+}
+
+/// Add `priority_fee_per_gas` (the effective tip at `base_fee`, negative
+/// when the fee cap is below it; the priority fee cap without a base fee)
+/// and its `fee_bucket` to every transaction with fee fields. Counts per
+/// bucket go to `metadata.fee_buckets` and `txpool.fees.bucket_txs`.
+pub fn annotate(snapshot: &mut Value, base_fee: Option<u128>) {
+    let mut counts: BTreeMap<&'static str, usize> = BTreeMap::new();
+    for tx in snapshot::transactions_mut(snapshot) {
+        let Some(caps) = FeeCaps::of(tx) else {
+            continue;
+        };
+        let tip = caps.effective_priority_fee(base_fee);
+        let bucket = fee_bucket(tip);
+        *counts.entry(bucket).or_insert(0) += 1;
+        if let Some(tx) = tx.as_object_mut() {
+            tx.insert("priority_fee_per_gas".to_string(), json!(tip));
+            tx.insert("fee_bucket".to_string(), json!(bucket));
+        }
+    }
+
+    for (fee_bucket, count) in &counts {
+        emit::emit!(
+            "{metric_agg} of {metric_name} is {metric_value}",
+            evt_kind: "metric",
+            metric_agg: "last",
+            metric_name: "txpool.fees.bucket_txs",
+            metric_value: count,
+            fee_bucket,
+        );
+    }
+
+    let metadata = snapshot::metadata_mut(snapshot);
+    metadata.insert("base_fee".to_string(), json!(base_fee.map(|fee| fee.to_string())));
+    metadata.insert("fee_buckets".to_string(), json!(counts));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GWEI: u128 = 1_000_000_000;
+
+    #[test]
+    fn effective_priority_fee() {
+        let caps = FeeCaps { max_fee: 30 * GWEI, max_priority_fee: 2 * GWEI };
+        assert_eq!(caps.effective_priority_fee(None), 2 * GWEI as i128);
+        assert_eq!(caps.effective_priority_fee(Some(10 * GWEI)), 2 * GWEI as i128);
+        assert_eq!(caps.effective_priority_fee(Some(29 * GWEI)), GWEI as i128);
+        assert_eq!(caps.effective_priority_fee(Some(31 * GWEI)), -(GWEI as i128));
+    }
+
+    #[test]
+    fn huge_fee_caps_saturate() {
+        let caps = FeeCaps { max_fee: 1 << 127, max_priority_fee: 1 << 127 };
+        assert_eq!(caps.effective_priority_fee(None), i128::MAX);
+        assert_eq!(caps.effective_priority_fee(Some(10 * GWEI)), i128::MAX - 10 * GWEI as i128);
+        assert_eq!(fee_bucket(caps.effective_priority_fee(Some(10 * GWEI))), "50+gwei");
+        let caps = FeeCaps { max_fee: 30 * GWEI, max_priority_fee: u128::MAX };
+        assert_eq!(caps.effective_priority_fee(Some(u128::MAX)), i128::MIN + 30 * GWEI as i128 + 1);
+    }
+}
@@ -0,0 +1,60 @@
+use serde_json::Value;
+use std::error::Error;
+
+use crate::rlp;
+use crate::snapshot::{TxEntry, find_field};
+
+/// Filter keys, as typed (`key=value`).
+pub const KEYS: [&str; 5] = ["from=", "to=", "nonce=", "section=", "type="];
+
+/// Transaction filter built from `key=value` conditions that must all match,
+/// shared by the REPL's `find` and `--select`.
+#[derive(Clone, Default)]
+pub struct TxFilter {
+    conditions: Vec<(String, String)>,
+}
+
+impl TxFilter {
+    pub fn parse<S: AsRef<str>>(filters: &[S]) -> Result<Self, Box<dyn Error>> {
+        let mut conditions = Vec::new();
+        for filter in filters {
+            let filter = filter.as_ref();
+            let (key, value) = filter
+                .split_once('=')
+                .ok_or_else(|| format!("Filter must be key=value: {}", filter))?;
+            if !KEYS.contains(&format!("{}=", key).as_str()) {
+                return Err(format!("Unknown filter: {}", key).into());
+            }
+            conditions.push((key.to_string(), value.to_ascii_lowercase()));
+        }
+        Ok(TxFilter { conditions })
+    }
+
+    /// Whether the filter has no conditions (and so matches everything).
+    pub fn is_empty(&self) -> bool {
+        self.conditions.is_empty()
+    }
+
+    /// Add the conditions of another filter.
+    pub fn extend(&mut self, other: TxFilter) {
+        self.conditions.extend(other.conditions);
+    }
+
+    pub fn matches(&self, entry: &TxEntry) -> bool {
+        self.conditions.iter().all(|(key, value)| match key.as_str() {
+            "from" => entry.account.eq_ignore_ascii_case(value),
+            "to" => tx_to(entry.tx).as_deref() == Some(value.as_str()),
+            "nonce" => entry.nonce == value,
+            "section" => entry.section == value,
+            _ => rlp::tx_type(entry.tx).to_string() == *value,
+        })
+    }
+}
+
+/// Lowercase recipient of a transaction; `None` for contract creations.
+pub fn tx_to(tx: &Value) -> Option<String> {
+    find_field(tx, "to")
+        .and_then(Value::as_str)
+        .filter(|to| to.starts_with("0x"))
+        .map(str::to_ascii_lowercase)
+}
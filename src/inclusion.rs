@@ -0,0 +1,166 @@
+use serde_json::{Value, json};
+use std::collections::HashMap;
+use std::error::Error;
+use std::fs;
+
+use crate::fees::{self, FeeCaps};
+use crate::latency::{self, Sample};
+use crate::quantity::parse_u128;
+use crate::rpc::RpcClient;
+use crate::snapshot::{self, find_field};
+use crate::time::rfc3339;
+
+/// Hash of a transaction entry, lowercased for comparisons.
+fn tx_hash(tx: &Value) -> Option<String> {
+    find_field(tx, "hash")
+        .and_then(Value::as_str)
+        .map(str::to_ascii_lowercase)
+}
+
+/// Block number of a mined transaction, or `None` while it is unknown to the chain.
+fn receipt_block(client: &RpcClient, hash: &str) -> Result<Option<u64>, Box<dyn Error>> {
+    let receipt = client.call("eth_getTransactionReceipt", json!([hash]))?;
+    Ok(receipt
+        .get("blockNumber")
+        .and_then(Value::as_str)
+        .and_then(|n| u64::from_str_radix(n.trim_start_matches("0x"), 16).ok()))
+}
+
+/// Timestamp and base fee of a block, fetched once per block number.
+fn block_info(
+    client: &RpcClient,
+    cache: &mut HashMap<u64, (u64, Option<u128>)>,
+    number: u64,
+) -> Result<(u64, Option<u128>), Box<dyn Error>> {
+    if let Some(info) = cache.get(&number) {
+        return Ok(*info);
+    }
+    let block = client.call("eth_getBlockByNumber", json!([format!("0x{:x}", number), false]))?;
+    let timestamp = block
+        .get("timestamp")
+        .and_then(Value::as_str)
+        .and_then(parse_u128)
+        .and_then(|ts| u64::try_from(ts).ok())
+        .ok_or_else(|| format!("Block {} has no timestamp", number))?;
+    let base_fee = block.get("baseFeePerGas").and_then(Value::as_str).and_then(parse_u128);
+    cache.insert(number, (timestamp, base_fee));
+    Ok((timestamp, base_fee))
+}
+
+/// Compare the snapshot at `previous_path` with the current one.
+///
+/// Every current transaction gets a `first_seen` (unix seconds) carried over
+/// from the previous snapshot, or `now` if it is new. Previous transactions
+/// that left the pool are looked up by receipt and annotated in the previous
+/// file with `included_in_block` (plus the block's `included_at` timestamp
+/// and the `inclusion_latency` in seconds) or `dropped_at`; the counts and a
+/// per-fee-bucket latency report go to both snapshots' `metadata.inclusion`.
+pub fn track(
+    snapshot: &mut Value,
+    previous_path: &str,
+    client: &RpcClient,
+    now: u64,
+) -> Result<(), Box<dyn Error>> {
+    let mut previous: Value = serde_json::from_str(&fs::read_to_string(previous_path)?)?;
+    let captured_at = snapshot::captured_at(&previous);
+
+    let mut first_seen: HashMap<String, u64> = HashMap::new();
+    for entry in snapshot::transactions(&previous) {
+        if let Some(hash) = tx_hash(entry.tx) {
+            let seen = entry.tx.get("first_seen").and_then(Value::as_u64).or(captured_at);
+            first_seen.insert(hash, seen.unwrap_or(now));
+        }
+    }
+
+    let mut still_pending = 0usize;
+    for tx in snapshot::transactions_mut(snapshot) {
+        let seen = match tx_hash(tx).and_then(|hash| first_seen.remove(&hash)) {
+            Some(seen) => {
+                still_pending += 1;
+                seen
+            }
+            None => now,
+        };
+        if let Some(tx) = tx.as_object_mut() {
+            tx.insert("first_seen".to_string(), json!(seen));
+        }
+    }
+
+    // Whatever is left in `first_seen` was in the previous pool but not this one.
+    let (mut included, mut dropped) = (0usize, 0usize);
+    let mut blocks = HashMap::new();
+    let mut samples = Vec::new();
+    for tx in snapshot::transactions_mut(&mut previous) {
+        let Some(hash) = tx_hash(tx) else {
+            continue;
+        };
+        if !first_seen.contains_key(&hash) || tx.get("included_in_block").is_some() {
+            continue;
+        }
+        let fee_caps = FeeCaps::of(tx);
+        let seen = tx.get("first_seen").and_then(Value::as_u64).or(captured_at);
+        let Some(tx) = tx.as_object_mut() else {
+            continue;
+        };
+        match receipt_block(client, &hash)? {
+            Some(block) => {
+                included += 1;
+                let (included_at, base_fee) = block_info(client, &mut blocks, block)?;
+                tx.insert("included_in_block".to_string(), json!(block));
+                tx.insert("included_at".to_string(), json!(included_at));
+                if let (Some(seen), Some(fee_caps)) = (seen, fee_caps) {
+                    let seconds = included_at.saturating_sub(seen);
+                    tx.insert("inclusion_latency".to_string(), json!(seconds));
+                    samples.push(Sample {
+                        fee_bucket: fees::fee_bucket(fee_caps.effective_priority_fee(base_fee)),
+                        seconds,
+                    });
+                }
+            }
+            None => {
+                dropped += 1;
+                tx.entry("dropped_at").or_insert(json!(now));
+            }
+        }
+    }
+
+    let left = included + dropped;
+    let inclusion_rate = if left == 0 { 0.0 } else { included as f64 / left as f64 };
+    emit::emit!(
+        "{metric_agg} of {metric_name} is {metric_value}",
+        evt_kind: "metric",
+        metric_agg: "count",
+        metric_name: "txpool.inclusion.included",
+        metric_value: included,
+    );
+    emit::emit!(
+        "{metric_agg} of {metric_name} is {metric_value}",
+        evt_kind: "metric",
+        metric_agg: "count",
+        metric_name: "txpool.inclusion.dropped",
+        metric_value: dropped,
+    );
+    emit::emit!(
+        "{metric_agg} of {metric_name} is {metric_value}",
+        evt_kind: "metric",
+        metric_agg: "last",
+        metric_name: "txpool.inclusion.rate",
+        metric_value: inclusion_rate,
+    );
+
+    let summary = json!({
+        "checked_at": rfc3339(now),
+        "included": included,
+        "dropped": dropped,
+        "still_pending": still_pending,
+        "inclusion_rate": inclusion_rate,
+        "latency": latency::report(&samples),
+    });
+    snapshot::metadata_mut(&mut previous).insert("inclusion".to_string(), summary.clone());
+    fs::write(previous_path, serde_json::to_string_pretty(&previous)?)?;
+
+    let mut summary = summary;
+    summary["previous"] = json!(previous_path);
+    snapshot::metadata_mut(snapshot).insert("inclusion".to_string(), summary);
+    Ok(())
+}
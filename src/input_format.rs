@@ -0,0 +1,78 @@
+use regex::Regex;
+use std::error::Error;
+
+/// How much of the input auto-detection looks at. The type name of a debug
+/// dump is its first token, so this only has to cover leading log noise.
+const DETECT_WINDOW: usize = 4096;
+
+/// Format of the data read from stdin.
+#[derive(Clone, Copy, Default, PartialEq)]
+pub enum InputFormat {
+    /// Detect the format from the leading tokens.
+    #[default]
+    Auto,
+    /// `TxpoolContent { .. }` debug dump.
+    Content,
+    /// `TxpoolInspect { .. }` debug dump.
+    Inspect,
+    /// `TxpoolStatus { .. }` debug dump: counts only, no transactions.
+    Status,
+    /// geth `txpool_content` JSON: the result, or a whole JSON-RPC response.
+    GethJson,
+}
+
+impl InputFormat {
+    pub fn parse(name: &str) -> Result<Self, Box<dyn Error>> {
+        match name {
+            "auto" => Ok(InputFormat::Auto),
+            "content" => Ok(InputFormat::Content),
+            "inspect" => Ok(InputFormat::Inspect),
+            "status" => Ok(InputFormat::Status),
+            "geth-json" => Ok(InputFormat::GethJson),
+            other => Err(format!("Unknown format: {} (content, inspect, status, geth-json, auto)", other).into()),
+        }
+    }
+
+    /// The forced format, or for `Auto` the one detected from the start of
+    /// `input` (`Auto` again when nothing matched).
+    pub fn resolve(self, input: &str) -> Self {
+        match self {
+            InputFormat::Auto => detect(input),
+            forced => forced,
+        }
+    }
+
+    /// Whether this is a pretty-printed debug dump of transactions, which
+    /// truncation and `--validate` work on.
+    pub fn is_debug_dump(self) -> bool {
+        matches!(self, InputFormat::Content | InputFormat::Inspect)
+    }
+}
+
+/// The first `DETECT_WINDOW` bytes of `input`, cut at a character boundary.
+fn window(input: &str) -> &str {
+    let mut end = input.len().min(DETECT_WINDOW);
+    while !input.is_char_boundary(end) {
+        end -= 1;
+    }
+    &input[..end]
+}
+
+/// Format named by the first `Txpool* {` type token near the start of the
+/// input, so detection costs the same on a 1 KB and a 1 GB dump. Input that
+/// starts as a JSON object is taken to be `txpool_content` JSON (e.g. saved
+/// from curl); debug dumps start with their type name instead.
+fn detect(input: &str) -> InputFormat {
+    if input.trim_start().starts_with('{') {
+        return InputFormat::GethJson;
+    }
+    let Ok(type_token) = Regex::new(r"\b(TxpoolContent|TxpoolInspect|TxpoolStatus)\s*\{") else {
+        return InputFormat::Auto;
+    };
+    match type_token.captures(window(input)).as_ref().map(|caps| &caps[1]) {
+        Some("TxpoolContent") => InputFormat::Content,
+        Some("TxpoolInspect") => InputFormat::Inspect,
+        Some("TxpoolStatus") => InputFormat::Status,
+        _ => InputFormat::Auto,
+    }
+}
@@ -0,0 +1,52 @@
+use serde_json::Value;
+use std::fmt::Write;
+
+use crate::quantity::to_u128;
+use crate::snapshot::{SECTIONS, find_field};
+
+/// geth's `txpool_inspect` summary of one transaction: recipient (or
+/// `contract creation`), value, gas limit and the price bid per gas (the fee
+/// cap, or the gas price for legacy transactions).
+fn summary(tx: &Value) -> String {
+    let amount = |keys: &[&str]| {
+        keys.iter()
+            .find_map(|key| find_field(tx, key).and_then(to_u128))
+            .map_or_else(|| "?".to_string(), |amount| amount.to_string())
+    };
+    let to = match find_field(tx, "to") {
+        Some(Value::String(to)) if to.starts_with("0x") => to.to_ascii_lowercase(),
+        Some(Value::String(_)) | Some(Value::Null) => "contract creation".to_string(),
+        _ => "?".to_string(),
+    };
+    format!(
+        "{}: {} wei + {} gas × {} wei",
+        to,
+        amount(&["value"]),
+        amount(&["gas_limit", "gas"]),
+        amount(&["max_fee_per_gas", "gas_price"])
+    )
+}
+
+/// Render any snapshot as `txpool_inspect`-style text: each section, its
+/// senders, and one summary line per nonce in nonce order.
+pub fn render(snapshot: &Value) -> String {
+    let mut text = String::new();
+    for section in SECTIONS {
+        let Some(accounts) = snapshot[section].as_object() else {
+            continue;
+        };
+        let _ = writeln!(text, "{}:", section);
+        for (account, nonces) in accounts {
+            let Some(nonces) = nonces.as_object() else {
+                continue;
+            };
+            let _ = writeln!(text, "  {}:", account.to_ascii_lowercase());
+            let mut entries: Vec<(&String, &Value)> = nonces.iter().collect();
+            entries.sort_by_key(|(nonce, _)| nonce.parse::<u64>().unwrap_or(u64::MAX));
+            for (nonce, tx) in entries {
+                let _ = writeln!(text, "    {}: {}", nonce, summary(tx));
+            }
+        }
+    }
+    text
+}
@@ -0,0 +1,332 @@
+use serde_json::{Map, Value};
+use std::error::Error;
+use std::panic::{self, AssertUnwindSafe};
+
+use crate::snapshot::SECTIONS;
+use crate::truncate::{account_header, indent};
+use crate::warnings::{ParseWarning, WarningKind};
+
+/// Accounts parsed together when a dump is retried in parts; a part that
+/// fails is retried one account at a time.
+const CHUNK_ACCOUNTS: usize = 256;
+
+type Parsed = Result<(Value, Vec<ParseWarning>), Box<dyn Error>>;
+
+/// One account block of a pretty-printed debug dump.
+struct Account<'a> {
+    section: &'static str,
+    address: String,
+    /// 1-based line of the account header.
+    line: usize,
+    text: &'a str,
+}
+
+/// Run `parse` and turn a panic into an error.
+fn contained<F>(parse: F) -> Parsed
+where
+    F: FnOnce() -> Parsed,
+{
+    panic::catch_unwind(AssertUnwindSafe(parse)).unwrap_or_else(|payload| {
+        let message = payload
+            .downcast_ref::<&str>()
+            .map(|message| message.to_string())
+            .or_else(|| payload.downcast_ref::<String>().cloned())
+            .unwrap_or_else(|| "unknown panic".to_string());
+        Err(format!("parser panicked: {}", message).into())
+    })
+}
+
+/// Split a content or inspect dump into its account blocks, each from its
+/// header to the line closing it.
+fn accounts(input: &str) -> Vec<Account<'_>> {
+    let lines: Vec<(usize, &str)> = input
+        .split_inclusive('\n')
+        .scan(0, |offset, line| {
+            let start = *offset;
+            *offset += line.len();
+            Some((start, line))
+        })
+        .collect();
+    let mut accounts = Vec::new();
+    let mut section = None;
+    let mut i = 0;
+    while i < lines.len() {
+        let (start, line) = lines[i];
+        let trimmed = line.trim();
+        if let Some(name) = SECTIONS.iter().find(|name| trimmed == format!("{}: {{", name)) {
+            section = Some(*name);
+        }
+        let (Some(section), Some(address)) = (section, account_header(line)) else {
+            i += 1;
+            continue;
+        };
+        let depth = indent(line);
+        let end = (i + 1..lines.len())
+            .find(|&j| !lines[j].1.trim().is_empty() && indent(lines[j].1) <= depth)
+            .unwrap_or(lines.len() - 1);
+        let (end_start, end_line) = lines[end];
+        accounts.push(Account {
+            section,
+            address: format!("0x{}", address),
+            line: i + 1,
+            text: &input[start..end_start + end_line.len()],
+        });
+        i = end + 1;
+    }
+    accounts
+}
+
+/// A dump holding only `accounts` (all of one section), under the same
+/// top-level wrapper as `input`.
+fn dump_of(input: &str, accounts: &[Account]) -> String {
+    let wrapper = input.lines().find(|line| !line.trim().is_empty()).unwrap_or("{");
+    let mut dump = format!("{}\n    {}: {{\n", wrapper.trim_end(), accounts[0].section);
+    for account in accounts {
+        dump.push_str(account.text);
+        if !account.text.ends_with('\n') {
+            dump.push('\n');
+        }
+    }
+    dump.push_str("    },\n}\n");
+    dump
+}
+
+/// Add the sections of a parsed part to the snapshot being assembled.
+pub fn merge(snapshot: &mut Map<String, Value>, part: Value) {
+    let Value::Object(part) = part else {
+        return;
+    };
+    for (section, accounts) in part {
+        match (snapshot.get_mut(&section), accounts) {
+            (Some(Value::Object(merged)), Value::Object(accounts)) => merged.extend(accounts),
+            (_, accounts) => {
+                snapshot.insert(section, accounts);
+            }
+        }
+    }
+}
+
+/// Consecutive accounts of one section, at most 256 of them.
+fn parts<'a, 'b>(accounts: &'b [Account<'a>]) -> Vec<&'b [Account<'a>]> {
+    let mut parts = Vec::new();
+    let mut rest = accounts;
+    while !rest.is_empty() {
+        let same_section = rest.iter().take_while(|account| account.section == rest[0].section).count();
+        let (part, next) = rest.split_at(same_section.min(CHUNK_ACCOUNTS));
+        parts.push(part);
+        rest = next;
+    }
+    parts
+}
+
+/// One part parsed by [`parse_part`].
+pub struct Part {
+    pub snapshot: Map<String, Value>,
+    /// The parser's warnings, then one `account_failed` per account left out.
+    pub warnings: Vec<ParseWarning>,
+    pub accounts: usize,
+    pub parsed_accounts: usize,
+}
+
+/// Parse the accounts of `part` together, and one at a time if that fails.
+/// Accounts that still fail are left out, logged with their address and
+/// reported as `account_failed` warnings.
+fn parse_part<F>(input: &str, part: &[Account], parse: &F) -> Part
+where
+    F: Fn(&str, bool) -> Parsed,
+{
+    let retries = match contained(|| parse(&dump_of(input, part), false)) {
+        Ok(parsed) => vec![(part, Ok(parsed))],
+        Err(err) if part.len() == 1 => vec![(part, Err(err))],
+        Err(_) => part
+            .chunks(1)
+            .map(|account| (account, contained(|| parse(&dump_of(input, account), false))))
+            .collect(),
+    };
+    let mut parsed = Part { snapshot: Map::new(), warnings: Vec::new(), accounts: part.len(), parsed_accounts: 0 };
+    let mut failed = Vec::new();
+    for (accounts, result) in retries {
+        match result {
+            Ok((value, warnings)) => {
+                merge(&mut parsed.snapshot, value);
+                parsed.warnings.extend(warnings);
+                parsed.parsed_accounts += accounts.len();
+            }
+            Err(err) => failed.push((&accounts[0], err.to_string())),
+        }
+    }
+    for (account, error) in failed {
+        let address = &account.address;
+        emit::error!("Skipped account {address}: {error}", address, error);
+        eprintln!("Skipped account {}: {}", address, error);
+        let location = format!("{} {}, line {}", account.section, address, account.line);
+        parsed.warnings.push(ParseWarning::new(WarningKind::AccountFailed, location, &error));
+    }
+    parsed
+}
+
+fn report_failed(accounts: usize) {
+    emit::emit!(
+        "{metric_agg} of {metric_name} is {metric_value}",
+        evt_kind: "metric",
+        metric_agg: "count",
+        metric_name: "txpool.parse.failed_accounts",
+        metric_value: accounts,
+    );
+}
+
+/// Parse a content or inspect dump with `parse`, keeping one malformed
+/// account from failing the whole run.
+///
+/// The dump is first parsed as a whole. If that fails or panics, it is
+/// parsed again in parts of up to 256 accounts, and a failing part one
+/// account at a time. Accounts that still fail are left out, logged with
+/// their address and reported as `account_failed` warnings. The original
+/// error is returned when no account parses. `parse` is told whether it
+/// gets the whole dump, so only the first failure leaves debug files behind.
+pub fn parse<F>(input: &str, parse: F) -> Parsed
+where
+    F: Fn(&str, bool) -> Parsed,
+{
+    let whole_err = match contained(|| parse(input, true)) {
+        Ok(parsed) => return Ok(parsed),
+        Err(err) => err,
+    };
+    let accounts = accounts(input);
+    if accounts.is_empty() {
+        return Err(whole_err);
+    }
+    let whole_error = whole_err.to_string();
+    emit::warn!("Dump did not parse as a whole ({whole_error}); parsing it account by account", whole_error);
+    eprintln!("Dump did not parse as a whole ({}); parsing it account by account", whole_error);
+
+    let mut snapshot = Map::new();
+    let mut warnings = Vec::new();
+    let mut parsed_accounts = 0;
+    for part in parts(&accounts) {
+        let part = parse_part(input, part, &parse);
+        merge(&mut snapshot, Value::Object(part.snapshot));
+        warnings.extend(part.warnings);
+        parsed_accounts += part.parsed_accounts;
+    }
+    if parsed_accounts == 0 {
+        return Err(whole_err);
+    }
+    report_failed(accounts.len() - parsed_accounts);
+    Ok((Value::Object(snapshot), warnings))
+}
+
+/// Parse a content or inspect dump in parts of up to 256 accounts, isolated
+/// as in [`parse`], and hand each part to `done` as soon as it is parsed, so
+/// a caller with a deadline can keep the accounts parsed before it. Stops
+/// when `done` returns false. A dump with no account blocks is parsed whole
+/// as one part.
+pub fn parse_streamed<F>(input: &str, parse: F, mut done: impl FnMut(Result<Part, String>) -> bool)
+where
+    F: Fn(&str, bool) -> Parsed,
+{
+    let accounts = accounts(input);
+    if accounts.is_empty() {
+        let whole = contained(|| parse(input, true)).map(|(value, warnings)| Part {
+            snapshot: match value {
+                Value::Object(snapshot) => snapshot,
+                _ => Map::new(),
+            },
+            warnings,
+            accounts: 0,
+            parsed_accounts: 0,
+        });
+        done(whole.map_err(|err| err.to_string()));
+        return;
+    }
+    for part in parts(&accounts) {
+        let part = parse_part(input, part, &parse);
+        if part.parsed_accounts < part.accounts {
+            report_failed(part.accounts - part.parsed_accounts);
+        }
+        if !done(Ok(part)) {
+            return;
+        }
+    }
+}
+
+/// Number of account blocks in a content or inspect dump.
+pub fn account_count(input: &str) -> usize {
+    accounts(input).len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::unknown_fields::UnknownFields;
+    use crate::warnings;
+
+    /// Three accounts; the middle one has a value that is not a number.
+    const DUMP: &str = "TxpoolContent {
+    pending: {
+        0x1111111111111111111111111111111111111111: {
+            \"0\": Transaction {
+                nonce: 0,
+                value: 1000,
+            },
+        },
+        0x2222222222222222222222222222222222222222: {
+            \"0\": Transaction {
+                nonce: 0,
+                value: 1000 ]] oops,
+            },
+        },
+        0x3333333333333333333333333333333333333333: {
+            \"7\": Transaction {
+                nonce: 7,
+                value: 5,
+            },
+        },
+    },
+    queued: {},
+}
+";
+
+    fn content(dump: &str, _: bool) -> Parsed {
+        crate::parse_txpool_content(dump, UnknownFields::Preserve, None)
+    }
+
+    #[test]
+    fn malformed_account_becomes_a_warning() {
+        let (mut snapshot, parse_warnings) = parse(DUMP, content).unwrap();
+        let pending = snapshot["pending"].as_object().unwrap();
+        let accounts: Vec<&str> = pending.keys().map(String::as_str).collect();
+        assert_eq!(
+            accounts,
+            ["0x1111111111111111111111111111111111111111", "0x3333333333333333333333333333333333333333"]
+        );
+
+        warnings::record(&mut snapshot, &parse_warnings);
+        let recorded = &snapshot["metadata"]["parse_warnings"];
+        assert_eq!(recorded["count"], 1);
+        assert_eq!(recorded["kinds"]["account_failed"], 1);
+        assert_eq!(
+            recorded["items"][0]["location"],
+            "pending 0x2222222222222222222222222222222222222222, line 9"
+        );
+    }
+
+    #[test]
+    fn panic_is_contained_to_its_account() {
+        let (snapshot, parse_warnings) = parse(DUMP.replace(" ]] oops", "").as_str(), |dump, whole| {
+            if dump.contains("0x3333") {
+                panic!("boom");
+            }
+            content(dump, whole)
+        })
+        .unwrap();
+        assert_eq!(snapshot["pending"].as_object().unwrap().len(), 2);
+        assert_eq!(parse_warnings.len(), 1);
+        assert!(parse_warnings[0].raw.contains("parser panicked: boom"));
+    }
+
+    #[test]
+    fn no_account_parses() {
+        assert!(parse(DUMP, |_, _| Err("broken".into())).is_err());
+    }
+}
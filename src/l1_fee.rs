@@ -0,0 +1,227 @@
+use serde_json::{Value, json};
+use std::error::Error;
+
+use crate::quantity::to_json;
+use crate::rlp;
+use crate::rpc::RpcClient;
+use crate::snapshot;
+
+/// OP-stack `GasPriceOracle` predeploy.
+const GAS_PRICE_ORACLE: &str = "0x420000000000000000000000000000000000000F";
+
+// Fjord cost function constants, scaled by 1e6.
+const L1_COST_INTERCEPT: i128 = -42_585_600;
+const L1_COST_FASTLZ_COEF: i128 = 836_500;
+const MIN_TRANSACTION_SIZE: i128 = 100;
+
+/// L1 fee parameters as exposed by the `GasPriceOracle` after Ecotone.
+#[derive(Clone, Default)]
+pub struct L1FeeParams {
+    pub l1_base_fee: Option<u128>,
+    pub l1_blob_base_fee: Option<u128>,
+    pub base_fee_scalar: Option<u128>,
+    pub blob_base_fee_scalar: Option<u128>,
+}
+
+impl L1FeeParams {
+    /// Fill any parameter not given on the command line from the oracle.
+    pub fn fill_from_rpc(&mut self, client: &RpcClient) -> Result<(), Box<dyn Error>> {
+        let calls = [
+            (&mut self.l1_base_fee, "0x519b4bd3"),          // l1BaseFee()
+            (&mut self.l1_blob_base_fee, "0xf8206140"),     // blobBaseFee()
+            (&mut self.base_fee_scalar, "0xc5985918"),      // baseFeeScalar()
+            (&mut self.blob_base_fee_scalar, "0x68d5dca6"), // blobBaseFeeScalar()
+        ];
+        for (param, selector) in calls {
+            if param.is_none() {
+                *param = Some(client.call_uint(GAS_PRICE_ORACLE, selector)?);
+            }
+        }
+        Ok(())
+    }
+
+    fn scaled_fee(&self) -> Result<u128, Box<dyn Error>> {
+        let missing = |name: &str| format!("Missing L1 fee parameter {} (pass the flag or --rpc-url)", name);
+        let l1_base_fee = self.l1_base_fee.ok_or_else(|| missing("--l1-base-fee"))?;
+        let l1_blob_base_fee = self.l1_blob_base_fee.ok_or_else(|| missing("--l1-blob-base-fee"))?;
+        let base_fee_scalar = self.base_fee_scalar.ok_or_else(|| missing("--l1-base-fee-scalar"))?;
+        let blob_base_fee_scalar = self
+            .blob_base_fee_scalar
+            .ok_or_else(|| missing("--l1-blob-base-fee-scalar"))?;
+
+        base_fee_scalar
+            .checked_mul(l1_base_fee)
+            .and_then(|v| v.checked_mul(16))
+            .and_then(|v| v.checked_add(blob_base_fee_scalar.checked_mul(l1_blob_base_fee)?))
+            .ok_or_else(|| "L1 fee parameters overflow".into())
+    }
+}
+
+/// Estimate the Fjord L1 data fee of a signed transaction in wei.
+pub fn estimate(signed_tx: &[u8], scaled_fee: u128) -> u128 {
+    let fastlz_size = flz_compress_len(signed_tx) as i128 + 68;
+    let estimated_size =
+        (L1_COST_INTERCEPT + L1_COST_FASTLZ_COEF * fastlz_size).max(MIN_TRANSACTION_SIZE * 1_000_000);
+    (estimated_size as u128).saturating_mul(scaled_fee) / 1_000_000_000_000
+}
+
+/// Add an `l1_fee_estimate` (wei) to every content-format transaction and record
+/// the parameters and pool total in the snapshot metadata.
+pub fn annotate(snapshot: &mut Value, params: &L1FeeParams) -> Result<(), Box<dyn Error>> {
+    let scaled_fee = params.scaled_fee()?;
+    let mut total: u128 = 0;
+    let mut estimated = 0usize;
+
+    for tx in snapshot::transactions_mut(snapshot) {
+        let Some(signed) = rlp::encode_signed(tx) else {
+            continue;
+        };
+        let fee = estimate(&signed, scaled_fee);
+        total = total.saturating_add(fee);
+        estimated += 1;
+        if let Some(tx) = tx.as_object_mut() {
+            tx.insert("l1_fee_estimate".to_string(), to_json(fee));
+        }
+    }
+
+    emit::emit!(
+        "{metric_agg} of {metric_name} is {metric_value}",
+        evt_kind: "metric",
+        metric_agg: "last",
+        metric_name: "txpool.l1_fee.total_wei",
+        metric_value: total as f64,
+        estimated_txs: estimated,
+    );
+
+    let metadata = snapshot::metadata_mut(snapshot);
+    metadata.insert(
+        "l1_fee".to_string(),
+        json!({
+            "model": "fjord",
+            "l1_base_fee": params.l1_base_fee.map(to_json),
+            "l1_blob_base_fee": params.l1_blob_base_fee.map(to_json),
+            "base_fee_scalar": params.base_fee_scalar.map(to_json),
+            "blob_base_fee_scalar": params.blob_base_fee_scalar.map(to_json),
+            "total_estimate": to_json(total),
+        }),
+    );
+    Ok(())
+}
+
+/// Length of the FastLZ (level 1) compression of `input`, as computed by the
+/// Fjord `GasPriceOracle` (`LibZip.flzCompress` length without materializing
+/// the output).
+fn flz_compress_len(input: &[u8]) -> u32 {
+    let mut n: u32 = 0;
+    let mut table = vec![0u32; 8192];
+    let u24 = |i: u32| -> u32 {
+        let i = i as usize;
+        u32::from(input[i]) | (u32::from(input[i + 1]) << 8) | (u32::from(input[i + 2]) << 16)
+    };
+    let hash = |v: u32| -> u32 { (2_654_435_769u32.wrapping_mul(v) >> 19) & 0x1fff };
+    let literals = |n: &mut u32, mut r: u32| {
+        *n += 0x21 * (r / 0x20);
+        r %= 0x20;
+        if r != 0 {
+            *n += r + 1;
+        }
+    };
+
+    let len = input.len() as u32;
+    let ip_limit = len.saturating_sub(13);
+    let mut anchor: u32 = 0;
+    let mut ip: u32 = 2;
+
+    while ip < ip_limit {
+        let mut reference;
+        loop {
+            let seq = u24(ip);
+            let h = hash(seq) as usize;
+            reference = table[h];
+            table[h] = ip;
+            let distance = ip.wrapping_sub(reference);
+            if ip >= ip_limit {
+                break;
+            }
+            ip += 1;
+            if distance <= 0x1fff && seq == u24(reference) {
+                break;
+            }
+        }
+        if ip >= ip_limit {
+            break;
+        }
+        ip -= 1;
+        if ip > anchor {
+            literals(&mut n, ip - anchor);
+        }
+
+        // Match length, mirroring the reference implementation's off-by-one.
+        let (p, q, end) = (reference + 3, ip + 3, ip_limit + 9);
+        let mut l: u32 = 0;
+        let mut e = end - q;
+        while l < e {
+            if input[(p + l) as usize] != input[(q + l) as usize] {
+                e = 0;
+            }
+            l += 1;
+        }
+
+        let m = l - 1;
+        n += 3 * (m / 262);
+        n += if m % 262 >= 6 { 3 } else { 2 };
+
+        ip += l;
+        for _ in 0..2 {
+            table[hash(u24(ip)) as usize] = ip;
+            ip += 1;
+        }
+        anchor = ip;
+    }
+
+    literals(&mut n, len - anchor);
+    n
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Parameters of op-geth's Fjord cost function tests.
+    fn params() -> L1FeeParams {
+        L1FeeParams {
+            l1_base_fee: Some(1_000_000_000),
+            l1_blob_base_fee: Some(10_000_000),
+            base_fee_scalar: Some(2),
+            blob_base_fee_scalar: Some(3),
+        }
+    }
+
+    #[test]
+    fn flz_compress_len_matches_reference() {
+        assert_eq!(flz_compress_len(&[]), 0);
+        assert_eq!(flz_compress_len(&[1]), 2);
+        assert_eq!(flz_compress_len(&[1; 1000]), 21);
+        assert_eq!(flz_compress_len(&[0; 1000]), 21);
+        // No 3-byte sequence repeats: 6 full literal runs of 32 and one of 8
+        let distinct: Vec<u8> = (0..200).collect();
+        assert_eq!(flz_compress_len(&distinct), 207);
+    }
+
+    #[test]
+    fn fjord_fee() {
+        let scaled_fee = params().scaled_fee().unwrap();
+        assert_eq!(scaled_fee, 2 * 1_000_000_000 * 16 + 3 * 10_000_000);
+        // Small transactions are billed at the 100-byte minimum
+        assert_eq!(estimate(&[1; 1000], scaled_fee), 3_203_000);
+        // (-42.5856 + 0.8365 * (207 + 68)) * 32030e6 / 1e6
+        let distinct: Vec<u8> = (0..200).collect();
+        assert_eq!(estimate(&distinct, scaled_fee), 6_004_084);
+    }
+
+    #[test]
+    fn missing_parameter() {
+        let params = L1FeeParams { l1_base_fee: None, ..params() };
+        assert!(params.scaled_fee().unwrap_err().to_string().contains("--l1-base-fee"));
+    }
+}
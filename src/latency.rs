@@ -0,0 +1,51 @@
+use serde_json::{Map, Value, json};
+use std::collections::BTreeMap;
+
+use crate::stats::percentile;
+
+/// Time-to-inclusion of one mined transaction.
+pub struct Sample {
+    pub fee_bucket: &'static str,
+    pub seconds: u64,
+}
+
+/// Per-fee-bucket distribution of first-seen-to-mined latencies, emitted as
+/// `txpool.inclusion.latency_seconds` percentiles and returned as a report.
+pub fn report(samples: &[Sample]) -> Value {
+    let mut buckets: BTreeMap<&str, Vec<u64>> = BTreeMap::new();
+    for sample in samples {
+        buckets.entry(sample.fee_bucket).or_default().push(sample.seconds);
+    }
+
+    let mut report = Map::new();
+    for (bucket, mut seconds) in buckets {
+        seconds.sort_unstable();
+        let count = seconds.len();
+        let mean = seconds.iter().sum::<u64>() as f64 / count as f64;
+        for (quantile, p) in [("p50", 50.0), ("p90", 90.0), ("p99", 99.0)] {
+            let metric_value = percentile(&seconds, p).unwrap_or(0);
+            emit::emit!(
+                "{metric_agg} of {metric_name} is {metric_value}",
+                evt_kind: "metric",
+                metric_agg: "last",
+                metric_name: "txpool.inclusion.latency_seconds",
+                metric_value,
+                fee_bucket: bucket,
+                quantile,
+            );
+        }
+        report.insert(
+            bucket.to_string(),
+            json!({
+                "count": count,
+                "mean": mean,
+                "min": seconds.first(),
+                "p50": percentile(&seconds, 50.0),
+                "p90": percentile(&seconds, 90.0),
+                "p99": percentile(&seconds, 99.0),
+                "max": seconds.last(),
+            }),
+        );
+    }
+    Value::Object(report)
+}
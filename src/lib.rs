@@ -0,0 +1,1071 @@
+// The snapshot JSON Schema is one `json!` literal, deeper than the default limit
+#![recursion_limit = "256"]
+
+use regex::Regex;
+use serde_json::{Value, json};
+use std::io::{self, IsTerminal};
+use std::time::{SystemTime, UNIX_EPOCH, Instant};
+use std::borrow::Cow;
+use std::collections::{BTreeSet, HashMap};
+use std::sync::Arc;
+
+mod account_gas;
+mod activity;
+mod alerts;
+mod arbitrum;
+mod avro;
+mod bench;
+mod bigquery;
+mod blob;
+mod budget;
+mod builder;
+mod bump;
+mod cbor;
+mod compare;
+mod chain;
+pub mod cli;
+mod clusters;
+mod creation;
+mod demand;
+mod deps;
+mod dune;
+mod encryption;
+mod entities;
+mod eviction;
+mod export;
+mod fees;
+mod filter;
+mod inclusion;
+mod input_format;
+mod inspect_text;
+mod isolate;
+mod keccak;
+mod l1_fee;
+mod latency;
+mod manifest;
+mod movement;
+mod multinode;
+mod node;
+mod normalize;
+mod output;
+mod paste;
+mod pool_age;
+mod projection;
+mod protobuf;
+mod quantity;
+mod raw_batch;
+mod rename;
+mod repl;
+mod replay;
+mod reth;
+mod rlp;
+mod rpc;
+mod rpc_content;
+mod schema;
+mod secp256k1;
+mod shape;
+mod size;
+pub mod sinks;
+mod snapshot;
+pub mod snapshotter;
+mod spam;
+mod state;
+mod stats;
+mod summary;
+mod synth;
+mod table;
+mod time;
+mod truncate;
+mod unknown_fields;
+mod validate;
+mod value_band;
+pub mod warnings;
+mod watch;
+
+use cli::{Command, ConvertOptions};
+use encryption::Encryption;
+use input_format::InputFormat;
+use output::OutputFormat;
+use sinks::Sink;
+use unknown_fields::UnknownFields;
+use rpc::RpcClient;
+use warnings::{ParseWarning, WarningKind};
+
+/// Run a parsed command line. The binary sets up telemetry around this.
+pub fn run(command: Command) -> Result<(), Box<dyn std::error::Error>> {
+    match command {
+        Command::Convert(mut options) => convert(&mut options),
+        Command::Bench { txs, iterations } => bench::run(txs, iterations),
+        Command::Repl { path } => repl::run(&path),
+        Command::Schema { bigquery } => print_schema(bigquery),
+        Command::Replay(options) => replay::run(&options),
+        Command::Verify { manifest, signer } => manifest::verify(&manifest, signer.as_deref()),
+        Command::Compare { paths, output } => compare::run(&paths, output.as_deref()),
+        Command::Generate { spec, format, output } => synth::run(&spec, format, output.as_deref()),
+    }
+}
+
+fn convert(options: &mut ConvertOptions) -> Result<(), Box<dyn std::error::Error>> {
+    if let Some(dir) = options.watch_dir.clone() {
+        return watch::run(&dir, options);
+    }
+    if options.rpc_urls.len() > 1 {
+        return multinode::run(options);
+    }
+    if let Some(path) = options.ipc.clone() {
+        let start_time = Instant::now();
+        let content = RpcClient::ipc(&path)
+            .with_policy(&options.rpc_policy)
+            .call("txpool_content", serde_json::json!([]))?;
+        let snapshot = rpc_content::to_snapshot(&content)?;
+        return convert_snapshot(snapshot, options, None, start_time);
+    }
+    if let Some(path) = options.reth_backup.clone() {
+        let start_time = Instant::now();
+        let snapshot = reth::read_backup(&std::fs::read(&path)?)?;
+        return convert_snapshot(snapshot, options, None, start_time);
+    }
+
+    // A terminal on stdin means nothing was piped in; say so and take a paste
+    // rather than wait silently for input that is not coming
+    let input = if io::stdin().is_terminal() {
+        paste::read(options.max_input_bytes)?
+    } else {
+        budget::read(io::stdin(), options.max_input_bytes)?
+    };
+    convert_dump(input, options, None)
+}
+
+/// Parse one debug dump and hand it to [`convert_snapshot`].
+fn convert_dump(
+    input: budget::Input,
+    options: &mut ConvertOptions,
+    stem: Option<String>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let start_time = Instant::now();
+    
+    emit::info!("Starting txpool parser");
+    
+    let budget::Input { text: input, cut } = input;
+    let bytes_read = input.len();
+    
+    emit::emit!(
+        "{metric_agg} of {metric_name} is {metric_value}",
+        evt_kind: "metric",
+        metric_agg: "count",
+        metric_name: "txpool.input.bytes",
+        metric_value: bytes_read,
+    );
+
+    let format = options.input_format.resolve(&input);
+    if !format.is_debug_dump() && (options.limits.is_set() || options.validate) {
+        return Err("--max-accounts, --max-txs-per-account and --validate only apply to content and inspect dumps".into());
+    }
+
+    let mut truncation = truncate::Truncation::default();
+    let input = match options.max_input_bytes.filter(|_| cut) {
+        Some(max) if format.is_debug_dump() => {
+            emit::warn!("Input is larger than {max} bytes; converting the accounts before the cut", max);
+            eprintln!("Input is larger than --max-input-bytes {}; converting the accounts before the cut", max);
+            truncation.input_cut_at = Some(max);
+            truncate::close_partial(&input)
+        }
+        Some(max) => {
+            return Err(format!(
+                "Input is larger than --max-input-bytes {}; only content and inspect dumps can be cut to a partial snapshot",
+                max
+            )
+            .into());
+        }
+        None => input,
+    };
+    let input = if options.limits.is_set() {
+        let (kept, limited) = truncate::apply(&input, &options.limits);
+        truncation.skipped_accounts = limited.skipped_accounts;
+        truncation.skipped_txs = limited.skipped_txs;
+        kept
+    } else {
+        input
+    };
+
+    // Parsed on a worker when `--timeout` is set, so a pathological dump
+    // cannot hold up a watch or poll loop past its budget. Debug dumps are
+    // parsed in parts, and those done by the deadline are kept.
+    let deadline = budget::Deadline::new(start_time, options.timeout);
+    let unknown_fields = options.unknown_fields;
+    let encryption = options.encryption.clone();
+    let (input, parse_result) = if options.timeout.is_some() && format.is_debug_dump() {
+        parse_until(input, format, unknown_fields, encryption, &deadline, &mut truncation)
+    } else {
+        deadline.run("Parsing", move || {
+            let parsed =
+                parse_debug_format(&input, format, unknown_fields, Some(&encryption)).map_err(|err| err.to_string());
+            (input, parsed)
+        })?
+    };
+    
+    let (mut json_value, parse_warnings) = parse_result?;
+    warnings::record(&mut json_value, &parse_warnings);
+    if options.limits.is_set() || truncation.input_cut_at.is_some() || truncation.parse_timed_out {
+        let (is_truncated, details) = truncation.to_metadata(&options.limits);
+        let metadata = snapshot::metadata_mut(&mut json_value);
+        metadata.insert("truncated".to_string(), is_truncated);
+        metadata.insert("truncation".to_string(), details);
+    }
+
+    if options.validate && truncation.parse_timed_out {
+        emit::warn!("Skipping --validate: the snapshot only holds the accounts parsed before --timeout");
+        eprintln!("Skipping --validate: the snapshot only holds the accounts parsed before --timeout");
+    } else if options.validate {
+        validate::validate(&input, &json_value)?;
+    }
+
+    convert_snapshot(json_value, options, stem, start_time)
+}
+
+/// Parse a content or inspect dump part by part on a worker, keeping the
+/// parts finished when `deadline` passes. A timeout is recorded in
+/// `truncation` with the accounts left unparsed. The input is handed back
+/// for `--validate` unless the worker still holds it after a timeout.
+fn parse_until(
+    input: String,
+    format: InputFormat,
+    unknown_fields: UnknownFields,
+    encryption: Encryption,
+    deadline: &budget::Deadline,
+    truncation: &mut truncate::Truncation,
+) -> (String, Result<(Value, Vec<ParseWarning>), String>) {
+    let format = format.resolve(&input);
+    let input = Arc::new(input);
+    let worker_input = Arc::clone(&input);
+    let (parts, timed_out) = deadline.collect("Parsing", move |sender| {
+        isolate::parse_streamed(
+            &worker_input,
+            |dump, whole| match format {
+                InputFormat::Content => parse_txpool_content(dump, unknown_fields, whole.then_some(&encryption)),
+                _ => parse_txpool_inspect(dump),
+            },
+            |part| sender.send(part).is_ok(),
+        );
+        // Released before the sender, so the caller can take the input back
+        drop(worker_input);
+    });
+
+    let mut snapshot = serde_json::Map::new();
+    let mut warnings = Vec::new();
+    let mut parsed_accounts = 0;
+    for part in parts {
+        let part = match part {
+            Ok(part) => part,
+            Err(err) => return (Arc::try_unwrap(input).unwrap_or_default(), Err(err)),
+        };
+        isolate::merge(&mut snapshot, Value::Object(part.snapshot));
+        warnings.extend(part.warnings);
+        parsed_accounts += part.parsed_accounts;
+    }
+    if timed_out {
+        let skipped = isolate::account_count(&input).saturating_sub(parsed_accounts);
+        emit::warn!("--timeout passed while parsing; converting the {parsed_accounts} accounts parsed so far", parsed_accounts);
+        eprintln!(
+            "--timeout passed while parsing; converting the {} accounts parsed so far ({} skipped)",
+            parsed_accounts, skipped
+        );
+        truncation.parse_timed_out = true;
+        truncation.skipped_accounts += skipped;
+    } else if parsed_accounts == 0 && warnings.iter().any(|warning| warning.kind == WarningKind::AccountFailed) {
+        return (Arc::try_unwrap(input).unwrap_or_default(), Err("No account of the dump could be parsed".to_string()));
+    }
+    // Sections without accounts, as the whole-dump parsers would keep them
+    let sections: &[&str] = if matches!(format, InputFormat::Content) { &snapshot::SECTIONS } else { &["pending"] };
+    for section in sections {
+        snapshot.entry(*section).or_insert_with(|| json!({}));
+    }
+    (Arc::try_unwrap(input).unwrap_or_default(), Ok((Value::Object(snapshot), warnings)))
+}
+
+/// Annotate a parsed snapshot and write it (plus any exports) to `<stem>.<ext>`,
+/// where `stem` defaults to `txpool_<unix seconds>`. `start_time` marks when
+/// reading the pool began, for the duration metric.
+fn convert_snapshot(
+    mut json_value: Value,
+    options: &mut ConvertOptions,
+    stem: Option<String>,
+    start_time: Instant,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let timestamp = annotate_local(&mut json_value, options.chain_id)?;
+
+    // Local annotations above always run. Stages that wait on the node or read
+    // other files are skipped once `--timeout` has passed, and the snapshot
+    // is written with what it has.
+    let mut deadline = budget::Deadline::new(start_time, options.timeout);
+
+    let rpc = match (&options.rpc_url, &options.ipc) {
+        (Some(url), _) => Some(RpcClient::new(url).with_policy(&options.rpc_policy)),
+        (None, Some(path)) => Some(RpcClient::ipc(path).with_policy(&options.rpc_policy)),
+        (None, None) => None,
+    };
+
+    let block_number = match &rpc {
+        Some(client) if deadline.allows("block_number") => Some(chain::fetch_block_number(client)?),
+        _ => None,
+    };
+    if let Some(block_number) = block_number {
+        snapshot::metadata_mut(&mut json_value).insert("block_number".to_string(), block_number.into());
+    }
+    if let Some(client) = &rpc && deadline.allows("node") {
+        snapshot::metadata_mut(&mut json_value).insert("node".to_string(), node::identity(client));
+    }
+
+    if options.l1_fee && deadline.allows("l1_fee") {
+        if let Some(client) = &rpc {
+            options.l1_fee_params.fill_from_rpc(client)?;
+        }
+        l1_fee::annotate(&mut json_value, &options.l1_fee_params)?;
+    }
+
+    let has_blobs = snapshot::transactions(&json_value).any(|entry| blob::blob_count(entry.tx) > 0);
+    if has_blobs && options.blob_base_fee.is_none() && let Some(client) = &rpc && deadline.allows("blob_base_fee") {
+        options.blob_base_fee = Some(blob::fetch_blob_base_fee(client)?);
+    }
+    blob::analyze(&mut json_value, options.blob_base_fee, &options.blob_limits);
+
+    if options.base_fee.is_none() && let Some(client) = &rpc && deadline.allows("base_fee") {
+        options.base_fee = Some(fees::fetch_base_fee(client)?);
+    }
+    fees::annotate(&mut json_value, options.base_fee);
+    value_band::annotate(&mut json_value, &options.value_bands);
+
+    if options.block_gas_limit.is_none() && let Some(client) = &rpc && deadline.allows("block_gas_limit") {
+        options.block_gas_limit = Some(account_gas::fetch_block_gas_limit(client)?);
+    }
+    let block_gas_limit = options.block_gas_limit.unwrap_or(account_gas::DEFAULT_BLOCK_GAS_LIMIT);
+    account_gas::report(&mut json_value, block_gas_limit);
+
+    if options.validate_state && let Some(client) = &rpc && deadline.allows("state") {
+        state::validate(&mut json_value, client)?;
+    }
+
+    if let (Some(blocks), Some(client)) = (options.activity_blocks, &rpc) && deadline.allows("activity") {
+        let head = match block_number {
+            Some(number) => number,
+            None => chain::fetch_block_number(client)?,
+        };
+        activity::annotate(&mut json_value, client, &options.activity_cache, head, blocks)?;
+    }
+
+    if let (Some(previous), Some(client)) = (&options.track_inclusion, &rpc) && deadline.allows("inclusion") {
+        inclusion::track(&mut json_value, previous, client, timestamp)?;
+    }
+    pool_age::annotate(&mut json_value, timestamp);
+
+    if let Some(previous) = &options.track_movement && deadline.allows("movement") {
+        movement::track(&mut json_value, previous, timestamp)?;
+    }
+
+    if options.alerts.is_enabled() && deadline.allows("alerts") {
+        alerts::evaluate(&json_value, &options.alerts, options.track_inclusion.as_deref());
+    }
+
+    if let Some(partial) = deadline.to_metadata() {
+        emit::warn!("Snapshot is partial: --timeout passed before {partial}", partial: partial.to_string());
+        eprintln!("--timeout passed; writing a partial snapshot without {}", partial["skipped"]);
+        snapshot::metadata_mut(&mut json_value).insert("partial".to_string(), partial);
+    }
+
+    let stem = stem.unwrap_or_else(|| {
+        default_stem(timestamp, json_value["metadata"]["chain_id"].as_u64(), block_number)
+    });
+    let filename = options.encryption.path(&format!("{}.{}", stem, options.output_format.extension()));
+    let document = output_document(&json_value, options);
+    let document = document.as_ref().unwrap_or(&json_value);
+    if options.check_schema {
+        check_schema(document)?;
+    }
+    let options = &*options;
+    let (written, failed_sinks) = std::thread::scope(|scope| {
+        let deliveries = sinks::spawn(scope, &options.sinks, &json_value, document);
+        let written = write_files(&json_value, document, options, &stem, &filename, start_time);
+        (written, deliveries.join())
+    });
+    let duration_ms = written?;
+
+    let output = if !options.sinks.contains(&Sink::File) {
+        "sinks"
+    } else if options.output_format.is_printed() {
+        "stdout"
+    } else {
+        &filename
+    };
+    summary::print(&json_value, output, duration_ms, summary::use_color(options.no_color));
+    if !failed_sinks.is_empty() {
+        return Err(format!("Delivery failed for {}", failed_sinks.join(", ")).into());
+    }
+    Ok(())
+}
+
+/// Stamp `metadata.captured_at` and run the annotations that need neither a
+/// node nor other files. Returns the capture time in unix seconds.
+fn annotate_local(json_value: &mut Value, chain_id: Option<u64>) -> Result<u64, std::time::SystemTimeError> {
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)?
+        .as_secs();
+    snapshot::metadata_mut(json_value).insert("captured_at".to_string(), time::rfc3339(timestamp).into());
+
+    arbitrum::annotate(json_value);
+    chain::tag_and_filter(json_value, chain_id);
+    size::annotate(json_value);
+    creation::annotate(json_value);
+    spam::analyze(json_value);
+    Ok(timestamp)
+}
+
+/// Write the snapshot file (when `file` is among the sinks), the exports and
+/// the manifest. Returns the conversion time up to the snapshot file.
+fn write_files(
+    json_value: &Value,
+    document: &Value,
+    options: &ConvertOptions,
+    stem: &str,
+    filename: &str,
+    start_time: Instant,
+) -> Result<u128, Box<dyn std::error::Error>> {
+    let mut written = Vec::new();
+    if options.sinks.contains(&Sink::File) {
+        let output_bytes = match options.output_format {
+            // Streamed account by account instead of rendered into one buffer
+            OutputFormat::Json => {
+                let mut file = options.encryption.create(filename)?;
+                let bytes = output::write_json(document, &mut file)?;
+                file.finish()?;
+                bytes
+            }
+            format if format.is_printed() => {
+                let contents = output::render(format, document)?;
+                let bytes = contents.len();
+                table::page(&String::from_utf8(contents)?)?;
+                bytes
+            }
+            format => {
+                let contents = output::render(format, document)?;
+                options.encryption.write(filename, &contents)?;
+                contents.len()
+            }
+        };
+
+        emit::emit!(
+            "{metric_agg} of {metric_name} is {metric_value}",
+            evt_kind: "metric",
+            metric_agg: "count",
+            metric_name: "txpool.output.bytes",
+            metric_value: output_bytes,
+        );
+
+        if !options.output_format.is_printed() {
+            emit::info!("Converted output saved to {filename}", filename);
+
+            println!("Converted output saved to {}", filename);
+            written.push(filename.to_string());
+        }
+    }
+
+    let duration_ms = start_time.elapsed().as_millis();
+    emit::emit!(
+        "{metric_agg} of {metric_name} is {metric_value}",
+        evt_kind: "metric",
+        metric_agg: "last",
+        metric_name: "txpool.parse.duration_ms",
+        metric_value: duration_ms,
+    );
+
+    let context = export::ExportContext {
+        base_fee: options.base_fee,
+        targets: &options.targets,
+        labels: options.labels.as_ref(),
+        select: &options.select,
+        pool_limits: &options.pool_limits,
+        bump_percent: options.bump_percent.unwrap_or(bump::DEFAULT_BUMP_PERCENT),
+        encryption: &options.encryption,
+    };
+    for kind in &options.exports {
+        let export_filename = export::write(*kind, json_value, stem, &context)?;
+        println!("Export saved to {}", export_filename);
+        written.push(export_filename);
+    }
+
+    if options.manifest {
+        for manifest_filename in manifest::write(stem, &written, json_value, options.sign_key.as_ref())? {
+            println!("Manifest saved to {}", manifest_filename);
+        }
+    }
+    Ok(duration_ms)
+}
+
+/// `txpool_<time>[_chain-<id>][_block-<number>]`, where `<time>` is the RFC 3339
+/// capture time with `:` replaced by `-` so the name is valid on every
+/// filesystem. Names sort chronologically.
+fn default_stem(timestamp: u64, chain_id: Option<u64>, block_number: Option<u64>) -> String {
+    let mut stem = format!("txpool_{}", time::rfc3339(timestamp).replace(':', "-"));
+    if let Some(chain_id) = chain_id {
+        stem.push_str(&format!("_chain-{}", chain_id));
+    }
+    if let Some(block_number) = block_number {
+        stem.push_str(&format!("_block-{}", block_number));
+    }
+    stem
+}
+
+/// The snapshot as written to the output file when `--fields`,
+/// `--normalize-hex`, `--hex-quantities` or `--rename-config` reshape it;
+/// `None` when it is written unchanged.
+fn output_document(json_value: &Value, options: &ConvertOptions) -> Option<Value> {
+    if options.fields.is_empty()
+        && options.shape.is_none()
+        && options.rename.is_none()
+        && !options.normalize_hex
+        && !options.hex_quantities
+    {
+        return None;
+    }
+    let mut document = match options.shape {
+        Some(shape) => shape::convert(json_value, shape),
+        None if !options.fields.is_empty() => projection::project(json_value, &options.fields),
+        None => json_value.clone(),
+    };
+    if options.normalize_hex {
+        normalize::normalize_hex(&mut document);
+    }
+    if options.hex_quantities {
+        normalize::hex_quantities(&mut document);
+    }
+    if let Some(rename) = &options.rename {
+        rename.apply(&mut document);
+    }
+    Some(document)
+}
+
+fn print_schema(bigquery: bool) -> Result<(), Box<dyn std::error::Error>> {
+    let schema = if bigquery {
+        bigquery::table_schema()
+    } else {
+        schema::snapshot_schema()
+    };
+    println!("{}", serde_json::to_string_pretty(&schema)?);
+    Ok(())
+}
+
+fn check_schema(document: &Value) -> Result<(), Box<dyn std::error::Error>> {
+    let errors = schema::validate(&schema::snapshot_schema(), document)?;
+    let error_count = errors.len();
+    emit::emit!(
+        "{metric_agg} of {metric_name} is {metric_value}",
+        evt_kind: "metric",
+        metric_agg: "count",
+        metric_name: "txpool.schema.violations",
+        metric_value: error_count,
+    );
+
+    if errors.is_empty() {
+        return Ok(());
+    }
+    for error in errors.iter().take(20) {
+        emit::error!("Schema violation: {error}", error);
+        eprintln!("Schema violation: {}", error);
+    }
+    if error_count > 20 {
+        eprintln!("... and {} more", error_count - 20);
+    }
+    Err(format!("Output failed schema validation with {} violations", error_count).into())
+}
+
+/// Parse a dump into a snapshot, with what the parser had to work around on
+/// the way. A content dump that does not parse is saved for debugging
+/// through `debug_file`, when given.
+fn parse_debug_format(
+    input: &str,
+    format: InputFormat,
+    unknown_fields: UnknownFields,
+    debug_file: Option<&Encryption>,
+) -> Result<(Value, Vec<ParseWarning>), Box<dyn std::error::Error>> {
+    // Check which format we're dealing with
+    match format.resolve(input) {
+        InputFormat::Content => isolate::parse(input, |dump, whole| {
+            parse_txpool_content(dump, unknown_fields, debug_file.filter(|_| whole))
+        }),
+        InputFormat::Inspect => isolate::parse(input, |dump, _| parse_txpool_inspect(dump)),
+        InputFormat::Status => Ok((parse_txpool_status(input)?, Vec::new())),
+        InputFormat::GethJson => Ok((rpc_content::from_json(&serde_json::from_str(input)?)?, Vec::new())),
+        InputFormat::Auto => Err("Unknown debug format (force a parser with --format)".into()),
+    }
+}
+
+/// A `TxpoolStatus` dump only has the section sizes; they are kept as
+/// `metadata.pool_status` next to empty sections.
+fn parse_txpool_status(input: &str) -> Result<Value, Box<dyn std::error::Error>> {
+    let count_re = Regex::new(r"\b(pending|queued):\s*(\d+)")?;
+    let mut status = serde_json::Map::new();
+    for caps in count_re.captures_iter(input) {
+        status.insert(caps[1].to_string(), json!(caps[2].parse::<u64>()?));
+    }
+    if status.is_empty() {
+        return Err("TxpoolStatus dump has no pending or queued count".into());
+    }
+    Ok(json!({
+        "pending": {},
+        "queued": {},
+        "metadata": { "pool_status": status },
+    }))
+}
+
+fn parse_txpool_inspect(input: &str) -> Result<(Value, Vec<ParseWarning>), Box<dyn std::error::Error>> {
+    let mut root = json!({ "pending": {} });
+    let pending = root["pending"].as_object_mut().unwrap();
+    let mut warnings = Vec::new();
+
+    // Regex to capture address and its transactions
+    let addr_re = Regex::new(r"(\w{40}): \{")?;
+    let mut current_addr = None;
+    let mut current_nonce = None;
+
+    for (line_index, line) in input.lines().enumerate() {
+        let trimmed = line.trim();
+
+        // Skip empty lines and struct names
+        if trimmed.is_empty() || trimmed.starts_with("TxpoolInspect") {
+            continue;
+        }
+
+        // Capture Ethereum address
+        if let Some(caps) = addr_re.captures(trimmed) {
+            current_addr = Some(format!("0x{}", &caps[1]));
+            continue;
+        }
+
+        // Capture nonce
+        if let Some(nonce) = trimmed.strip_suffix(": TxpoolInspectSummary {") {
+            if let Some(addr) = &current_addr {
+                let nonce = nonce.trim_matches('"');
+                current_nonce = Some(nonce.to_string());
+                pending
+                    .entry(addr)
+                    .or_insert(json!({}))
+                    .as_object_mut()
+                    .unwrap()
+                    .insert(nonce.to_string(), json!({}));
+            }
+            continue;
+        }
+
+        // Capture transaction fields
+        if let (Some(addr), Some(nonce)) = (&current_addr, &current_nonce) {
+            if let Some(entry) = pending.get_mut(addr).and_then(|a| a.get_mut(nonce)) {
+                let entry = entry.as_object_mut().unwrap();
+                // Values and gas prices are wei amounts past u64; gas fits it
+                let dropped = if let Some(to_val) = trimmed.strip_prefix("to: Some(") {
+                    let to_addr = to_val.trim().trim_matches(',').trim_matches(')');
+                    entry.insert("to".to_string(), json!(format!("0x{}", to_addr)));
+                    None
+                } 
+                else if trimmed == "to: None," {
+                    entry.insert("to".to_string(), Value::Null);
+                    None
+                }
+                else if let Some(value) = trimmed.strip_prefix("value: ") {
+                    inspect_number(entry, "value", value, u128::MAX)
+                }
+                else if let Some(gas) = trimmed.strip_prefix("gas: ") {
+                    inspect_number(entry, "gas", gas, u64::MAX.into())
+                }
+                else if let Some(gas_price) = trimmed.strip_prefix("gas_price: ") {
+                    inspect_number(entry, "gas_price", gas_price, u128::MAX)
+                }
+                else if trimmed != "}," && trimmed != "}" {
+                    Some(WarningKind::DroppedField)
+                } else {
+                    None
+                };
+                if let Some(kind) = dropped {
+                    let location = format!("{} nonce {}, line {}", addr, nonce, line_index + 1);
+                    warnings.push(ParseWarning::new(kind, location, line));
+                }
+            }
+        }
+
+        // Reset when we hit the end of a block
+        if trimmed == "}," || trimmed == "}" {
+            if current_nonce.is_some() {
+                current_nonce = None;
+            } else if current_addr.is_some() {
+                current_addr = None;
+            }
+        }
+    }
+
+    Ok((root, warnings))
+}
+
+/// Set `field` of an inspect entry to the number in `text` (up to `max`), or
+/// say why it was left out.
+fn inspect_number(
+    entry: &mut serde_json::Map<String, Value>,
+    field: &str,
+    text: &str,
+    max: u128,
+) -> Option<WarningKind> {
+    let digits = text.trim_matches(',');
+    match digits.parse::<u128>() {
+        Ok(num) if num <= max => {
+            entry.insert(field.to_string(), quantity::to_json(num));
+            None
+        }
+        _ if !digits.is_empty() && digits.bytes().all(|b| b.is_ascii_digit()) => Some(WarningKind::NumericOverflow),
+        _ => Some(WarningKind::DroppedField),
+    }
+}
+
+/// Apply `regex` to the dump being cleaned. `replace_all` only allocates
+/// when something matches, so passes that do not apply to a dump leave it
+/// in place instead of copying it.
+fn replace_regex(cleaned: &mut String, regex: &Regex, replacement: impl regex::Replacer) {
+    let replaced = match regex.replace_all(cleaned, replacement) {
+        Cow::Owned(replaced) => Some(replaced),
+        Cow::Borrowed(_) => None,
+    };
+    if let Some(replaced) = replaced {
+        *cleaned = replaced;
+    }
+}
+
+/// `str::replace` on the dump being cleaned, skipping the copy when `from`
+/// does not occur.
+fn replace_str(cleaned: &mut String, from: &str, to: &str) {
+    if cleaned.contains(from) {
+        *cleaned = cleaned.replace(from, to);
+    }
+}
+
+/// Parse a `TxpoolContent` dump. When it is not valid JSON once cleaned and
+/// `debug_file` is given, the cleaned text is saved next to the output,
+/// encrypted like the output is.
+fn parse_txpool_content(
+    input: &str,
+    unknown_fields: UnknownFields,
+    debug_file: Option<&Encryption>,
+) -> Result<(Value, Vec<ParseWarning>), Box<dyn std::error::Error>> {
+    let mut cleaned = input.to_string();
+    
+    // Metrics collection
+    let mut type_wrapper_counts: HashMap<&str, i64> = HashMap::new();
+    let parse_start = Instant::now();
+    
+    // Step 0: Unwrap serde_json values carried in OtherFields of unknown
+    // envelopes, and lazily computed fields that were never initialized
+    replace_regex(&mut cleaned, &Regex::new(r"OnceLock\(\s*<uninit>,?\s*\)")?, "None");
+    let string_re = Regex::new(r#"String\("((?:[^"\\]|\\.)*)"\)"#)?;
+    replace_regex(&mut cleaned, &string_re, |caps: &regex::Captures| {
+        // Hex strings are left bare so the hex step below quotes them once
+        if caps[1].starts_with("0x") && caps[1][2..].bytes().all(|b| b.is_ascii_hexdigit()) {
+            caps[1].to_string()
+        } else {
+            format!("\"{}\"", &caps[1])
+        }
+    });
+    replace_str(&mut cleaned, "Array [", "[");
+    replace_regex(&mut cleaned, &Regex::new(r"\bNull\b")?, "None");
+
+    // Step 1: Remove type wrappers and clean up structure indicators
+    let type_wrappers = [
+        "TxpoolContent", "AnyRpcTransaction", "WithOtherFields", "Transaction",
+        "Recovered", "Ethereum", "Eip1559", "Signed", "TxEip1559", "Call",
+        "OnceLock", "PrimitiveSignature", "AccessList", "OtherFields", "AnyRpc",
+        "Tx", "Legacy", "TxLegacy", "Eip2930", "TxEip2930", "Eip4844", "TxEip4844",
+        "DepositReceipt", "DepositTransaction", "OpDepositReceipt", "SequentialReceipt",
+        "Create", "AccessListItem", "TxEip7702", "Eip7702", "Authorization",
+        // Non-Ethereum envelopes (e.g. Arbitrum retryables and internal txs)
+        "Unknown", "UnknownTxEnvelope", "UnknownTypedTransaction", "AnyTxType",
+        "DeserMemo", "Object", "Number", "Bool"
+    ];
+    
+    let wrapper_count = type_wrappers.len();
+    emit::debug!("Starting type wrapper removal for {wrapper_count} wrapper types", wrapper_count);
+    
+    // One pass over the dump for all wrappers (`Name {`, `Name\n{` or
+    // `Name(`), repeated until nothing is left, instead of three per wrapper
+    let wrapper_re = Regex::new(&format!(r"({})(\s*\{{|\()", type_wrappers.join("|")))?;
+    loop {
+        let mut replaced = false;
+        replace_regex(&mut cleaned, &wrapper_re, |caps: &regex::Captures| {
+            replaced = true;
+            if let Some(wrapper) = type_wrappers.iter().find(|wrapper| **wrapper == &caps[1]) {
+                *type_wrapper_counts.entry(wrapper).or_insert(0) += 1;
+            }
+            if caps[2].ends_with('(') { "(" } else { "{" }
+        });
+        if !replaced {
+            break;
+        }
+    }
+    
+    // Emit metrics for each type wrapper
+    for (wrapper_name, count) in &type_wrapper_counts {
+        emit::emit!(
+            "{metric_agg} of {metric_name} is {metric_value}",
+            evt_kind: "metric",
+            metric_agg: "count",
+            metric_name: "txpool.type_wrapper.instances",
+            metric_value: count,
+            wrapper_type: wrapper_name,
+        );
+        emit::debug!("Found {count} instances of type wrapper: {wrapper_name}", count, wrapper_name);
+    }
+
+    // Wrappers the list does not know are removed the same way and reported,
+    // rather than left behind as bare names that break the JSON
+    let mut unknown_wrappers = BTreeSet::new();
+    replace_regex(&mut cleaned, &Regex::new(r"\b([A-Z][a-zA-Z0-9]*)(\s*\{|\()")?, |caps: &regex::Captures| {
+        if &caps[1] == "Some" {
+            return caps[0].to_string();
+        }
+        unknown_wrappers.insert(caps[1].to_string());
+        if caps[2].ends_with('(') { "(" } else { "{" }.to_string()
+    });
+    let warnings: Vec<ParseWarning> = unknown_wrappers
+        .iter()
+        .map(|name| ParseWarning::unknown_wrapper(input, name))
+        .collect();
+    
+    // Step 2: Handle Some/None and special values
+    replace_str(&mut cleaned, "Some(", "");
+    replace_str(&mut cleaned, "None", "null");
+    
+    // Step 3: Quote field names
+    let field_names = [
+        "pending", "queued", "inner", "signer", "to", "value", "input", 
+        "signature", "y_parity", "r", "s", "hash", "block_hash", "block_number",
+        "transaction_index", "effective_gas_price", "other", "chain_id", "nonce",
+        "gas_limit", "max_fee_per_gas", "max_priority_fee_per_gas", "tx",
+        "access_list", "gas", "gas_price", "from", "data", "type", "v",
+        "address", "storage_keys", "blob_versioned_hashes", "max_fee_per_blob_gas",
+        "authorization_list", "ty", "fields", "memo"
+    ];
+    
+    // All known names in one pass, rather than a scan of the dump per name
+    let mut field_replacements = 0i64;
+    let field_re = Regex::new(&format!(r"\b({})\s*:", field_names.join("|")))?;
+    replace_regex(&mut cleaned, &field_re, |caps: &regex::Captures| {
+        field_replacements += 1;
+        format!("\"{}\":", &caps[1])
+    });
+    
+    emit::emit!(
+        "{metric_agg} of {metric_name} is {metric_value}",
+        evt_kind: "metric",
+        metric_agg: "count",
+        metric_name: "txpool.field.replacements",
+        metric_value: field_replacements,
+    );
+
+    // Anything still bare is a field the list above does not know about
+    let (quoted, unknown) = unknown_fields::quote(&cleaned, unknown_fields)?;
+    cleaned = quoted;
+    
+    // Step 4: Handle Create for contract creation (after field names are quoted)
+    replace_str(&mut cleaned, "Create,", "null,");
+    replace_str(&mut cleaned, "Create\n", "null\n");
+    
+    // Step 5: Handle hex values (including empty 0x)
+    replace_regex(&mut cleaned, &Regex::new(r"\b0x([0-9a-fA-F]*)\b")?, "\"0x$1\"");
+    
+    // Step 5: Clean up parentheses and fix structure
+    // Remove opening parentheses after colons or on lines by themselves
+    replace_regex(&mut cleaned, &Regex::new(r":\s*\(")?, ": ");
+    
+    // Remove closing parentheses followed by comma
+    replace_str(&mut cleaned, "),", ",");
+    // Remove all parentheses
+    replace_str(&mut cleaned, ")", "");
+    replace_str(&mut cleaned, "(", "");
+    
+    // Step 6: Fix empty objects/arrays
+    replace_str(&mut cleaned, "\n                                                [],\n                                            ", "[]");
+    replace_str(&mut cleaned, " {}", "{}");
+    
+    // Remove type names immediately before braces
+    replace_regex(&mut cleaned, &Regex::new(r"[A-Z][a-zA-Z0-9]*\{")?, "{");
+    
+    // Also remove standalone type names on their own or followed by whitespace and brace
+    replace_regex(&mut cleaned, &Regex::new(r#":\s*([A-Z][a-zA-Z0-9]*)\s*\n\s*\{"#)?, ": {");
+    
+    // Step 7: Remove underscores from numbers
+    replace_regex(&mut cleaned, &Regex::new(r":\s*(\d+)_")?, ": $1");
+    
+    // Step 8: Fix trailing commas (more aggressive)
+    // Fix any sequence of closing braces/brackets with trailing commas
+    replace_regex(&mut cleaned, &Regex::new(r"\},\s*\}")?, "}}");
+    replace_regex(&mut cleaned, &Regex::new(r"\],\s*\}")?, "]}");
+    replace_regex(&mut cleaned, &Regex::new(r"\},\s*\]")?, "}]");
+    // Standard trailing comma removal
+    replace_regex(&mut cleaned, &Regex::new(r",\s*\}")?, "}");
+    replace_regex(&mut cleaned, &Regex::new(r",\s*\]")?, "]");
+    
+    // Step 9: Fix any remaining structural issues
+    // Remove commas on their own lines (line-anchored so consecutive ones
+    // left behind by nested tuple wrappers are all caught)
+    replace_regex(&mut cleaned, &Regex::new(r"(?m)^[ \t]*,[ \t]*\n")?, "");
+    
+    // Fix trailing commas after values on their own lines
+    replace_regex(&mut cleaned, &Regex::new(r#"("0x[0-9a-fA-F]+"|true|false|\d+),\s*\}"#)?, "$1}");
+    replace_regex(&mut cleaned, &Regex::new(r#"("0x[0-9a-fA-F]+"|true|false|\d+),\s*\]"#)?, "$1]");
+    
+    // Final cleanup: process line by line to fix multi-line value issues
+    let lines: Vec<&str> = cleaned.lines().collect();
+    let mut final_cleaned = String::with_capacity(cleaned.len());
+    
+    for i in 0..lines.len() {
+        let line = lines[i].trim_end();
+        
+        // Check if this line ends with a closing brace/bracket followed by comma
+        if line.ends_with("},") || line.ends_with("],") {
+            // Look ahead to see if the next non-empty line is also a closing brace/bracket
+            let mut j = i + 1;
+            while j < lines.len() && lines[j].trim().is_empty() {
+                j += 1;
+            }
+            
+            if j < lines.len() {
+                let next_line = lines[j].trim();
+                if next_line.starts_with('}') || next_line.starts_with(']') {
+                    // Remove the trailing comma
+                    final_cleaned.push_str(&line[..line.len()-1]);
+                    final_cleaned.push('\n');
+                    continue;
+                }
+            }
+        }
+        
+        final_cleaned.push_str(line);
+        final_cleaned.push('\n');
+    }
+    
+    cleaned = final_cleaned;
+    
+    // Parse as JSON
+    let parse_duration_ms = parse_start.elapsed().as_millis();
+    
+    let parse_duration_ms_i64 = parse_duration_ms as i64;
+    emit::emit!(
+        "{metric_agg} of {metric_name} is {metric_value}",
+        evt_kind: "metric",
+        metric_agg: "last",
+        metric_name: "txpool.content.parse_duration_ms",
+        metric_value: parse_duration_ms_i64,
+    );
+    
+    match serde_json::from_str(&cleaned) {
+        Ok(mut json) => {
+            emit::info!("Successfully parsed txpool content in {parse_duration_ms}ms", parse_duration_ms);
+            if unknown_fields == UnknownFields::Extra {
+                unknown_fields::move_to_extra(&mut json, &unknown);
+            }
+            Ok((json, warnings))
+        },
+        Err(e) => {
+            let error_line = e.line();
+            let error_column = e.column();
+            emit::emit!(
+                "{metric_agg} of {metric_name} is {metric_value}",
+                evt_kind: "metric",
+                metric_agg: "count",
+                metric_name: "txpool.parse.errors",
+                metric_value: 1,
+                error_type: "json_parse_error",
+                error_line,
+                error_column,
+            );
+            
+            // Parts retried by `isolate::parse` are reported there instead
+            if let Some(encryption) = debug_file {
+                let timestamp = SystemTime::now()
+                    .duration_since(UNIX_EPOCH)?
+                    .as_secs();
+                let debug_filename = encryption.path(&format!("debug_clean_{}.txt", timestamp));
+                encryption.write(&debug_filename, cleaned.as_bytes())?;
+                
+                let error_msg = format!("{}", e);
+                emit::error!("JSON parse error: {error} at line {line} column {column}", error: error_msg, line: error_line, column: error_column);
+                eprintln!("JSON parse error: {}", e);
+                eprintln!("Cleaned output saved to {} for debugging", debug_filename);
+            }
+            Err(e.into())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// An Eip1559 transaction closes two tuple wrappers in a row
+    /// (`Eip1559(` and `Ethereum(`), which leaves consecutive comma-only
+    /// lines once the wrappers are removed.
+    const NESTED_TUPLES: &str = r#"TxpoolContent {
+    pending: {
+        0x09f1fd9d03f0a9b4553274161bbf84755d5bca46: {
+            "0": AnyRpcTransaction(
+                WithOtherFields {
+                    inner: Transaction {
+                        inner: Recovered {
+                            inner: Ethereum(
+                                Eip1559(
+                                    Signed {
+                                        tx: TxEip1559 {
+                                            chain_id: 1,
+                                            nonce: 0,
+                                            gas_limit: 21000,
+                                            max_fee_per_gas: 30000000000,
+                                            max_priority_fee_per_gas: 1000000000,
+                                            to: Call(
+                                                0xff9b273f422863872601349fef087eb05753f8ef,
+                                            ),
+                                            value: 1000,
+                                            access_list: AccessList(
+                                                [],
+                                            ),
+                                            input: 0x,
+                                        },
+                                        signature: PrimitiveSignature {
+                                            y_parity: false,
+                                            r: 12158460252043748188,
+                                            s: 16246539215492125100,
+                                        },
+                                        hash: OnceLock(
+                                            0x8634930f702b64521674658f30892ddd2f957488e4fd469e656ed1cb9a126362,
+                                        ),
+                                    },
+                                ),
+                            ),
+                            signer: 0x09f1fd9d03f0a9b4553274161bbf84755d5bca46,
+                        },
+                        block_hash: None,
+                        block_number: None,
+                        transaction_index: None,
+                        effective_gas_price: None,
+                    },
+                    other: OtherFields {
+                        inner: {},
+                    },
+                },
+            ),
+        },
+    },
+    queued: {},
+}
+"#;
+
+    #[test]
+    fn consecutive_comma_lines_are_removed() {
+        let (snapshot, warnings) = parse_txpool_content(NESTED_TUPLES, UnknownFields::Preserve, None).unwrap();
+        let tx = &snapshot["pending"]["0x09f1fd9d03f0a9b4553274161bbf84755d5bca46"]["0"];
+        assert_eq!(snapshot::find_field(tx, "nonce"), Some(&json!(0)));
+        assert!(warnings.is_empty());
+    }
+}
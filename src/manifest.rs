@@ -0,0 +1,285 @@
+use k256::ecdsa;
+use serde_json::{Value, json};
+use sha2::{Digest, Sha256};
+use std::error::Error;
+use std::fs::{self, File};
+use std::io::Read;
+use std::path::Path;
+
+use crate::keccak::keccak256;
+use crate::quantity::{bytes_to_hex, hex_to_bytes};
+use crate::secp256k1;
+use crate::snapshot;
+
+/// Private key that signs manifests, read from a file holding it as hex.
+/// Signing uses `k256` (constant time, RFC 6979 nonces), which also wipes
+/// the key from memory when it is dropped.
+#[derive(Clone)]
+pub struct SigningKey {
+    key: ecdsa::SigningKey,
+    address: String,
+}
+
+impl SigningKey {
+    pub fn load(path: &str) -> Result<Self, Box<dyn Error>> {
+        let secret = hex_to_bytes(fs::read_to_string(path)?.trim())
+            .filter(|bytes| bytes.len() == 32)
+            .ok_or_else(|| format!("Signing key file {} must hold a 32-byte hex private key", path))?;
+        SigningKey::from_secret(&secret).ok_or_else(|| format!("Signing key in {} is not a valid secp256k1 key", path).into())
+    }
+
+    fn from_secret(secret: &[u8]) -> Option<Self> {
+        let key = ecdsa::SigningKey::from_slice(secret).ok()?;
+        // Uncompressed SEC1 point: 0x04 ++ x ++ y
+        let public_key = key.verifying_key().to_encoded_point(false);
+        let address = bytes_to_hex(&keccak256(&public_key.as_bytes()[1..])[12..]);
+        Some(SigningKey { key, address })
+    }
+
+    /// EIP-191 signature over `message`: `r ++ s ++ v` with low `s` and
+    /// `v` of 27 or 28.
+    fn sign(&self, message: &[u8]) -> Result<[u8; 65], Box<dyn Error>> {
+        let (signature, recovery_id) = self.key.sign_prehash_recoverable(&personal_message_hash(message))?;
+        let mut out = [0u8; 65];
+        out[..64].copy_from_slice(&signature.to_bytes());
+        out[64] = 27 + u8::from(recovery_id.is_y_odd());
+        Ok(out)
+    }
+}
+
+/// `keccak256("\x19Ethereum Signed Message:\n" ++ len ++ message)`, the EIP-191
+/// hash `personal_sign` and `cast wallet sign` use.
+fn personal_message_hash(message: &[u8]) -> [u8; 32] {
+    let mut prefixed = format!("\x19Ethereum Signed Message:\n{}", message.len()).into_bytes();
+    prefixed.extend_from_slice(message);
+    keccak256(&prefixed)
+}
+
+/// Hex SHA-256 (no `0x`, as `sha256sum` prints it) and length of a file,
+/// read in chunks.
+fn hash_file(path: &Path) -> Result<(String, u64), Box<dyn Error>> {
+    let mut file = File::open(path)?;
+    let mut hasher = Sha256::new();
+    let mut buffer = vec![0u8; 1 << 16];
+    let mut bytes = 0u64;
+    loop {
+        let read = file.read(&mut buffer)?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+        bytes += read as u64;
+    }
+    Ok((bytes_to_hex(&hasher.finalize())[2..].to_string(), bytes))
+}
+
+fn file_name(path: &str) -> String {
+    Path::new(path)
+        .file_name()
+        .map_or_else(|| path.to_string(), |name| name.to_string_lossy().into_owned())
+}
+
+/// Write `<stem>.manifest.json` listing the SHA-256 and size of each file in
+/// `files` with the snapshot's transaction count and capture time. With a
+/// key, also write `<stem>.manifest.sig`: an EIP-191 signature over the
+/// manifest's exact bytes. Returns the names of the files written.
+pub fn write(
+    stem: &str,
+    files: &[String],
+    snapshot: &Value,
+    key: Option<&SigningKey>,
+) -> Result<Vec<String>, Box<dyn Error>> {
+    let mut entries = Vec::new();
+    for path in files {
+        let (sha256, bytes) = hash_file(Path::new(path))?;
+        entries.push(json!({ "name": file_name(path), "bytes": bytes, "sha256": sha256 }));
+    }
+    let manifest = json!({
+        "version": 1,
+        "captured_at": snapshot["metadata"]["captured_at"],
+        "tx_count": snapshot::transactions(snapshot).count(),
+        "files": entries,
+    });
+
+    // No trailing newline, so `$(cat <stem>.manifest.json)` is the signed message.
+    let contents = serde_json::to_string_pretty(&manifest)?;
+    let manifest_filename = format!("{}.manifest.json", stem);
+    fs::write(&manifest_filename, &contents)?;
+    let mut written = vec![manifest_filename];
+
+    if let Some(key) = key {
+        let signature = key.sign(contents.as_bytes())?;
+        let signature_file = json!({
+            "scheme": "eip191",
+            "signer": key.address,
+            "signature": bytes_to_hex(&signature),
+        });
+        let signature_filename = format!("{}.manifest.sig", stem);
+        fs::write(&signature_filename, serde_json::to_string_pretty(&signature_file)?)?;
+        written.push(signature_filename);
+    }
+
+    emit::emit!(
+        "{metric_agg} of {metric_name} is {metric_value}",
+        evt_kind: "metric",
+        metric_agg: "count",
+        metric_name: "txpool.manifest.files",
+        metric_value: files.len(),
+    );
+    Ok(written)
+}
+
+/// Re-hash every file a manifest lists (relative to the manifest's directory)
+/// and, when a `.sig` sits next to it, recover the signer. Fails when a file
+/// is missing or changed, the signature does not match, or the signer is not
+/// `expected_signer`.
+pub fn verify(path: &str, expected_signer: Option<&str>) -> Result<(), Box<dyn Error>> {
+    let contents = fs::read_to_string(path)?;
+    let manifest: Value =
+        serde_json::from_str(&contents).map_err(|err| format!("Invalid manifest {}: {}", path, err))?;
+    let files = manifest["files"]
+        .as_array()
+        .ok_or_else(|| format!("Manifest {} has no files list", path))?;
+    let dir = Path::new(path).parent().unwrap_or(Path::new(""));
+
+    let mut failures = 0;
+    for entry in files {
+        let name = entry["name"].as_str().ok_or("Manifest file entry without a name")?;
+        let status = match hash_file(&dir.join(name)) {
+            Ok((sha256, bytes)) if Some(sha256.as_str()) == entry["sha256"].as_str()
+                && Some(bytes) == entry["bytes"].as_u64() => "ok",
+            Ok(_) => "MODIFIED",
+            Err(_) => "MISSING",
+        };
+        if status != "ok" {
+            failures += 1;
+        }
+        println!("  {:<9}{}", status, name);
+    }
+
+    let signature_path = path.strip_suffix(".json").unwrap_or(path).to_string() + ".sig";
+    let signer = match fs::read_to_string(&signature_path) {
+        Ok(signature_file) => Some(recover_signer(&contents, &signature_file, &signature_path)?),
+        Err(_) => None,
+    };
+    match (&signer, expected_signer) {
+        (Some(signer), _) => println!("Signed by {}", signer),
+        (None, Some(_)) => return Err(format!("No signature next to {} ({} not found)", path, signature_path).into()),
+        (None, None) => println!("Not signed"),
+    }
+    if let (Some(signer), Some(expected)) = (&signer, expected_signer)
+        && !signer.eq_ignore_ascii_case(expected)
+    {
+        return Err(format!("Manifest was signed by {}, not {}", signer, expected).into());
+    }
+    if failures > 0 {
+        return Err(format!("{} of {} files failed verification", failures, files.len()).into());
+    }
+    println!("{} files verified", files.len());
+    Ok(())
+}
+
+/// Signer recovered from a `.manifest.sig` file; it has to match the signer
+/// the file names.
+fn recover_signer(manifest: &str, signature_file: &str, path: &str) -> Result<String, Box<dyn Error>> {
+    let signature_file: Value =
+        serde_json::from_str(signature_file).map_err(|err| format!("Invalid signature file {}: {}", path, err))?;
+    let signature = signature_file["signature"]
+        .as_str()
+        .and_then(hex_to_bytes)
+        .filter(|signature| signature.len() == 65)
+        .ok_or_else(|| format!("Signature file {} needs a 65-byte hex signature", path))?;
+    let r: [u8; 32] = signature[..32].try_into()?;
+    let s: [u8; 32] = signature[32..64].try_into()?;
+    let y_odd = match signature[64] {
+        0 | 27 => false,
+        1 | 28 => true,
+        v => return Err(format!("Unsupported signature v value {} in {}", v, path).into()),
+    };
+    let recovered = secp256k1::recover_address(&personal_message_hash(manifest.as_bytes()), &r, &s, y_odd)
+        .map(|address| bytes_to_hex(&address))
+        .ok_or_else(|| format!("Signature in {} is invalid", path))?;
+    let claimed = signature_file["signer"].as_str().unwrap_or_default();
+    if !recovered.eq_ignore_ascii_case(claimed) {
+        return Err(format!("Signature in {} was made by {}, not the listed signer {}", path, recovered, claimed).into());
+    }
+    Ok(recovered)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(last_byte: u8) -> SigningKey {
+        let mut secret = [0u8; 32];
+        secret[31] = last_byte;
+        SigningKey::from_secret(&secret).unwrap()
+    }
+
+    /// Empty scratch directory for one test.
+    fn scratch(name: &str) -> std::path::PathBuf {
+        let dir = std::env::temp_dir().join(format!("rust-txpool-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn address_of_key_one() {
+        assert_eq!(key(1).address, "0x7e5f4552091a69125d5dfcb7b8c2659029395bdf");
+        assert!(SigningKey::from_secret(&[0u8; 32]).is_none());
+    }
+
+    #[test]
+    fn rfc6979_signature() {
+        // Private key 1 over SHA-256("Satoshi Nakamoto"), low s
+        let digest = Sha256::digest(b"Satoshi Nakamoto");
+        let (signature, _) = key(1).key.sign_prehash_recoverable(&digest).unwrap();
+        assert_eq!(
+            bytes_to_hex(&signature.to_bytes()),
+            "0x934b1ea10a4b3c1757e2b0c017d0b6143ce3c9a7e6a4a49860d7a6ab210ee3d8\
+             2442ce9d2b916064108014783e923ec36b49743e2ffa1c4496f01a512aafd9e5"
+        );
+    }
+
+    #[test]
+    fn signature_recovers_the_signer() {
+        let key = key(7);
+        let signature = key.sign(b"manifest").unwrap();
+        let recovered = secp256k1::recover_address(
+            &personal_message_hash(b"manifest"),
+            signature[..32].try_into().unwrap(),
+            signature[32..64].try_into().unwrap(),
+            signature[64] == 28,
+        )
+        .unwrap();
+        assert_eq!(bytes_to_hex(&recovered), key.address);
+    }
+
+    #[test]
+    fn sha256_of_a_file() {
+        let path = scratch("sha256").join("abc.txt");
+        fs::write(&path, "abc").unwrap();
+        assert_eq!(
+            hash_file(&path).unwrap(),
+            ("ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad".to_string(), 3)
+        );
+    }
+
+    #[test]
+    fn write_and_verify_round_trip() {
+        let dir = scratch("manifest");
+        let snapshot_path = dir.join("snapshot.json").to_string_lossy().into_owned();
+        fs::write(&snapshot_path, "{}").unwrap();
+        let stem = dir.join("snapshot").to_string_lossy().into_owned();
+        let snapshot = json!({ "pending": {}, "metadata": { "captured_at": "2026-01-01T00:00:00Z" } });
+        let key = key(7);
+        write(&stem, std::slice::from_ref(&snapshot_path), &snapshot, Some(&key)).unwrap();
+
+        let manifest = format!("{}.manifest.json", stem);
+        verify(&manifest, Some(&key.address)).unwrap();
+        assert!(verify(&manifest, Some("0x7e5f4552091a69125d5dfcb7b8c2659029395bdf")).is_err());
+        fs::write(&snapshot_path, "{ }").unwrap();
+        assert!(verify(&manifest, None).is_err());
+    }
+}
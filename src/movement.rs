@@ -0,0 +1,113 @@
+use serde_json::{Map, Value, json};
+use std::collections::{BTreeMap, HashMap};
+use std::error::Error;
+use std::fs;
+
+use crate::snapshot::{self, find_field};
+
+/// Where a transaction sat in the previous snapshot.
+struct Previous {
+    section: &'static str,
+    hash: Option<String>,
+    section_since: Option<u64>,
+}
+
+fn tx_hash(tx: &Value) -> Option<String> {
+    find_field(tx, "hash").and_then(Value::as_str).map(str::to_ascii_lowercase)
+}
+
+/// Compare sections with the snapshot at `previous_path` to see which
+/// transactions moved between `queued` and `pending`.
+///
+/// Every transaction gets `section_since` (unix seconds): carried over when
+/// it stays in its section, `now` when it is new or just moved. A move is
+/// only counted for the same transaction (same hash when both snapshots have
+/// one) in the same sender/nonce slot. Moves are listed per sender under
+/// `metadata.movement` with the nonce, direction and how long the
+/// transaction had sat in its previous section (at least; up to the previous
+/// snapshot), and counted in `txpool.movement.promoted` and
+/// `txpool.movement.demoted`.
+pub fn track(snapshot: &mut Value, previous_path: &str, now: u64) -> Result<(), Box<dyn Error>> {
+    let previous: Value = serde_json::from_str(&fs::read_to_string(previous_path)?)?;
+    let previous_captured_at = snapshot::captured_at(&previous);
+
+    let mut slots: HashMap<(String, String), Previous> = HashMap::new();
+    for entry in snapshot::transactions(&previous) {
+        slots.insert(
+            (entry.account.to_ascii_lowercase(), entry.nonce.to_string()),
+            Previous {
+                section: entry.section,
+                hash: tx_hash(entry.tx),
+                section_since: entry.tx.get("section_since").and_then(Value::as_u64).or(previous_captured_at),
+            },
+        );
+    }
+
+    // (section, account, nonce, section_since) of every current transaction.
+    let mut updates = Vec::new();
+    let mut senders: BTreeMap<String, Vec<Value>> = BTreeMap::new();
+    let (mut promoted, mut demoted) = (0usize, 0usize);
+    for entry in snapshot::transactions(snapshot) {
+        let account = entry.account.to_ascii_lowercase();
+        let previous = slots
+            .get(&(account.clone(), entry.nonce.to_string()))
+            .filter(|previous| match (&previous.hash, tx_hash(entry.tx)) {
+                (Some(before), Some(now)) => *before == now,
+                _ => true,
+            });
+        let section_since = match previous {
+            Some(previous) if previous.section == entry.section => previous.section_since.unwrap_or(now),
+            Some(previous) => {
+                let direction = if entry.section == "pending" {
+                    promoted += 1;
+                    "promoted"
+                } else {
+                    demoted += 1;
+                    "demoted"
+                };
+                senders.entry(account).or_default().push(json!({
+                    "nonce": entry.nonce,
+                    "hash": tx_hash(entry.tx),
+                    "direction": direction,
+                    "seconds_in_previous_section": previous.section_since.map(|since| now.saturating_sub(since)),
+                }));
+                now
+            }
+            None => now,
+        };
+        updates.push((entry.section, entry.account.to_string(), entry.nonce.to_string(), section_since));
+    }
+    for (section, account, nonce, section_since) in updates {
+        if let Some(tx) = snapshot[section][account.as_str()][nonce.as_str()].as_object_mut() {
+            tx.insert("section_since".to_string(), json!(section_since));
+        }
+    }
+
+    emit::emit!(
+        "{metric_agg} of {metric_name} is {metric_value}",
+        evt_kind: "metric",
+        metric_agg: "count",
+        metric_name: "txpool.movement.promoted",
+        metric_value: promoted,
+    );
+    emit::emit!(
+        "{metric_agg} of {metric_name} is {metric_value}",
+        evt_kind: "metric",
+        metric_agg: "count",
+        metric_name: "txpool.movement.demoted",
+        metric_value: demoted,
+    );
+
+    let senders: Map<String, Value> = senders.into_iter().map(|(account, moves)| (account, json!(moves))).collect();
+    snapshot::metadata_mut(snapshot).insert(
+        "movement".to_string(),
+        json!({
+            "previous": previous_path,
+            "interval_seconds": previous_captured_at.map(|at| now.saturating_sub(at)),
+            "promoted": promoted,
+            "demoted": demoted,
+            "senders": senders,
+        }),
+    );
+    Ok(())
+}
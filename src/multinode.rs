@@ -0,0 +1,182 @@
+use serde_json::{Map, Value, json};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::error::Error;
+use std::thread;
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+use crate::alerts::AlertRules;
+use crate::cli::ConvertOptions;
+use crate::rpc::{RpcClient, RpcPolicy};
+use crate::rpc_content;
+use crate::sinks::Sink;
+use crate::snapshot::{self, find_field};
+
+/// Fetch `txpool_content` from every `--rpc-url` at once and write one
+/// snapshot per node (`<name>_node-<i>`) plus a merged union
+/// (`<name>_union`). With `--poll <seconds>` this repeats until interrupted.
+pub fn run(options: &ConvertOptions) -> Result<(), Box<dyn Error>> {
+    loop {
+        let started = Instant::now();
+        let result = poll_once(options);
+        let Some(interval) = options.poll_interval else {
+            return result;
+        };
+        // A failed round (e.g. a sink that is down) must not stop the collector.
+        if let Err(err) = result {
+            let err = err.to_string();
+            emit::error!("Poll round failed: {err}", err);
+            eprintln!("Poll round failed: {}", err);
+        }
+        thread::sleep(interval.saturating_sub(started.elapsed()));
+    }
+}
+
+fn poll_once(options: &ConvertOptions) -> Result<(), Box<dyn Error>> {
+    let start_time = Instant::now();
+    let timestamp = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+
+    // Fallbacks would mix another node into a node's view; only retries and
+    // the rate limit apply here.
+    let policy = RpcPolicy { fallbacks: Vec::new(), ..options.rpc_policy.clone() };
+
+    // One thread per node; errors become strings because `Box<dyn Error>` is not `Send`.
+    let results: Vec<Result<Value, String>> = thread::scope(|scope| {
+        let handles: Vec<_> = options
+            .rpc_urls
+            .iter()
+            .map(|url| {
+                let policy = &policy;
+                scope.spawn(move || {
+                    let content = RpcClient::new(url)
+                        .with_policy(policy)
+                        .call("txpool_content", json!([]))
+                        .map_err(|err| err.to_string())?;
+                    rpc_content::to_snapshot(&content).map_err(|err| err.to_string())
+                })
+            })
+            .collect();
+        handles
+            .into_iter()
+            .map(|handle| handle.join().unwrap_or_else(|_| Err("poll thread panicked".to_string())))
+            .collect()
+    });
+
+    let mut views = Vec::new();
+    for (url, result) in options.rpc_urls.iter().zip(results) {
+        match result {
+            Ok(view) => views.push((url.as_str(), Some(view))),
+            Err(err) => {
+                emit::error!("Failed to read txpool_content from {url}: {err}", url, err);
+                eprintln!("Failed to read txpool_content from {}: {}", url, err);
+                views.push((url.as_str(), None));
+            }
+        }
+    }
+    if views.iter().all(|(_, view)| view.is_none()) {
+        return Err("No node returned txpool_content".into());
+    }
+
+    let base = crate::default_stem(timestamp, None, None);
+    let union = merge(&views);
+
+    // Per-node snapshots only carry that node's own RPC lookups; exports,
+    // inclusion and movement tracking, state validation, alerts and streaming run once, on
+    // the union.
+    for (index, (url, view)) in views.into_iter().enumerate() {
+        let Some(view) = view else {
+            continue;
+        };
+        let mut node_options = options.clone();
+        node_options.rpc_url = Some(url.to_string());
+        node_options.rpc_policy.fallbacks.clear();
+        node_options.exports.clear();
+        node_options.track_inclusion = None;
+        node_options.track_movement = None;
+        node_options.sinks.retain(|sink| *sink == Sink::File);
+        node_options.alerts = AlertRules::default();
+        node_options.validate_state = false;
+        crate::convert_snapshot(view, &mut node_options, Some(format!("{}_node-{}", base, index)), start_time)?;
+    }
+    crate::convert_snapshot(union, &mut options.clone(), Some(format!("{}_union", base)), start_time)
+}
+
+fn tx_hash(tx: &Value) -> Option<String> {
+    find_field(tx, "hash").and_then(Value::as_str).map(str::to_ascii_lowercase)
+}
+
+/// One sender/nonce slot of the union.
+struct Slot<'a> {
+    section: &'static str,
+    tx: &'a Value,
+    /// Hash each node holds in this slot.
+    hashes: HashMap<&'a str, Option<String>>,
+}
+
+/// Deduplicated union of the node views. Each sender/nonce slot is taken from
+/// the first node that has it, except that a pending copy wins over a queued
+/// one. Every transaction gets a `visibility` object mapping each node URL to
+/// whether that node holds the same transaction hash. Slots where nodes hold
+/// different transactions (replacements still propagating) are counted in
+/// `metadata.nodes.conflicting_slots`.
+pub fn merge(views: &[(&str, Option<Value>)]) -> Value {
+    let mut slots: BTreeMap<(String, String), Slot> = BTreeMap::new();
+    for (url, view) in views {
+        let Some(view) = view else {
+            continue;
+        };
+        for entry in snapshot::transactions(view) {
+            let key = (entry.account.to_ascii_lowercase(), entry.nonce.to_string());
+            let slot = slots.entry(key).or_insert_with(|| Slot {
+                section: entry.section,
+                tx: entry.tx,
+                hashes: HashMap::new(),
+            });
+            if slot.section == "queued" && entry.section == "pending" {
+                slot.section = entry.section;
+                slot.tx = entry.tx;
+            }
+            slot.hashes.insert(url, tx_hash(entry.tx));
+        }
+    }
+
+    let mut union = json!({ "pending": {}, "queued": {} });
+    let mut conflicting = 0usize;
+    for ((account, nonce), slot) in slots {
+        let hash = tx_hash(slot.tx);
+        let distinct: HashSet<&Option<String>> = slot.hashes.values().collect();
+        if distinct.len() > 1 {
+            conflicting += 1;
+        }
+        let visibility: Map<String, Value> = views
+            .iter()
+            .map(|(url, _)| {
+                let visible = slot.hashes.get(url).is_some_and(|held| *held == hash);
+                (url.to_string(), json!(visible))
+            })
+            .collect();
+
+        let mut tx = slot.tx.clone();
+        tx["visibility"] = Value::Object(visibility);
+        union[slot.section]
+            .as_object_mut()
+            .expect("sections created above")
+            .entry(account)
+            .or_insert_with(|| json!({}))[nonce.as_str()] = tx;
+    }
+
+    let nodes: Vec<Value> = views
+        .iter()
+        .map(|(url, view)| {
+            json!({
+                "url": url,
+                "ok": view.is_some(),
+                "transactions": view.as_ref().map(|view| snapshot::transactions(view).count()),
+            })
+        })
+        .collect();
+    snapshot::metadata_mut(&mut union).insert(
+        "nodes".to_string(),
+        json!({ "views": nodes, "conflicting_slots": conflicting }),
+    );
+    union
+}
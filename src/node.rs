@@ -0,0 +1,54 @@
+use serde_json::{Value, json};
+
+use crate::quantity::parse_u128;
+use crate::rpc::RpcClient;
+
+/// Call a method that takes no parameters; nodes often disable the `net`
+/// namespace, so a failure is logged and recorded as null instead of
+/// aborting the conversion.
+fn optional_call(client: &RpcClient, method: &str) -> Value {
+    match client.call(method, json!([])) {
+        Ok(result) => result,
+        Err(err) => {
+            let err = err.to_string();
+            emit::warn!("Could not read node identity with {method}: {err}", method, err);
+            Value::Null
+        }
+    }
+}
+
+/// Identity of the node the snapshot came from, for `metadata.node`:
+/// `web3_clientVersion` as `client_version`, its implementation name
+/// (`geth`, `reth`, `nethermind`, ...) as `client`, `net_version` as
+/// `network_id` and `net_peerCount` as `peer_count`.
+pub fn identity(client: &RpcClient) -> Value {
+    let client_version = optional_call(client, "web3_clientVersion");
+    let implementation = client_version
+        .as_str()
+        .and_then(|version| version.split('/').next())
+        .map(str::to_ascii_lowercase);
+    let network_id = optional_call(client, "net_version");
+    let peer_count = optional_call(client, "net_peerCount")
+        .as_str()
+        .and_then(parse_u128)
+        .and_then(|count| u64::try_from(count).ok());
+
+    if let Some(peers) = peer_count {
+        let client_label = implementation.clone().unwrap_or_default();
+        emit::emit!(
+            "{metric_agg} of {metric_name} is {metric_value}",
+            evt_kind: "metric",
+            metric_agg: "last",
+            metric_name: "txpool.node.peers",
+            metric_value: peers,
+            client: client_label,
+        );
+    }
+
+    json!({
+        "client_version": client_version,
+        "client": implementation,
+        "network_id": network_id,
+        "peer_count": peer_count,
+    })
+}
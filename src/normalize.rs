@@ -0,0 +1,117 @@
+use serde_json::{Map, Value};
+
+use crate::quantity::to_hex_quantity;
+use crate::snapshot;
+
+/// Fields holding 20-byte addresses.
+const ADDRESS_FIELDS: [&str; 4] = ["to", "from", "signer", "address"];
+
+/// Fields holding 32-byte hashes (or arrays of them).
+const HASH_FIELDS: [&str; 4] = ["hash", "block_hash", "storage_keys", "blob_versioned_hashes"];
+
+/// Fields that are JSON-RPC quantities.
+const QUANTITY_FIELDS: [&str; 17] = [
+    "nonce",
+    "value",
+    "gas",
+    "gas_limit",
+    "gas_price",
+    "max_fee_per_gas",
+    "max_priority_fee_per_gas",
+    "max_fee_per_blob_gas",
+    "chain_id",
+    "block_number",
+    "transaction_index",
+    "effective_gas_price",
+    "ty",
+    "r",
+    "s",
+    "v",
+    "y_parity",
+];
+
+fn is_hex(text: &str) -> bool {
+    text.strip_prefix("0x")
+        .or_else(|| text.strip_prefix("0X"))
+        .is_some_and(|digits| digits.bytes().all(|b| b.is_ascii_hexdigit()))
+}
+
+/// Lowercase `0x` hex left-padded with zeros to `width` digits.
+fn fixed_width(text: &str, width: usize) -> String {
+    format!("0x{:0>width$}", text[2..].to_ascii_lowercase())
+}
+
+fn normalize_string(text: &str, field: &str) -> String {
+    if ADDRESS_FIELDS.contains(&field) {
+        fixed_width(text, 40)
+    } else if HASH_FIELDS.contains(&field) {
+        fixed_width(text, 64)
+    } else if QUANTITY_FIELDS.contains(&field) {
+        to_hex_quantity(&Value::String(text.to_ascii_lowercase())).unwrap_or_else(|| text.to_ascii_lowercase())
+    } else {
+        text.to_ascii_lowercase()
+    }
+}
+
+/// Canonicalize every hex string in the snapshot so outputs from different
+/// clients compare equal: all hex is lowercase, addresses and hashes are
+/// zero-padded to their full width, and hex quantities lose leading zeros
+/// (`0x0` for zero). Account keys are normalized like addresses.
+pub fn normalize_hex(snapshot: &mut Value) {
+    for section in snapshot::SECTIONS {
+        if let Some(accounts) = snapshot.get_mut(section).and_then(Value::as_object_mut) {
+            let normalized: Map<String, Value> = std::mem::take(accounts)
+                .into_iter()
+                .map(|(account, nonces)| {
+                    let account = if is_hex(&account) { fixed_width(&account, 40) } else { account };
+                    (account, nonces)
+                })
+                .collect();
+            *accounts = normalized;
+        }
+    }
+    for tx in snapshot::transactions_mut(snapshot) {
+        normalize_nested(tx, "");
+    }
+}
+
+fn normalize_nested(value: &mut Value, field: &str) {
+    match value {
+        Value::String(text) if is_hex(text) => *text = normalize_string(text, field),
+        Value::Object(map) => {
+            for (key, nested) in map.iter_mut() {
+                normalize_nested(nested, key);
+            }
+        }
+        // Array items take the field name of the array (e.g. `storage_keys`).
+        Value::Array(items) => items.iter_mut().for_each(|item| normalize_nested(item, field)),
+        _ => {}
+    }
+}
+
+/// Write every quantity field of every transaction as a JSON-RPC hex
+/// `QUANTITY` string instead of a decimal number.
+pub fn hex_quantities(snapshot: &mut Value) {
+    for tx in snapshot::transactions_mut(snapshot) {
+        quantities_nested(tx);
+    }
+}
+
+fn quantities_nested(value: &mut Value) {
+    match value {
+        Value::Object(map) => {
+            for (key, nested) in map.iter_mut() {
+                if QUANTITY_FIELDS.contains(&key.as_str())
+                    && matches!(nested, Value::Number(_) | Value::Bool(_) | Value::String(_))
+                    && let Some(hex) = to_hex_quantity(nested)
+                {
+                    *nested = Value::String(hex);
+                } else {
+                    quantities_nested(nested);
+                }
+            }
+        }
+        Value::Array(items) => items.iter_mut().for_each(quantities_nested),
+        _ => {}
+    }
+}
@@ -0,0 +1,150 @@
+use serde_json::Value;
+use std::error::Error;
+use std::io::{self, Write};
+
+use crate::snapshot::SECTIONS;
+use crate::{avro, cbor, inspect_text, protobuf, table};
+
+/// Encoding of the main snapshot file.
+#[derive(Clone, Copy, Default, PartialEq)]
+pub enum OutputFormat {
+    /// Pretty-printed JSON with the full nested transaction structure.
+    #[default]
+    Json,
+    /// Avro object container of flat transaction records.
+    Avro,
+    /// Length-delimited `txpool.v1.PoolTransaction` messages (`proto/txpool.proto`).
+    Protobuf,
+    /// CBOR of the full snapshot; `deterministic` selects core deterministic encoding.
+    Cbor { deterministic: bool },
+    /// Aligned text table shown in the terminal instead of being written to a file.
+    Table,
+    /// `txpool_inspect`-style summary lines, shown in the terminal like `Table`.
+    InspectText,
+}
+
+impl OutputFormat {
+    pub fn parse(name: &str) -> Result<Self, Box<dyn Error>> {
+        match name {
+            "json" => Ok(OutputFormat::Json),
+            "avro" => Ok(OutputFormat::Avro),
+            "protobuf" => Ok(OutputFormat::Protobuf),
+            "cbor" => Ok(OutputFormat::Cbor { deterministic: false }),
+            "cbor-deterministic" => Ok(OutputFormat::Cbor { deterministic: true }),
+            "table" => Ok(OutputFormat::Table),
+            "inspect-text" => Ok(OutputFormat::InspectText),
+            other => Err(format!("Unknown output format: {}", other).into()),
+        }
+    }
+
+    pub fn extension(self) -> &'static str {
+        match self {
+            OutputFormat::Json => "json",
+            OutputFormat::Avro => "avro",
+            OutputFormat::Protobuf => "pb",
+            OutputFormat::Cbor { .. } => "cbor",
+            OutputFormat::Table | OutputFormat::InspectText => "txt",
+        }
+    }
+
+    /// Printed to the terminal (through the pager) rather than written to a file.
+    pub fn is_printed(self) -> bool {
+        matches!(self, OutputFormat::Table | OutputFormat::InspectText)
+    }
+}
+
+/// Indentation of the pretty JSON output, as in `serde_json::to_string_pretty`.
+const INDENT: &[u8] = b"  ";
+
+fn indent<W: Write>(writer: &mut W, level: usize) -> io::Result<()> {
+    for _ in 0..level {
+        writer.write_all(INDENT)?;
+    }
+    Ok(())
+}
+
+/// Writer that counts the bytes passed through it.
+struct Counting<W> {
+    inner: W,
+    bytes: usize,
+}
+
+impl<W: Write> Write for Counting<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let written = self.inner.write(buf)?;
+        self.bytes += written;
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+/// Write `value` as pretty JSON nested `level` deep in a pretty document.
+/// JSON strings cannot contain raw newlines, so every line break is
+/// structural and only needs the extra indentation.
+fn write_value<W: Write>(writer: &mut W, value: &Value, level: usize) -> Result<(), Box<dyn Error>> {
+    let pretty = serde_json::to_vec_pretty(value)?;
+    for (index, line) in pretty.split(|byte| *byte == b'\n').enumerate() {
+        if index > 0 {
+            writer.write_all(b"\n")?;
+            indent(writer, level)?;
+        }
+        writer.write_all(line)?;
+    }
+    Ok(())
+}
+
+/// Write the snapshot as pretty JSON without building the whole document in
+/// memory: each account of a section is serialized and flushed on its own.
+/// The bytes are the same as `serde_json::to_string_pretty`'s. Returns the
+/// number of bytes written.
+pub fn write_json<W: Write>(snapshot: &Value, writer: W) -> Result<usize, Box<dyn Error>> {
+    let mut writer = Counting { inner: writer, bytes: 0 };
+    let Some(document) = snapshot.as_object().filter(|document| !document.is_empty()) else {
+        write_value(&mut writer, snapshot, 0)?;
+        writer.flush()?;
+        return Ok(writer.bytes);
+    };
+
+    writer.write_all(b"{")?;
+    for (index, (key, value)) in document.iter().enumerate() {
+        writer.write_all(if index == 0 { b"\n" } else { b",\n" })?;
+        indent(&mut writer, 1)?;
+        write_value(&mut writer, &Value::String(key.clone()), 0)?;
+        writer.write_all(b": ")?;
+        match value.as_object().filter(|accounts| !accounts.is_empty() && SECTIONS.contains(&key.as_str())) {
+            Some(accounts) => {
+                writer.write_all(b"{")?;
+                for (index, (account, transactions)) in accounts.iter().enumerate() {
+                    writer.write_all(if index == 0 { b"\n" } else { b",\n" })?;
+                    indent(&mut writer, 2)?;
+                    write_value(&mut writer, &Value::String(account.clone()), 0)?;
+                    writer.write_all(b": ")?;
+                    write_value(&mut writer, transactions, 2)?;
+                    writer.flush()?;
+                }
+                writer.write_all(b"\n")?;
+                indent(&mut writer, 1)?;
+                writer.write_all(b"}")?;
+            }
+            None => write_value(&mut writer, value, 1)?,
+        }
+    }
+    writer.write_all(b"\n}")?;
+    writer.flush()?;
+    Ok(writer.bytes)
+}
+
+/// Serialize the snapshot in `format`.
+pub fn render(format: OutputFormat, snapshot: &Value) -> Result<Vec<u8>, Box<dyn Error>> {
+    Ok(match format {
+        OutputFormat::Json => serde_json::to_string_pretty(snapshot)?.into_bytes(),
+        OutputFormat::Avro => avro::to_container(snapshot),
+        OutputFormat::Protobuf => protobuf::to_delimited(snapshot),
+        OutputFormat::Cbor { deterministic } => cbor::encode(snapshot, deterministic),
+        OutputFormat::Table => table::to_table(snapshot).into_bytes(),
+        OutputFormat::InspectText => inspect_text::render(snapshot).into_bytes(),
+    })
+}
@@ -0,0 +1,50 @@
+use std::io::{self, BufRead, IsTerminal, Write};
+use std::time::{Duration, Instant};
+
+use crate::budget::{self, Input};
+
+/// Line that ends a paste, for terminals where Ctrl-D is awkward to send.
+const SENTINEL: &str = "EOF";
+
+/// Shortest time between two updates of the byte counter.
+const REDRAW_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Read a dump pasted into the terminal, up to Ctrl-D or an `EOF` line, with
+/// a live byte counter on stderr. Past `max_bytes`, reading stops and the
+/// paste is cut as [`budget::read`] would cut piped input.
+pub fn read(max_bytes: Option<usize>) -> io::Result<Input> {
+    eprintln!(
+        "Reading a txpool dump from the terminal. Paste it, then press Ctrl-D or enter a line with {}.",
+        SENTINEL
+    );
+    eprintln!("(Pipe a file or command output in to skip this, e.g. `cast tx-pool content | rust-txpool`.)");
+    let counter = io::stderr().is_terminal();
+    let mut text = String::new();
+    let mut last_redraw = Instant::now();
+    for line in io::stdin().lock().lines() {
+        let line = line?;
+        if line.trim() == SENTINEL {
+            break;
+        }
+        text.push_str(&line);
+        text.push('\n');
+        if counter && last_redraw.elapsed() >= REDRAW_INTERVAL {
+            // Drawn at the start of the line, so the terminal's echo of the
+            // next pasted line writes over it
+            eprint!("\x1b[2K{} bytes read\r", text.len());
+            let _ = io::stderr().flush();
+            last_redraw = Instant::now();
+        }
+        if max_bytes.is_some_and(|max| text.len() > max) {
+            break;
+        }
+    }
+    if counter {
+        eprint!("\x1b[2K");
+    }
+    eprintln!("{} bytes read", text.len());
+    if text.trim().is_empty() {
+        return Err(io::Error::new(io::ErrorKind::UnexpectedEof, "Nothing was pasted"));
+    }
+    budget::read(text.as_bytes(), max_bytes)
+}
@@ -0,0 +1,55 @@
+use serde_json::{Value, json};
+
+use crate::snapshot;
+use crate::stats::percentile;
+
+/// Give every transaction with a `first_seen` an `age_seconds` (time in the
+/// pool as of `now`) and record the pool's age distribution in
+/// `metadata.age`, emitted as `txpool.pool.age_seconds` percentiles.
+/// Snapshots without `first_seen` (no `--track-inclusion` chain) are left
+/// alone.
+pub fn annotate(snapshot: &mut Value, now: u64) {
+    let mut ages = Vec::new();
+    for tx in snapshot::transactions_mut(snapshot) {
+        let Some(first_seen) = tx.get("first_seen").and_then(Value::as_u64) else {
+            continue;
+        };
+        let age = now.saturating_sub(first_seen);
+        ages.push(age);
+        if let Some(tx) = tx.as_object_mut() {
+            tx.insert("age_seconds".to_string(), json!(age));
+        }
+    }
+    if ages.is_empty() {
+        return;
+    }
+
+    ages.sort_unstable();
+    // Transactions first seen in this snapshot are 0 s old; the rest date
+    // back to earlier snapshots of the chain.
+    let new = ages.iter().take_while(|age| **age == 0).count();
+    for (quantile, p) in [("p50", 50.0), ("p90", 90.0), ("p99", 99.0), ("max", 100.0)] {
+        let metric_value = percentile(&ages, p).unwrap_or(0);
+        emit::emit!(
+            "{metric_agg} of {metric_name} is {metric_value}",
+            evt_kind: "metric",
+            metric_agg: "last",
+            metric_name: "txpool.pool.age_seconds",
+            metric_value,
+            quantile,
+        );
+    }
+    let mean = ages.iter().sum::<u64>() as f64 / ages.len() as f64;
+    snapshot::metadata_mut(snapshot).insert(
+        "age".to_string(),
+        json!({
+            "txs": ages.len(),
+            "new_txs": new,
+            "mean": mean,
+            "p50": percentile(&ages, 50.0),
+            "p90": percentile(&ages, 90.0),
+            "p99": percentile(&ages, 99.0),
+            "max": ages.last(),
+        }),
+    );
+}
@@ -0,0 +1,48 @@
+use serde_json::{Map, Value};
+
+use crate::snapshot::{self, find_field};
+
+/// Copy of the snapshot in which every transaction is replaced by a flat
+/// object holding only `fields`, looked up anywhere in the nested
+/// transaction. `from` falls back to the sender account key and `nonce` to
+/// the nonce key, so both are always present. Fields a transaction lacks are
+/// left out rather than set to null. Metadata is kept unchanged.
+pub fn project(snapshot: &Value, fields: &[String]) -> Value {
+    let mut projected = Map::new();
+    if let Some(metadata) = snapshot.get("metadata") {
+        projected.insert("metadata".to_string(), metadata.clone());
+    }
+    for section in snapshot::SECTIONS {
+        if snapshot.get(section).is_some() {
+            projected.insert(section.to_string(), Value::Object(Map::new()));
+        }
+    }
+
+    for entry in snapshot::transactions(snapshot) {
+        let mut tx = Map::new();
+        for field in fields {
+            let value = match field.as_str() {
+                "from" => find_field(entry.tx, "from")
+                    .or_else(|| find_field(entry.tx, "signer"))
+                    .cloned()
+                    .or_else(|| Some(Value::String(entry.account.to_string()))),
+                "nonce" => find_field(entry.tx, "nonce")
+                    .cloned()
+                    .or_else(|| entry.nonce.parse::<u64>().ok().map(Value::from)),
+                other => find_field(entry.tx, other).cloned(),
+            };
+            if let Some(value) = value {
+                tx.insert(field.clone(), value);
+            }
+        }
+        projected[entry.section]
+            .as_object_mut()
+            .expect("section created above")
+            .entry(entry.account.to_string())
+            .or_insert_with(|| Value::Object(Map::new()))
+            .as_object_mut()
+            .expect("account is always an object")
+            .insert(entry.nonce.to_string(), Value::Object(tx));
+    }
+    Value::Object(projected)
+}
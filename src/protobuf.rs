@@ -0,0 +1,182 @@
+use serde_json::Value;
+
+use crate::blob;
+use crate::chain::tx_chain_id;
+use crate::quantity::{hex_to_bytes, to_be_bytes, to_u128};
+use crate::rlp;
+use crate::snapshot::{self, find_field};
+
+const VARINT: u64 = 0;
+const LEN: u64 = 2;
+
+/// Length-delimited `txpool.v1.PoolTransaction` messages (see
+/// `proto/txpool.proto`), one per transaction.
+pub fn to_delimited(snapshot: &Value) -> Vec<u8> {
+    let snapshot_time = snapshot::captured_at(snapshot).unwrap_or(0);
+    let mut out = Vec::new();
+    for entry in snapshot::transactions(snapshot) {
+        let Ok(nonce) = entry.nonce.parse::<u64>() else {
+            continue;
+        };
+        let tx = entry.tx;
+        let hex = |key: &str| {
+            find_field(tx, key)
+                .and_then(Value::as_str)
+                .and_then(hex_to_bytes)
+                .unwrap_or_default()
+        };
+        let amount = |key: &str| find_field(tx, key).and_then(to_be_bytes).unwrap_or_default();
+        let uint = |key: &str| {
+            find_field(tx, key)
+                .and_then(to_u128)
+                .and_then(|n| u64::try_from(n).ok())
+                .unwrap_or(0)
+        };
+
+        let mut message = Vec::new();
+        uint64(&mut message, 1, snapshot_time);
+        if let Some(first_seen) = tx.get("first_seen").and_then(Value::as_u64) {
+            field(&mut message, 2, VARINT);
+            varint(&mut message, first_seen);
+        }
+        uint64(&mut message, 3, if entry.section == "pending" { 1 } else { 2 });
+        bytes(&mut message, 4, &hex_to_bytes(entry.account).unwrap_or_default());
+        uint64(&mut message, 5, nonce);
+        uint64(&mut message, 6, u64::from(rlp::tx_type(tx)));
+        if let Some(chain_id) = tx_chain_id(tx) {
+            field(&mut message, 7, VARINT);
+            varint(&mut message, chain_id);
+        }
+        bytes(&mut message, 8, &hex("hash"));
+        bytes(&mut message, 9, &hex("to"));
+        bytes(&mut message, 10, &amount("value"));
+        uint64(&mut message, 11, uint("gas_limit"));
+        bytes(&mut message, 12, &amount("gas_price"));
+        bytes(&mut message, 13, &amount("max_fee_per_gas"));
+        bytes(&mut message, 14, &amount("max_priority_fee_per_gas"));
+        bytes(&mut message, 15, &amount("max_fee_per_blob_gas"));
+        uint64(&mut message, 16, blob::blob_count(tx) as u64);
+        bytes(&mut message, 17, &hex("input"));
+        uint64(&mut message, 18, uint("size_bytes"));
+
+        varint(&mut out, message.len() as u64);
+        out.extend(message);
+    }
+    out
+}
+
+fn varint(out: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        out.push(value as u8 | 0x80);
+        value >>= 7;
+    }
+    out.push(value as u8);
+}
+
+fn field(out: &mut Vec<u8>, number: u64, wire_type: u64) {
+    varint(out, (number << 3) | wire_type);
+}
+
+/// A proto3 scalar varint field, omitted when zero.
+fn uint64(out: &mut Vec<u8>, number: u64, value: u64) {
+    if value != 0 {
+        field(out, number, VARINT);
+        varint(out, value);
+    }
+}
+
+/// A proto3 `bytes` field, omitted when empty.
+fn bytes(out: &mut Vec<u8>, number: u64, value: &[u8]) {
+    if !value.is_empty() {
+        field(out, number, LEN);
+        varint(out, value.len() as u64);
+        out.extend_from_slice(value);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn delimited_messages_follow_the_proto() {
+        let account = "0x09f1fd9d03f0a9b4553274161bbf84755d5bca46";
+        let snapshot = json!({
+            "metadata": { "captured_at": "2024-01-01T00:00:00Z" },
+            "pending": { account: { "7": { "inner": { "inner": { "inner": {
+                "tx": {
+                    "chain_id": 1,
+                    "nonce": 7,
+                    "gas_limit": 21000,
+                    "max_fee_per_gas": 30000000000u64,
+                    "max_priority_fee_per_gas": 1000000000,
+                    "to": "0xff9b273f422863872601349fef087eb05753f8ef",
+                    "value": 1000,
+                    "access_list": [],
+                    "input": "0x6000"
+                },
+                "hash": "0xab"
+            } } } } } },
+            "queued": { account: { "9": { "inner": { "inner": { "inner": {
+                "tx": { "chain_id": 1, "nonce": 9, "gas_limit": 53000, "gas_price": 2, "to": null, "value": 0, "input": "0x" }
+            } } } } } }
+        });
+        // Field numbers and wire types of `proto/txpool.proto`; zero and empty
+        // values are left out as proto3 does.
+        let expected = [
+            "57",                                           // length prefix
+            "088081c8ac06",                                 // 1 snapshot_time 1704067200
+            "1801",                                         // 3 section PENDING
+            "221409f1fd9d03f0a9b4553274161bbf84755d5bca46", // 4 from
+            "2807",                                         // 5 nonce
+            "3002",                                         // 6 tx_type
+            "3801",                                         // 7 chain_id
+            "4201ab",                                       // 8 hash
+            "4a14ff9b273f422863872601349fef087eb05753f8ef", // 9 to
+            "520203e8",                                     // 10 value 1000
+            "5888a401",                                     // 11 gas_limit 21000
+            "6a0506fc23ac00",                               // 13 max_fee_per_gas
+            "72043b9aca00",                                 // 14 max_priority_fee_per_gas
+            "8a01026000",                                   // 17 input
+            "29",                                           // length prefix
+            "088081c8ac06",                                 // 1 snapshot_time
+            "1802",                                         // 3 section QUEUED
+            "221409f1fd9d03f0a9b4553274161bbf84755d5bca46", // 4 from
+            "2809",                                         // 5 nonce
+            "3801",                                         // 7 chain_id
+            "58889e03",                                     // 11 gas_limit 53000
+            "620102",                                       // 12 gas_price
+        ]
+        .concat();
+        let encoded: String = to_delimited(&snapshot).iter().map(|b| format!("{:02x}", b)).collect();
+        assert_eq!(encoded, expected);
+    }
+
+    #[test]
+    fn field_numbers_match_the_proto() {
+        let proto = include_str!("../proto/txpool.proto");
+        for declaration in [
+            "uint64 snapshot_time = 1;",
+            "optional uint64 first_seen = 2;",
+            "Section section = 3;",
+            "bytes from = 4;",
+            "uint64 nonce = 5;",
+            "uint32 tx_type = 6;",
+            "optional uint64 chain_id = 7;",
+            "bytes hash = 8;",
+            "bytes to = 9;",
+            "bytes value = 10;",
+            "uint64 gas_limit = 11;",
+            "bytes gas_price = 12;",
+            "bytes max_fee_per_gas = 13;",
+            "bytes max_priority_fee_per_gas = 14;",
+            "bytes max_fee_per_blob_gas = 15;",
+            "uint32 blob_count = 16;",
+            "bytes input = 17;",
+            "uint64 size_bytes = 18;",
+        ] {
+            assert!(proto.contains(declaration), "{}", declaration);
+        }
+    }
+}
@@ -0,0 +1,130 @@
+use serde_json::{Value, json};
+use std::fmt::Write;
+
+/// Read an unsigned quantity from a snapshot value.
+///
+/// Accepts JSON integers of any width (numbers keep their exact digits) and
+/// decimal or `0x` hex strings.
+pub fn to_u128(value: &Value) -> Option<u128> {
+    match value {
+        Value::Number(n) => n
+            .as_u64()
+            .map(u128::from)
+            .or_else(|| n.to_string().parse().ok()),
+        Value::String(s) => parse_u128(s),
+        _ => None,
+    }
+}
+
+/// Parse a decimal or `0x` hex string into a `u128`.
+pub fn parse_u128(text: &str) -> Option<u128> {
+    let text = text.trim();
+    match text.strip_prefix("0x") {
+        Some("") => Some(0),
+        Some(hex) => u128::from_str_radix(hex, 16).ok(),
+        None => text.replace('_', "").parse().ok(),
+    }
+}
+
+/// Minimal big-endian bytes of a quantity up to 256 bits (empty for zero), as
+/// RLP expects integers to be encoded.
+pub fn to_be_bytes(value: &Value) -> Option<Vec<u8>> {
+    let mut bytes = match value {
+        Value::Number(n) => match n.as_u64() {
+            Some(v) => v.to_be_bytes().to_vec(),
+            None => decimal_to_be_bytes(&n.to_string())?,
+        },
+        Value::String(s) => match s.strip_prefix("0x") {
+            Some(hex) => hex_to_bytes(&format!("{}{}", if hex.len() % 2 == 1 { "0" } else { "" }, hex))?,
+            None => decimal_to_be_bytes(s)?,
+        },
+        Value::Bool(b) => vec![u8::from(*b)],
+        _ => return None,
+    };
+    let leading = bytes.iter().take_while(|b| **b == 0).count();
+    bytes.drain(..leading);
+    Some(bytes)
+}
+
+/// JSON value for a wei amount: a number when it fits in `u64`, otherwise a
+/// decimal string so large amounts are never rounded through `f64`.
+pub fn to_json(amount: u128) -> Value {
+    match u64::try_from(amount) {
+        Ok(small) => json!(small),
+        Err(_) => json!(amount.to_string()),
+    }
+}
+
+/// `amount` as a decimal number of `10^decimals` units, truncated to `places`
+/// fractional digits (e.g. wei as gwei with `9, 2`).
+pub fn format_units(amount: u128, decimals: u32, places: u32) -> String {
+    let unit = 10u128.pow(decimals);
+    let fraction = (amount % unit) / 10u128.pow(decimals - places);
+    format!("{}.{:0width$}", amount / unit, fraction, width = places as usize)
+}
+
+/// Decode a hex string with or without a `0x` prefix.
+pub fn hex_to_bytes(hex: &str) -> Option<Vec<u8>> {
+    let hex = hex.strip_prefix("0x").unwrap_or(hex);
+    if !hex.len().is_multiple_of(2) {
+        return None;
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+/// A quantity as a JSON-RPC `QUANTITY`: lowercase `0x` hex without leading
+/// zeros, `0x0` for zero.
+pub fn to_hex_quantity(value: &Value) -> Option<String> {
+    let hex = bytes_to_hex(&to_be_bytes(value)?);
+    let digits = hex[2..].trim_start_matches('0');
+    Some(format!("0x{}", if digits.is_empty() { "0" } else { digits }))
+}
+
+/// Encode bytes as a lowercase `0x` hex string.
+pub fn bytes_to_hex(bytes: &[u8]) -> String {
+    let mut out = String::with_capacity(2 + bytes.len() * 2);
+    out.push_str("0x");
+    for byte in bytes {
+        let _ = write!(out, "{:02x}", byte);
+    }
+    out
+}
+
+fn decimal_to_be_bytes(text: &str) -> Option<Vec<u8>> {
+    let mut bytes = vec![0u8; 32];
+    for digit in text.trim().chars() {
+        let mut carry = digit.to_digit(10)?;
+        for byte in bytes.iter_mut().rev() {
+            let next = u32::from(*byte) * 10 + carry;
+            *byte = next as u8;
+            carry = next >> 8;
+        }
+        if carry != 0 {
+            return None;
+        }
+    }
+    Some(bytes)
+}
+
+/// Decimal digits of a big-endian unsigned integer of any width.
+pub fn be_bytes_to_decimal(bytes: &[u8]) -> String {
+    let mut number = bytes.to_vec();
+    let mut digits = Vec::new();
+    while number.iter().any(|b| *b != 0) {
+        let mut remainder = 0u32;
+        for byte in number.iter_mut() {
+            let current = (remainder << 8) | u32::from(*byte);
+            *byte = (current / 10) as u8;
+            remainder = current % 10;
+        }
+        digits.push(b'0' + remainder as u8);
+    }
+    if digits.is_empty() {
+        return "0".to_string();
+    }
+    digits.reverse();
+    String::from_utf8(digits).unwrap_or_default()
+}
@@ -0,0 +1,49 @@
+use serde_json::{Value, json};
+use std::collections::BTreeMap;
+
+use crate::filter::TxFilter;
+use crate::quantity::bytes_to_hex;
+use crate::rlp;
+use crate::snapshot;
+
+/// Raw signed envelopes of the transactions matching `filter`, ordered by
+/// sender and then nonce so each sender's transactions replay in sequence.
+/// Transactions that cannot be re-encoded (inspect summaries, unknown
+/// envelopes) are left out.
+pub fn selected_raw(snapshot: &Value, filter: &TxFilter) -> Vec<String> {
+    let mut by_sender: BTreeMap<String, Vec<(u64, String)>> = BTreeMap::new();
+    for entry in snapshot::transactions(snapshot).filter(|entry| filter.matches(entry)) {
+        let (Ok(nonce), Some(raw)) = (entry.nonce.parse::<u64>(), rlp::encode_signed(entry.tx)) else {
+            continue;
+        };
+        by_sender
+            .entry(entry.account.to_ascii_lowercase())
+            .or_default()
+            .push((nonce, bytes_to_hex(&raw)));
+    }
+    by_sender
+        .into_values()
+        .flat_map(|mut txs| {
+            txs.sort();
+            txs.into_iter().map(|(_, raw)| raw)
+        })
+        .collect()
+}
+
+/// JSON-RPC batch request body with one `eth_sendRawTransaction` per
+/// selected transaction, ready for `curl --data @file`.
+pub fn send_raw_batch(snapshot: &Value, filter: &TxFilter) -> Value {
+    let requests: Vec<Value> = selected_raw(snapshot, filter)
+        .into_iter()
+        .enumerate()
+        .map(|(id, raw)| {
+            json!({
+                "jsonrpc": "2.0",
+                "id": id + 1,
+                "method": "eth_sendRawTransaction",
+                "params": [raw],
+            })
+        })
+        .collect();
+    Value::Array(requests)
+}
@@ -0,0 +1,108 @@
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+use std::error::Error;
+use std::fs;
+
+use crate::snapshot;
+
+/// Output field renames loaded from a JSON config file:
+///
+/// ```json
+/// { "case": "camelCase", "fields": { "input": "data" } }
+/// ```
+///
+/// `fields` maps parsed field names to the names written to the output;
+/// `case` (`camelCase` or `snake_case`, the default) converts every other
+/// field name. Explicit entries take precedence over the case conversion.
+#[derive(Clone, Default)]
+pub struct RenameMap {
+    fields: HashMap<String, String>,
+    camel_case: bool,
+}
+
+impl RenameMap {
+    pub fn load(path: &str) -> Result<Self, Box<dyn Error>> {
+        let config: Value = serde_json::from_str(&fs::read_to_string(path)?)
+            .map_err(|err| format!("Invalid rename config {}: {}", path, err))?;
+        let config = config
+            .as_object()
+            .ok_or_else(|| format!("Rename config {} must be a JSON object", path))?;
+
+        let mut map = RenameMap::default();
+        for (key, value) in config {
+            match key.as_str() {
+                "case" => {
+                    map.camel_case = match value.as_str() {
+                        Some("camelCase") => true,
+                        Some("snake_case") => false,
+                        _ => return Err(format!("Unknown case in {}: {} (camelCase, snake_case)", path, value).into()),
+                    }
+                }
+                "fields" => {
+                    let fields = value
+                        .as_object()
+                        .ok_or_else(|| format!("`fields` in {} must be an object", path))?;
+                    for (from, to) in fields {
+                        let to = to
+                            .as_str()
+                            .ok_or_else(|| format!("Rename target for {} must be a string", from))?;
+                        map.fields.insert(from.clone(), to.to_string());
+                    }
+                }
+                other => return Err(format!("Unknown key in rename config {}: {}", path, other).into()),
+            }
+        }
+        Ok(map)
+    }
+
+    fn rename(&self, field: &str) -> String {
+        if let Some(renamed) = self.fields.get(field) {
+            renamed.clone()
+        } else if self.camel_case {
+            to_camel_case(field)
+        } else {
+            field.to_string()
+        }
+    }
+
+    /// Rename the fields of every transaction, at any depth. Pool sections,
+    /// account addresses, nonces and metadata keep their names.
+    pub fn apply(&self, snapshot: &mut Value) {
+        for tx in snapshot::transactions_mut(snapshot) {
+            self.rename_keys(tx);
+        }
+    }
+
+    fn rename_keys(&self, value: &mut Value) {
+        match value {
+            Value::Object(map) => {
+                let renamed: Map<String, Value> = std::mem::take(map)
+                    .into_iter()
+                    .map(|(key, mut value)| {
+                        self.rename_keys(&mut value);
+                        (self.rename(&key), value)
+                    })
+                    .collect();
+                *map = renamed;
+            }
+            Value::Array(items) => items.iter_mut().for_each(|item| self.rename_keys(item)),
+            _ => {}
+        }
+    }
+}
+
+fn to_camel_case(field: &str) -> String {
+    let mut out = String::with_capacity(field.len());
+    let mut upper = false;
+    for c in field.chars() {
+        if c == '_' && !out.is_empty() {
+            upper = true;
+        } else if upper {
+            out.extend(c.to_uppercase());
+            upper = false;
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
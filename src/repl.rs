@@ -0,0 +1,210 @@
+use rustyline::completion::{Completer, Pair};
+use rustyline::error::ReadlineError;
+use rustyline::highlight::Highlighter;
+use rustyline::hint::Hinter;
+use rustyline::history::DefaultHistory;
+use rustyline::validate::Validator;
+use rustyline::{Context, Editor, Helper};
+use serde_json::Value;
+use std::collections::{BTreeSet, HashMap};
+use std::error::Error;
+use std::fs;
+
+use crate::filter::{self, TxFilter, tx_to};
+use crate::snapshot::{self, find_field};
+
+const COMMANDS: [&str; 7] = ["help", "summary", "top", "find", "show", "quit", "exit"];
+const TOP_KINDS: [&str; 2] = ["senders", "recipients"];
+
+const HELP: &str = "\
+Commands:
+  summary                      transaction, sender and section counts
+  top senders|recipients [n]   accounts with the most transactions (default 10)
+  find key=value ...           transactions matching all filters
+                               (from=, to=, nonce=, section=, type=)
+  show <txhash>                full transaction JSON
+  quit                         leave the REPL";
+
+/// Interactive prompt for exploring a converted snapshot.
+pub fn run(path: &str) -> Result<(), Box<dyn Error>> {
+    let snapshot: Value = serde_json::from_str(&fs::read_to_string(path)?)?;
+    let helper = ReplHelper::new(&snapshot);
+
+    let mut editor: Editor<ReplHelper, DefaultHistory> = Editor::new()?;
+    editor.set_helper(Some(helper));
+    println!("Loaded {} ({} transactions). Type `help` for commands.", path, snapshot::transactions(&snapshot).count());
+
+    loop {
+        let line = match editor.readline("txpool> ") {
+            Ok(line) => line,
+            Err(ReadlineError::Interrupted | ReadlineError::Eof) => break,
+            Err(err) => return Err(err.into()),
+        };
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        editor.add_history_entry(line)?;
+        if matches!(line, "quit" | "exit") {
+            break;
+        }
+        match execute(&snapshot, line) {
+            Ok(output) => println!("{}", output),
+            Err(err) => println!("Error: {}", err),
+        }
+    }
+    Ok(())
+}
+
+/// Run one REPL command against the snapshot and return its output.
+pub fn execute(snapshot: &Value, line: &str) -> Result<String, Box<dyn Error>> {
+    let words: Vec<&str> = line.split_whitespace().collect();
+    match words.as_slice() {
+        ["help"] => Ok(HELP.to_string()),
+        ["summary"] => Ok(summary(snapshot)),
+        ["top", kind, rest @ ..] => {
+            let limit = match rest {
+                [] => 10,
+                [n] => n.parse()?,
+                _ => return Err("usage: top senders|recipients [n]".into()),
+            };
+            top(snapshot, kind, limit)
+        }
+        ["find", filters @ ..] if !filters.is_empty() => find(snapshot, filters),
+        ["show", hash] => show(snapshot, hash),
+        _ => Err(format!("Unknown command: {} (try `help`)", line).into()),
+    }
+}
+
+fn tx_hash(tx: &Value) -> String {
+    find_field(tx, "hash")
+        .and_then(Value::as_str)
+        .unwrap_or("-")
+        .to_string()
+}
+
+fn summary(snapshot: &Value) -> String {
+    let mut lines = Vec::new();
+    for section in snapshot::SECTIONS {
+        let accounts = snapshot[section].as_object().map_or(0, |accounts| accounts.len());
+        let txs = snapshot::transactions(snapshot)
+            .filter(|entry| entry.section == section)
+            .count();
+        lines.push(format!("{:<8} {:>8} txs  {:>6} senders", section, txs, accounts));
+    }
+    lines.join("\n")
+}
+
+fn top(snapshot: &Value, kind: &str, limit: usize) -> Result<String, Box<dyn Error>> {
+    let mut counts: HashMap<String, usize> = HashMap::new();
+    for entry in snapshot::transactions(snapshot) {
+        let account = match kind {
+            "senders" => Some(entry.account.to_ascii_lowercase()),
+            "recipients" => tx_to(entry.tx),
+            other => return Err(format!("Unknown top list: {} (senders, recipients)", other).into()),
+        };
+        if let Some(account) = account {
+            *counts.entry(account).or_insert(0) += 1;
+        }
+    }
+
+    let mut counts: Vec<(String, usize)> = counts.into_iter().collect();
+    counts.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+    Ok(counts
+        .into_iter()
+        .take(limit)
+        .map(|(account, count)| format!("{:>6}  {}", count, account))
+        .collect::<Vec<_>>()
+        .join("\n"))
+}
+
+fn find(snapshot: &Value, filters: &[&str]) -> Result<String, Box<dyn Error>> {
+    let filter = TxFilter::parse(filters)?;
+    let lines: Vec<String> = snapshot::transactions(snapshot)
+        .filter(|entry| filter.matches(entry))
+        .map(|entry| {
+            format!(
+                "{:<7} {} {:>6}  {}",
+                entry.section,
+                entry.account,
+                entry.nonce,
+                tx_hash(entry.tx)
+            )
+        })
+        .collect();
+    Ok(format!("{}\n{} matching transactions", lines.join("\n"), lines.len()))
+}
+
+fn show(snapshot: &Value, hash: &str) -> Result<String, Box<dyn Error>> {
+    let entry = snapshot::transactions(snapshot)
+        .find(|entry| tx_hash(entry.tx).eq_ignore_ascii_case(hash))
+        .ok_or_else(|| format!("No transaction with hash {}", hash))?;
+    Ok(serde_json::to_string_pretty(entry.tx)?)
+}
+
+/// Tab completion for commands, their arguments, addresses and hashes.
+struct ReplHelper {
+    addresses: BTreeSet<String>,
+    hashes: BTreeSet<String>,
+}
+
+impl ReplHelper {
+    fn new(snapshot: &Value) -> Self {
+        let mut addresses = BTreeSet::new();
+        let mut hashes = BTreeSet::new();
+        for entry in snapshot::transactions(snapshot) {
+            addresses.insert(entry.account.to_ascii_lowercase());
+            addresses.extend(tx_to(entry.tx));
+            hashes.insert(tx_hash(entry.tx).to_ascii_lowercase());
+        }
+        ReplHelper { addresses, hashes }
+    }
+
+    fn candidates(&self, previous: &[&str], word: &str) -> Vec<String> {
+        let matching = |options: &mut dyn Iterator<Item = String>| -> Vec<String> {
+            options.filter(|option| option.starts_with(word)).collect()
+        };
+        match previous {
+            [] => matching(&mut COMMANDS.iter().map(|c| c.to_string())),
+            ["top"] => matching(&mut TOP_KINDS.iter().map(|k| k.to_string())),
+            ["show"] => matching(&mut self.hashes.iter().cloned()),
+            ["find", ..] => match word.split_once('=') {
+                Some((key @ ("from" | "to"), _)) => {
+                    matching(&mut self.addresses.iter().map(|address| format!("{}={}", key, address)))
+                }
+                Some((_, _)) => Vec::new(),
+                None => matching(&mut filter::KEYS.iter().map(|k| k.to_string())),
+            },
+            _ => Vec::new(),
+        }
+    }
+}
+
+impl Completer for ReplHelper {
+    type Candidate = Pair;
+
+    fn complete(&self, line: &str, pos: usize, _ctx: &Context<'_>) -> rustyline::Result<(usize, Vec<Pair>)> {
+        let line = &line[..pos];
+        let start = line.rfind(char::is_whitespace).map_or(0, |i| i + 1);
+        let previous: Vec<&str> = line[..start].split_whitespace().collect();
+        let candidates = self
+            .candidates(&previous, &line[start..])
+            .into_iter()
+            .map(|candidate| Pair {
+                display: candidate.clone(),
+                replacement: candidate,
+            })
+            .collect();
+        Ok((start, candidates))
+    }
+}
+
+impl Hinter for ReplHelper {
+    type Hint = String;
+}
+
+impl Highlighter for ReplHelper {}
+
+impl Validator for ReplHelper {}
+
+impl Helper for ReplHelper {}
@@ -0,0 +1,216 @@
+use serde_json::{Map, Value, json};
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fs;
+use std::net::TcpListener;
+use std::process::{Child, Command, Stdio};
+use std::thread;
+use std::time::Duration;
+
+use crate::cli::ReplayOptions;
+use crate::filter::TxFilter;
+use crate::quantity::to_hex_quantity;
+use crate::rlp;
+use crate::rpc::RpcClient;
+use crate::snapshot::{self, find_field};
+
+/// Node replayed against when neither `--anvil` nor `--rpc-url` is given.
+const DEFAULT_RPC_URL: &str = "http://127.0.0.1:8545";
+
+/// How long a spawned anvil gets to start answering requests.
+const ANVIL_STARTUP_ATTEMPTS: usize = 120;
+
+/// An anvil fork started for the replay, killed when dropped.
+struct Anvil {
+    child: Child,
+    url: String,
+}
+
+impl Anvil {
+    fn spawn(fork_url: &str, fork_block: Option<u64>) -> Result<Self, Box<dyn Error>> {
+        let port = TcpListener::bind("127.0.0.1:0")?.local_addr()?.port();
+        let mut command = Command::new("anvil");
+        command
+            .args(["--fork-url", fork_url, "--port", &port.to_string()])
+            .stdout(Stdio::null())
+            .stderr(Stdio::null());
+        if let Some(block) = fork_block {
+            command.args(["--fork-block-number", &block.to_string()]);
+        }
+        let child = command
+            .spawn()
+            .map_err(|err| format!("Could not start anvil (is Foundry installed?): {}", err))?;
+        let anvil = Anvil {
+            child,
+            url: format!("http://127.0.0.1:{}", port),
+        };
+
+        let client = RpcClient::new(&anvil.url);
+        for _ in 0..ANVIL_STARTUP_ATTEMPTS {
+            if client.call("eth_chainId", json!([])).is_ok() {
+                return Ok(anvil);
+            }
+            thread::sleep(Duration::from_millis(500));
+        }
+        Err("anvil did not start answering requests within 60 seconds".into())
+    }
+}
+
+impl Drop for Anvil {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+/// `eth_sendTransaction` parameters for an impersonated replay of `tx`.
+/// Only legacy, EIP-2930 and EIP-1559 transactions can be sent this way;
+/// others (blob, set-code, chain-specific types) yield `None`.
+fn send_params(sender: &str, nonce: u64, tx: &Value) -> Option<Value> {
+    let ty = rlp::tx_type(tx);
+    if ty > 2 {
+        return None;
+    }
+    let quantity = |key: &str| find_field(tx, key).and_then(to_hex_quantity);
+    let mut params = Map::new();
+    params.insert("from".to_string(), json!(sender));
+    params.insert("nonce".to_string(), json!(format!("0x{:x}", nonce)));
+    if let Some(to) = find_field(tx, "to").and_then(Value::as_str).filter(|to| to.starts_with("0x")) {
+        params.insert("to".to_string(), json!(to));
+    }
+    params.insert("value".to_string(), json!(quantity("value").unwrap_or_else(|| "0x0".to_string())));
+    params.insert("input".to_string(), json!(find_field(tx, "input").and_then(Value::as_str).unwrap_or("0x")));
+    if let Some(gas) = quantity("gas_limit").or_else(|| quantity("gas")) {
+        params.insert("gas".to_string(), json!(gas));
+    }
+    if ty == 2 {
+        params.insert("maxFeePerGas".to_string(), json!(quantity("max_fee_per_gas")));
+        params.insert("maxPriorityFeePerGas".to_string(), json!(quantity("max_priority_fee_per_gas")));
+    } else {
+        params.insert("gasPrice".to_string(), json!(quantity("gas_price")));
+    }
+    Some(Value::Object(params))
+}
+
+/// Replay one transaction and describe the outcome: `success` or `reverted`
+/// from the receipt, `pending` when it was accepted but not mined (e.g. a
+/// nonce gap), `rejected` when the node refused it, `skipped` when it
+/// cannot be sent unsigned.
+fn replay_tx(client: &RpcClient, sender: &str, nonce: u64, tx: &Value) -> Value {
+    let mut result = json!({
+        "from": sender,
+        "nonce": nonce,
+        "hash": find_field(tx, "hash"),
+    });
+    let Some(params) = send_params(sender, nonce, tx) else {
+        result["status"] = json!("skipped");
+        result["error"] = json!(format!("type {} transactions cannot be replayed unsigned", rlp::tx_type(tx)));
+        return result;
+    };
+    let replay_hash = match client.call("eth_sendTransaction", json!([params])) {
+        Ok(hash) => hash,
+        Err(err) => {
+            result["status"] = json!("rejected");
+            result["error"] = json!(err.to_string());
+            return result;
+        }
+    };
+    result["replay_hash"] = replay_hash.clone();
+    match client.call("eth_getTransactionReceipt", json!([replay_hash])) {
+        Ok(Value::Null) => result["status"] = json!("pending"),
+        Ok(receipt) => {
+            let success = receipt["status"].as_str() == Some("0x1");
+            result["status"] = json!(if success { "success" } else { "reverted" });
+            result["gas_used"] = receipt["gasUsed"].clone();
+            result["block_number"] = receipt["blockNumber"].clone();
+        }
+        Err(err) => {
+            result["status"] = json!("pending");
+            result["error"] = json!(err.to_string());
+        }
+    }
+    result
+}
+
+/// Replay the transactions of a snapshot that match `filter` against an
+/// anvil node, sender by sender in nonce order, impersonating each sender.
+fn replay(snapshot: &Value, client: &RpcClient, filter: &TxFilter) -> Result<Vec<Value>, Box<dyn Error>> {
+    let mut by_sender: BTreeMap<String, Vec<(u64, &Value)>> = BTreeMap::new();
+    for entry in snapshot::transactions(snapshot).filter(|entry| filter.matches(entry)) {
+        if let Ok(nonce) = entry.nonce.parse::<u64>() {
+            by_sender
+                .entry(entry.account.to_ascii_lowercase())
+                .or_default()
+                .push((nonce, entry.tx));
+        }
+    }
+
+    let mut results = Vec::new();
+    for (sender, mut txs) in by_sender {
+        txs.sort_by_key(|(nonce, _)| *nonce);
+        client.call("anvil_impersonateAccount", json!([sender]))?;
+        for (nonce, tx) in txs {
+            results.push(replay_tx(client, &sender, nonce, tx));
+        }
+        client.call("anvil_stopImpersonatingAccount", json!([sender]))?;
+    }
+    Ok(results)
+}
+
+/// Replay a converted snapshot on an anvil fork (spawned with `--anvil`, or
+/// already running at `--rpc-url`) and write the per-transaction outcomes to
+/// `<snapshot>.replay.json`. Outcome counts are printed and emitted as
+/// `txpool.replay.txs` with a `status` label.
+pub fn run(options: &ReplayOptions) -> Result<(), Box<dyn Error>> {
+    let snapshot: Value = serde_json::from_str(&fs::read_to_string(&options.path)?)?;
+
+    // A spawned fork starts at the block the snapshot was taken at, when known.
+    let fork_block = options
+        .anvil
+        .then(|| options.fork_block.or_else(|| snapshot["metadata"]["block_number"].as_u64()))
+        .flatten();
+    let anvil = match &options.fork_url {
+        Some(fork_url) if options.anvil => Some(Anvil::spawn(fork_url, fork_block)?),
+        _ => None,
+    };
+    let url = match &anvil {
+        Some(anvil) => anvil.url.clone(),
+        None => options.rpc_url.clone().unwrap_or_else(|| DEFAULT_RPC_URL.to_string()),
+    };
+    let client = RpcClient::new(&url);
+
+    let results = replay(&snapshot, &client, &options.select)?;
+
+    let mut counts: BTreeMap<&str, usize> = BTreeMap::new();
+    for result in &results {
+        *counts.entry(result["status"].as_str().unwrap_or("unknown")).or_insert(0) += 1;
+    }
+    for (status, count) in &counts {
+        emit::emit!(
+            "{metric_agg} of {metric_name} is {metric_value}",
+            evt_kind: "metric",
+            metric_agg: "count",
+            metric_name: "txpool.replay.txs",
+            metric_value: count,
+            status,
+        );
+    }
+
+    let stem = options.path.strip_suffix(".json").unwrap_or(&options.path);
+    let report_path = format!("{}.replay.json", stem);
+    let report = json!({
+        "snapshot": options.path,
+        "node": url,
+        "fork_block": fork_block,
+        "counts": counts,
+        "transactions": results,
+    });
+    fs::write(&report_path, serde_json::to_string_pretty(&report)?)?;
+
+    eprintln!("Replayed {} transactions against {}", results.len(), url);
+    for (status, count) in &counts {
+        eprintln!("  {:<15}{}", status, count);
+    }
+    println!("Replay report saved to: {}", report_path);
+    Ok(())
+}
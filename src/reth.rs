@@ -0,0 +1,274 @@
+use serde_json::{Map, Value, json};
+use std::error::Error;
+
+use crate::keccak::keccak256;
+use crate::quantity::bytes_to_hex;
+use crate::rlp::{self, Item};
+use crate::rpc_content;
+use crate::secp256k1::recover_address;
+
+/// Read reth's transaction pool backup (`txpool-transactions-backup.rlp`, written
+/// on shutdown and reloaded on start) into a snapshot.
+///
+/// The file is an RLP list of signed transactions: legacy transactions as
+/// lists, typed ones as EIP-2718 envelopes wrapped in RLP strings. The backup
+/// stores neither senders nor sub-pools, so senders are recovered from the
+/// signatures and every transaction is placed under `pending`.
+pub fn read_backup(bytes: &[u8]) -> Result<Value, Box<dyn Error>> {
+    let (list, rest) = rlp::decode(bytes).ok_or("Reth backup is not valid RLP")?;
+    if !rest.is_empty() {
+        return Err(format!("Reth backup has {} trailing bytes", rest.len()).into());
+    }
+    let items = list.items().ok_or("Reth backup is not an RLP list")?;
+
+    let mut pending: Map<String, Value> = Map::new();
+    let mut skipped = 0usize;
+    for item in &items {
+        let Some(tx) = transaction(item) else {
+            skipped += 1;
+            continue;
+        };
+        let from = tx["from"].as_str().unwrap_or_default().to_string();
+        let nonce = tx["nonce"]
+            .as_str()
+            .and_then(|n| u64::from_str_radix(n.trim_start_matches("0x"), 16).ok())
+            .unwrap_or_default();
+        pending
+            .entry(from)
+            .or_insert_with(|| json!({}))
+            .as_object_mut()
+            .expect("account entries are objects")
+            .insert(nonce.to_string(), tx);
+    }
+
+    if skipped > 0 {
+        emit::warn!("Skipped {skipped} undecodable transactions in reth backup", skipped);
+        eprintln!("Warning: skipped {} undecodable transactions in reth backup", skipped);
+    }
+    rpc_content::to_snapshot(&json!({ "pending": pending, "queued": {} }))
+}
+
+/// Minimal `0x` hex quantity of a big-endian integer (`0x0` for zero).
+fn quantity(bytes: &[u8]) -> String {
+    let hex = bytes_to_hex(bytes);
+    let digits = hex[2..].trim_start_matches('0');
+    format!("0x{}", if digits.is_empty() { "0" } else { digits })
+}
+
+fn word(bytes: &[u8]) -> Option<[u8; 32]> {
+    let mut out = [0u8; 32];
+    out.get_mut(32usize.checked_sub(bytes.len())?..)?.copy_from_slice(bytes);
+    Some(out)
+}
+
+fn uint(bytes: &[u8]) -> Option<u64> {
+    if bytes.len() > 8 {
+        return None;
+    }
+    Some(bytes.iter().fold(0u64, |acc, b| (acc << 8) | u64::from(*b)))
+}
+
+/// Decode one backup entry into an RPC-style transaction object.
+fn transaction(item: &Item) -> Option<Value> {
+    if item.is_list {
+        return legacy(item);
+    }
+    let (&ty, envelope) = item.payload.split_first()?;
+    let (body, _) = rlp::decode(envelope)?;
+    let fields = body.items()?;
+    // Pooled blob transactions may carry their sidecar: [tx, blobs, commitments, proofs].
+    if ty == 3 && fields.first().is_some_and(|first| first.is_list) {
+        let (inner, _) = rlp::decode(fields[0].raw)?;
+        return typed(ty, &inner, inner.items()?);
+    }
+    typed(ty, &body, fields)
+}
+
+fn legacy(item: &Item) -> Option<Value> {
+    let fields = item.items()?;
+    let [nonce, gas_price, gas, to, value, input, v, r, s] = fields.as_slice() else {
+        return None;
+    };
+    let v = uint(v.payload)?;
+    let (chain_id, y_odd) = match v {
+        27 | 28 => (None, v == 28),
+        v if v >= 35 => (Some((v - 35) / 2), (v - 35) % 2 == 1),
+        _ => return None,
+    };
+
+    let mut unsigned: Vec<Vec<u8>> = fields[..6].iter().map(|field| field.raw.to_vec()).collect();
+    if let Some(chain_id) = chain_id {
+        unsigned.push(rlp::string(&chain_id.to_be_bytes()[chain_id.leading_zeros() as usize / 8..]));
+        unsigned.push(rlp::string(&[]));
+        unsigned.push(rlp::string(&[]));
+    }
+    let sighash = keccak256(&rlp::list(&unsigned));
+    let from = recover_address(&sighash, &word(r.payload)?, &word(s.payload)?, y_odd)?;
+
+    let mut tx = json!({
+        "type": "0x0",
+        "hash": bytes_to_hex(&keccak256(item.raw)),
+        "from": bytes_to_hex(&from),
+        "nonce": quantity(nonce.payload),
+        "gasPrice": quantity(gas_price.payload),
+        "gas": quantity(gas.payload),
+        "to": address(to),
+        "value": quantity(value.payload),
+        "input": bytes_to_hex(input.payload),
+        "v": quantity(&v.to_be_bytes()),
+        "r": quantity(r.payload),
+        "s": quantity(s.payload),
+    });
+    if let Some(chain_id) = chain_id {
+        tx["chainId"] = json!(quantity(&chain_id.to_be_bytes()));
+    }
+    Some(tx)
+}
+
+fn typed(ty: u8, body: &Item, fields: Vec<Item>) -> Option<Value> {
+    let names: &[&str] = match ty {
+        1 => &["chainId", "nonce", "gasPrice", "gas", "to", "value", "input", "accessList"],
+        2 => &[
+            "chainId",
+            "nonce",
+            "maxPriorityFeePerGas",
+            "maxFeePerGas",
+            "gas",
+            "to",
+            "value",
+            "input",
+            "accessList",
+        ],
+        3 => &[
+            "chainId",
+            "nonce",
+            "maxPriorityFeePerGas",
+            "maxFeePerGas",
+            "gas",
+            "to",
+            "value",
+            "input",
+            "accessList",
+            "maxFeePerBlobGas",
+            "blobVersionedHashes",
+        ],
+        4 => &[
+            "chainId",
+            "nonce",
+            "maxPriorityFeePerGas",
+            "maxFeePerGas",
+            "gas",
+            "to",
+            "value",
+            "input",
+            "accessList",
+            "authorizationList",
+        ],
+        _ => return None,
+    };
+    if fields.len() != names.len() + 3 {
+        return None;
+    }
+    let (unsigned, signature) = fields.split_at(names.len());
+    let [y_parity, r, s] = signature else {
+        return None;
+    };
+
+    let mut payload = vec![ty];
+    payload.extend(rlp::list(&unsigned.iter().map(|field| field.raw.to_vec()).collect::<Vec<_>>()));
+    let y_odd = uint(y_parity.payload)? == 1;
+    let from = recover_address(&keccak256(&payload), &word(r.payload)?, &word(s.payload)?, y_odd)?;
+
+    let mut envelope = vec![ty];
+    envelope.extend_from_slice(body.raw);
+
+    let mut tx = Map::new();
+    tx.insert("type".to_string(), json!(quantity(&[ty])));
+    tx.insert("hash".to_string(), json!(bytes_to_hex(&keccak256(&envelope))));
+    tx.insert("from".to_string(), json!(bytes_to_hex(&from)));
+    for (name, field) in names.iter().zip(unsigned) {
+        let value = match *name {
+            "to" => address(field),
+            "input" => json!(bytes_to_hex(field.payload)),
+            "accessList" => access_list(field)?,
+            "authorizationList" => authorization_list(field)?,
+            "blobVersionedHashes" => {
+                let hashes: Vec<String> = field.items()?.iter().map(|hash| bytes_to_hex(hash.payload)).collect();
+                json!(hashes)
+            }
+            _ => json!(quantity(field.payload)),
+        };
+        tx.insert(name.to_string(), value);
+    }
+    tx.insert("yParity".to_string(), json!(quantity(y_parity.payload)));
+    tx.insert("r".to_string(), json!(quantity(r.payload)));
+    tx.insert("s".to_string(), json!(quantity(s.payload)));
+    Some(Value::Object(tx))
+}
+
+/// Recipient address, or `null` for contract creation.
+fn address(field: &Item) -> Value {
+    if field.payload.is_empty() {
+        Value::Null
+    } else {
+        json!(bytes_to_hex(field.payload))
+    }
+}
+
+fn access_list(field: &Item) -> Option<Value> {
+    let mut entries = Vec::new();
+    for entry in field.items()? {
+        let [address, keys] = entry.items()?.try_into().ok()?;
+        let keys: Vec<String> = keys.items()?.iter().map(|key| bytes_to_hex(key.payload)).collect();
+        entries.push(json!({ "address": bytes_to_hex(address.payload), "storageKeys": keys }));
+    }
+    Some(json!(entries))
+}
+
+fn authorization_list(field: &Item) -> Option<Value> {
+    let mut entries = Vec::new();
+    for entry in field.items()? {
+        let [chain_id, address, nonce, y_parity, r, s] = entry.items()?.try_into().ok()?;
+        entries.push(json!({
+            "chainId": quantity(chain_id.payload),
+            "address": bytes_to_hex(address.payload),
+            "nonce": quantity(nonce.payload),
+            "yParity": quantity(y_parity.payload),
+            "r": quantity(r.payload),
+            "s": quantity(s.payload),
+        }));
+    }
+    Some(json!(entries))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::quantity::hex_to_bytes;
+    use crate::snapshot::find_field;
+
+    /// The signed example transaction of EIP-155 (nonce 9, chain 1).
+    const EIP155_EXAMPLE: &str = "0xf86c098504a817c800825208943535353535353535353535353535353535353535880de0b6b3a76400008025a028ef61340bd939bc2195fe537567866003e1a15d3c71ff63e1590620aa636276a067cbe9d8997f761aecb703304b3800ccf555c9f3dc64214b297fb1966a3b6d83";
+
+    #[test]
+    fn legacy_transaction_and_sender() {
+        let backup = rlp::list(&[hex_to_bytes(EIP155_EXAMPLE).unwrap()]);
+        let snapshot = read_backup(&backup).unwrap();
+        let tx = &snapshot["pending"]["0x9d8a62f656a8d1615c1294fd71e9cfb3e4855a4f"]["9"];
+        assert_eq!(
+            find_field(tx, "hash").and_then(Value::as_str),
+            Some("0x33469b22e9f636356c4160a87eb19df52b7412e8eac32a4a55ffe88ea8350788")
+        );
+        assert_eq!(
+            find_field(tx, "to").and_then(Value::as_str),
+            Some("0x3535353535353535353535353535353535353535")
+        );
+    }
+
+    #[test]
+    fn trailing_bytes() {
+        let mut backup = rlp::list(&[]);
+        backup.push(0);
+        assert!(read_backup(&backup).unwrap_err().to_string().contains("trailing"));
+    }
+}
@@ -0,0 +1,346 @@
+use serde_json::Value;
+
+use crate::arbitrum;
+use crate::quantity::{hex_to_bytes, to_be_bytes};
+use crate::snapshot::find_field;
+
+/// EIP-2718 transaction type of a snapshot transaction, inferred from which
+/// fields its signed payload carries (or taken from an unknown envelope).
+pub fn tx_type(tx: &Value) -> u8 {
+    let Some(fields) = find_field(tx, "tx") else {
+        return arbitrum::envelope_type(tx)
+            .and_then(|code| u8::try_from(code).ok())
+            .unwrap_or(0);
+    };
+    if fields.get("authorization_list").is_some() {
+        4
+    } else if fields.get("blob_versioned_hashes").is_some() {
+        3
+    } else if fields.get("max_fee_per_gas").is_some() {
+        2
+    } else if fields.get("access_list").is_some() {
+        1
+    } else {
+        0
+    }
+}
+
+/// Reconstruct the signed EIP-2718 envelope of a content-format transaction.
+///
+/// Returns `None` when the entry lacks the signed payload (e.g. inspect
+/// summaries or unknown envelopes).
+pub fn encode_signed(tx: &Value) -> Option<Vec<u8>> {
+    let fields = find_field(tx, "tx")?;
+    let signature = find_field(tx, "signature")?;
+    let ty = tx_type(tx);
+
+    let y_parity = signature
+        .get("y_parity")
+        .or_else(|| signature.get("odd_y_parity"))
+        .and_then(Value::as_bool)
+        .unwrap_or(false);
+    let r = signature.get("r").and_then(to_be_bytes).unwrap_or_default();
+    let s = signature.get("s").and_then(to_be_bytes).unwrap_or_default();
+
+    let mut items = payload(fields, ty);
+    if ty == 0 {
+        let chain_id = fields.get("chain_id").and_then(Value::as_u64);
+        let v = match chain_id {
+            Some(id) => 35 + 2 * id + u64::from(y_parity),
+            None => 27 + u64::from(y_parity),
+        };
+        items.push(string(&minimal(v)));
+        items.push(string(&r));
+        items.push(string(&s));
+        return Some(list(&items));
+    }
+
+    items.push(string(&minimal(u64::from(y_parity))));
+    items.push(string(&r));
+    items.push(string(&s));
+
+    let mut envelope = vec![ty];
+    envelope.extend(list(&items));
+    Some(envelope)
+}
+
+/// Unsigned encoding of a content-format transaction, as handed to a signer:
+/// the typed envelope without signature fields, or the EIP-155 signing
+/// payload for legacy transactions.
+pub fn encode_unsigned(tx: &Value) -> Option<Vec<u8>> {
+    let fields = find_field(tx, "tx")?;
+    let ty = tx_type(tx);
+
+    let mut items = payload(fields, ty);
+    if ty == 0 {
+        if let Some(chain_id) = fields.get("chain_id").and_then(Value::as_u64) {
+            items.push(string(&minimal(chain_id)));
+            items.push(string(&[]));
+            items.push(string(&[]));
+        }
+        return Some(list(&items));
+    }
+
+    let mut envelope = vec![ty];
+    envelope.extend(list(&items));
+    Some(envelope)
+}
+
+/// RLP items of the transaction fields, without the signature.
+fn payload(fields: &Value, ty: u8) -> Vec<Vec<u8>> {
+    let uint = |key: &str| fields.get(key).and_then(to_be_bytes).unwrap_or_default();
+    let bytes = |key: &str| {
+        fields
+            .get(key)
+            .and_then(Value::as_str)
+            .and_then(hex_to_bytes)
+            .unwrap_or_default()
+    };
+    let to = fields
+        .get("to")
+        .and_then(Value::as_str)
+        .and_then(hex_to_bytes)
+        .unwrap_or_default();
+
+    let mut items = Vec::new();
+    if ty == 0 {
+        items.push(string(&uint("nonce")));
+        items.push(string(&uint("gas_price")));
+        items.push(string(&uint("gas_limit")));
+        items.push(string(&to));
+        items.push(string(&uint("value")));
+        items.push(string(&bytes("input")));
+        return items;
+    }
+
+    items.push(string(&uint("chain_id")));
+    items.push(string(&uint("nonce")));
+    if ty == 1 {
+        items.push(string(&uint("gas_price")));
+    } else {
+        items.push(string(&uint("max_priority_fee_per_gas")));
+        items.push(string(&uint("max_fee_per_gas")));
+    }
+    items.push(string(&uint("gas_limit")));
+    items.push(string(&to));
+    items.push(string(&uint("value")));
+    items.push(string(&bytes("input")));
+    items.push(access_list(fields.get("access_list")));
+    if ty == 3 {
+        items.push(string(&uint("max_fee_per_blob_gas")));
+        let hashes: Vec<Vec<u8>> = fields
+            .get("blob_versioned_hashes")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+            .filter_map(Value::as_str)
+            .filter_map(hex_to_bytes)
+            .map(|hash| string(&hash))
+            .collect();
+        items.push(list(&hashes));
+    }
+    if ty == 4 {
+        items.push(authorization_list(fields.get("authorization_list")));
+    }
+    items
+}
+
+fn access_list(value: Option<&Value>) -> Vec<u8> {
+    let entries: Vec<Vec<u8>> = value
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .map(|item| {
+            let address = item
+                .get("address")
+                .and_then(Value::as_str)
+                .and_then(hex_to_bytes)
+                .unwrap_or_default();
+            let keys: Vec<Vec<u8>> = item
+                .get("storage_keys")
+                .and_then(Value::as_array)
+                .into_iter()
+                .flatten()
+                .filter_map(Value::as_str)
+                .filter_map(hex_to_bytes)
+                .map(|key| string(&key))
+                .collect();
+            list(&[string(&address), list(&keys)])
+        })
+        .collect();
+    list(&entries)
+}
+
+fn authorization_list(value: Option<&Value>) -> Vec<u8> {
+    let entries: Vec<Vec<u8>> = value
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .map(|item| {
+            let uint = |key: &str| {
+                find_field(item, key)
+                    .and_then(to_be_bytes)
+                    .unwrap_or_default()
+            };
+            let address = find_field(item, "address")
+                .and_then(Value::as_str)
+                .and_then(hex_to_bytes)
+                .unwrap_or_default();
+            list(&[
+                string(&uint("chain_id")),
+                string(&address),
+                string(&uint("nonce")),
+                string(&uint("y_parity")),
+                string(&uint("r")),
+                string(&uint("s")),
+            ])
+        })
+        .collect();
+    list(&entries)
+}
+
+fn minimal(value: u64) -> Vec<u8> {
+    let bytes = value.to_be_bytes();
+    let leading = bytes.iter().take_while(|b| **b == 0).count();
+    bytes[leading..].to_vec()
+}
+
+/// RLP-encode a byte string.
+pub fn string(bytes: &[u8]) -> Vec<u8> {
+    if bytes.len() == 1 && bytes[0] < 0x80 {
+        return bytes.to_vec();
+    }
+    let mut out = header(0x80, bytes.len());
+    out.extend_from_slice(bytes);
+    out
+}
+
+/// RLP-encode a list of already encoded items.
+pub fn list(items: &[Vec<u8>]) -> Vec<u8> {
+    let payload_len = items.iter().map(Vec::len).sum();
+    let mut out = header(0xc0, payload_len);
+    for item in items {
+        out.extend_from_slice(item);
+    }
+    out
+}
+
+fn header(offset: u8, len: usize) -> Vec<u8> {
+    if len <= 55 {
+        return vec![offset + len as u8];
+    }
+    let len_bytes = minimal(len as u64);
+    let mut out = vec![offset + 55 + len_bytes.len() as u8];
+    out.extend(len_bytes);
+    out
+}
+
+/// One decoded RLP item, borrowing from the input.
+pub struct Item<'a> {
+    /// The full encoding, header included.
+    pub raw: &'a [u8],
+    /// String bytes, or the concatenated encodings of a list's items.
+    pub payload: &'a [u8],
+    pub is_list: bool,
+}
+
+impl<'a> Item<'a> {
+    /// Items of a list (`None` for strings or malformed payloads).
+    pub fn items(&self) -> Option<Vec<Item<'a>>> {
+        if !self.is_list {
+            return None;
+        }
+        let mut rest = self.payload;
+        let mut items = Vec::new();
+        while !rest.is_empty() {
+            let (item, tail) = decode(rest)?;
+            items.push(item);
+            rest = tail;
+        }
+        Some(items)
+    }
+}
+
+/// Decode the first RLP item of `input`, returning it and the remaining bytes.
+pub fn decode(input: &[u8]) -> Option<(Item<'_>, &[u8])> {
+    let first = *input.first()?;
+    let (header_len, payload_len, is_list) = match first {
+        0x00..=0x7f => (0, 1, false),
+        0x80..=0xb7 => (1, usize::from(first - 0x80), false),
+        0xb8..=0xbf => long_length(input, usize::from(first - 0xb7), false)?,
+        0xc0..=0xf7 => (1, usize::from(first - 0xc0), true),
+        0xf8..=0xff => long_length(input, usize::from(first - 0xf7), true)?,
+    };
+    let end = header_len.checked_add(payload_len)?;
+    if input.len() < end {
+        return None;
+    }
+    let item = Item {
+        raw: &input[..end],
+        payload: &input[header_len..end],
+        is_list,
+    };
+    Some((item, &input[end..]))
+}
+
+fn long_length(input: &[u8], len_of_len: usize, is_list: bool) -> Option<(usize, usize, bool)> {
+    if len_of_len > 8 {
+        return None;
+    }
+    let len_bytes = input.get(1..1 + len_of_len)?;
+    let len = len_bytes.iter().fold(0usize, |acc, b| (acc << 8) | usize::from(*b));
+    Some((1 + len_of_len, len, is_list))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::keccak::keccak256;
+    use crate::quantity::{bytes_to_hex, hex_to_bytes};
+
+    /// `rlp([sender, nonce])`, hashed for the address of a CREATE.
+    fn sender_and_nonce(nonce: u64) -> Vec<u8> {
+        let sender = hex_to_bytes("0x6ac7ea33f8831ea9dcc53393aaa88b25a785dbf0").unwrap();
+        list(&[string(&sender), string(&minimal(nonce))])
+    }
+
+    #[test]
+    fn address_and_nonce() {
+        let encoded = sender_and_nonce(0);
+        assert_eq!(
+            bytes_to_hex(&encoded),
+            "0xd6946ac7ea33f8831ea9dcc53393aaa88b25a785dbf080"
+        );
+        assert_eq!(bytes_to_hex(&sender_and_nonce(1)).get(46..), Some("01"));
+        assert_eq!(bytes_to_hex(&sender_and_nonce(0x80)).get(46..), Some("8180"));
+        // The well-known first two contract addresses of this sender
+        assert_eq!(
+            bytes_to_hex(&keccak256(&encoded)[12..]),
+            "0xcd234a471b72ba2f1ccf0a70fcaba648a5eecd8d"
+        );
+        assert_eq!(
+            bytes_to_hex(&keccak256(&sender_and_nonce(1))[12..]),
+            "0x343c43a37d37dff08ae8c4a11544c718abb4fcf8"
+        );
+    }
+
+    #[test]
+    fn long_string_header() {
+        let encoded = string(&[0xaa; 56]);
+        assert_eq!(encoded[..2], [0xb8, 56]);
+        assert_eq!(encoded.len(), 58);
+    }
+
+    #[test]
+    fn decode_round_trip() {
+        let encoded = sender_and_nonce(1);
+        let (item, rest) = decode(&encoded).unwrap();
+        assert!(rest.is_empty());
+        let items = item.items().unwrap();
+        assert_eq!(items.len(), 2);
+        assert_eq!(items[0].payload.len(), 20);
+        assert_eq!(items[1].payload, [1]);
+        // A header claiming more bytes than there are
+        assert!(decode(&encoded[..10]).is_none());
+    }
+}
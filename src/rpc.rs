@@ -0,0 +1,298 @@
+use serde_json::{Value, json};
+use std::cell::Cell;
+use std::error::Error;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::quantity::parse_u128;
+
+/// Longest wait between two attempts of a request.
+const MAX_BACKOFF: Duration = Duration::from_secs(30);
+
+/// JSON-RPC error code providers such as Infura use for rate limiting.
+const LIMIT_EXCEEDED: i64 = -32005;
+
+/// Where requests are sent.
+#[derive(Clone)]
+enum Transport {
+    Http(String),
+    /// Path of a node's IPC socket (e.g. `geth.ipc`).
+    Ipc(String),
+}
+
+impl Transport {
+    fn describe(&self) -> &str {
+        match self {
+            Transport::Http(url) => url,
+            Transport::Ipc(path) => path,
+        }
+    }
+}
+
+/// How hard a client tries before giving up on a request: `--rpc-rate-limit`,
+/// `--rpc-retries` and `--rpc-fallback`.
+#[derive(Clone, Default)]
+pub struct RpcPolicy {
+    /// Shortest gap between two requests of one client, from the most
+    /// requests per second it may send.
+    pub min_interval: Option<Duration>,
+    /// Extra attempts of a request that failed in a way that may pass (no
+    /// connection, HTTP 429 or 5xx, a rate-limit error), with exponential
+    /// backoff from 500 ms.
+    pub retries: u32,
+    /// HTTP endpoints tried in order once an endpoint has used up its retries.
+    pub fallbacks: Vec<String>,
+}
+
+/// Why an attempt failed, which decides what is tried next.
+enum Failure {
+    /// May pass when tried again: no connection, overload or rate limiting.
+    Transient(Box<dyn Error>),
+    /// This endpoint cannot serve requests (e.g. HTTP 401); another may.
+    Endpoint(Box<dyn Error>),
+    /// The node answered with a JSON-RPC error; trying again would not help.
+    Answered(Box<dyn Error>),
+}
+
+fn transient(err: impl Into<Box<dyn Error>>) -> Failure {
+    Failure::Transient(err.into())
+}
+
+fn unusable(err: impl Into<Box<dyn Error>>) -> Failure {
+    Failure::Endpoint(err.into())
+}
+
+/// Minimal blocking JSON-RPC client over HTTP or a local IPC socket.
+pub struct RpcClient {
+    /// The endpoint the client was made for, then the fallbacks.
+    endpoints: Vec<Transport>,
+    /// Endpoint requests go to; it stays on a fallback once it has switched.
+    active: Cell<usize>,
+    retries: u32,
+    min_interval: Option<Duration>,
+    last_request: Cell<Option<Instant>>,
+    next_id: Cell<u64>,
+}
+
+impl RpcClient {
+    pub fn new(url: &str) -> Self {
+        RpcClient::with_transport(Transport::Http(url.to_string()))
+    }
+
+    /// Client for the IPC socket at `path`, for nodes without HTTP RPC.
+    pub fn ipc(path: &str) -> Self {
+        RpcClient::with_transport(Transport::Ipc(path.to_string()))
+    }
+
+    fn with_transport(transport: Transport) -> Self {
+        RpcClient {
+            endpoints: vec![transport],
+            active: Cell::new(0),
+            retries: 0,
+            min_interval: None,
+            last_request: Cell::new(None),
+            next_id: Cell::new(1),
+        }
+    }
+
+    /// Apply the rate limit, retries and fallback endpoints of `policy`.
+    pub fn with_policy(mut self, policy: &RpcPolicy) -> Self {
+        self.endpoints.truncate(1);
+        self.endpoints
+            .extend(policy.fallbacks.iter().map(|url| Transport::Http(url.clone())));
+        self.retries = policy.retries;
+        self.min_interval = policy.min_interval;
+        self
+    }
+
+    /// Call `method` and return its `result`, turning JSON-RPC errors into `Err`.
+    ///
+    /// Failures that may pass are retried on the active endpoint with
+    /// backoff; once its retries are used up, or it cannot serve requests at
+    /// all, the next endpoint takes over for this and later calls. Errors the
+    /// node answers with are returned as they are.
+    pub fn call(&self, method: &str, params: Value) -> Result<Value, Box<dyn Error>> {
+        let request = self.request(method, params);
+        self.exchange(method, &request, |mut response| result(method, &mut response))
+    }
+
+    /// Call `method` once per entry of `params` in a single JSON-RPC batch,
+    /// returning the results in the same order. One failed call fails the
+    /// batch; retries and failover work as for [`RpcClient::call`].
+    pub fn batch(&self, method: &str, params: Vec<Value>) -> Result<Vec<Value>, Box<dyn Error>> {
+        if params.is_empty() {
+            return Ok(Vec::new());
+        }
+        let requests: Vec<Value> = params.into_iter().map(|params| self.request(method, params)).collect();
+        let ids: Vec<Value> = requests.iter().map(|request| request["id"].clone()).collect();
+        self.exchange(method, &Value::Array(requests), |response| {
+            let Value::Array(mut responses) = response else {
+                return Err(unusable(format!("Batch of {} returned {}", method, response)));
+            };
+            ids.iter()
+                .map(|id| {
+                    let position = responses
+                        .iter()
+                        .position(|response| response["id"] == *id)
+                        .ok_or_else(|| unusable(format!("Batch of {} has no response for id {}", method, id)))?;
+                    result(method, &mut responses.swap_remove(position))
+                })
+                .collect()
+        })
+    }
+
+    fn request(&self, method: &str, params: Value) -> Value {
+        let id = self.next_id.get();
+        self.next_id.set(id + 1);
+        json!({
+            "jsonrpc": "2.0",
+            "id": id,
+            "method": method,
+            "params": params,
+        })
+    }
+
+    /// Send `request` with retries and failover; `read` turns the response
+    /// into the caller's result.
+    fn exchange<T>(
+        &self,
+        method: &str,
+        request: &Value,
+        read: impl Fn(Value) -> Result<T, Failure>,
+    ) -> Result<T, Box<dyn Error>> {
+        let first = self.active.get();
+        let mut last_err = None;
+        for offset in 0..self.endpoints.len() {
+            let index = (first + offset) % self.endpoints.len();
+            let endpoint = &self.endpoints[index];
+            if offset > 0 {
+                let from = self.endpoints[(index + self.endpoints.len() - 1) % self.endpoints.len()].describe();
+                let to = endpoint.describe();
+                emit::warn!("RPC endpoint {from} failed; failing over to {to}", from, to);
+                emit::emit!(
+                    "{metric_agg} of {metric_name} is {metric_value}",
+                    evt_kind: "metric",
+                    metric_agg: "count",
+                    metric_name: "txpool.rpc.failovers",
+                    metric_value: 1,
+                    endpoint: to,
+                );
+            }
+            for attempt in 0..=self.retries {
+                if attempt > 0 {
+                    let wait = (Duration::from_millis(500) * 2u32.saturating_pow((attempt - 1).min(16))).min(MAX_BACKOFF);
+                    emit::emit!(
+                        "{metric_agg} of {metric_name} is {metric_value}",
+                        evt_kind: "metric",
+                        metric_agg: "count",
+                        metric_name: "txpool.rpc.retries",
+                        metric_value: 1,
+                        method,
+                    );
+                    thread::sleep(wait);
+                }
+                self.throttle();
+                match send(endpoint, request).and_then(&read) {
+                    Ok(result) => {
+                        self.active.set(index);
+                        return Ok(result);
+                    }
+                    Err(Failure::Transient(err)) => last_err = Some(err),
+                    Err(Failure::Endpoint(err)) => {
+                        last_err = Some(err);
+                        break;
+                    }
+                    Err(Failure::Answered(err)) => return Err(err),
+                }
+            }
+        }
+        Err(last_err.expect("every client has an endpoint"))
+    }
+
+    /// Wait until the rate limit allows the next request.
+    fn throttle(&self) {
+        let Some(min_interval) = self.min_interval else {
+            return;
+        };
+        if let Some(last) = self.last_request.get() {
+            thread::sleep(min_interval.saturating_sub(last.elapsed()));
+        }
+        self.last_request.set(Some(Instant::now()));
+    }
+
+    /// `eth_call` against the latest block, returning the raw hex result.
+    pub fn eth_call(&self, to: &str, data: &str) -> Result<String, Box<dyn Error>> {
+        let result = self.call("eth_call", json!([{ "to": to, "data": data }, "latest"]))?;
+        result
+            .as_str()
+            .map(str::to_string)
+            .ok_or_else(|| format!("eth_call to {} returned {}", to, result).into())
+    }
+
+    /// `eth_call` returning a single `uint256` word that fits in a `u128`.
+    pub fn call_uint(&self, to: &str, data: &str) -> Result<u128, Box<dyn Error>> {
+        let word = self.eth_call(to, data)?;
+        parse_u128(&word).ok_or_else(|| format!("eth_call to {} returned out-of-range word {}", to, word).into())
+    }
+}
+
+/// One attempt of `request` against `endpoint`, returning the response.
+fn send(endpoint: &Transport, request: &Value) -> Result<Value, Failure> {
+    match endpoint {
+        Transport::Http(url) => {
+            let body = match ureq::post(url)
+                .set("Content-Type", "application/json")
+                .timeout(Duration::from_secs(30))
+                .send_string(&request.to_string())
+            {
+                Ok(response) => response.into_string().map_err(transient)?,
+                Err(ureq::Error::Status(status, _)) if status == 429 || status >= 500 => {
+                    return Err(transient(format!("{} answered HTTP {}", url, status)));
+                }
+                Err(err @ ureq::Error::Status(..)) => return Err(unusable(err)),
+                Err(err) => return Err(transient(err)),
+            };
+            serde_json::from_str(&body).map_err(unusable)
+        }
+        Transport::Ipc(path) => ipc_request(path, request).map_err(transient),
+    }
+}
+
+/// The `result` of one JSON-RPC response. A rate-limit error may pass when
+/// tried again; other errors are the node's answer.
+fn result(method: &str, response: &mut Value) -> Result<Value, Failure> {
+    if let Some(error) = response.get("error") {
+        let err = format!("RPC {} failed: {}", method, error);
+        return Err(if error["code"].as_i64() == Some(LIMIT_EXCEEDED) {
+            transient(err)
+        } else {
+            Failure::Answered(err.into())
+        });
+    }
+    Ok(response
+        .get_mut("result")
+        .map(Value::take)
+        .unwrap_or(Value::Null))
+}
+
+/// Send one request over a Unix domain socket and read back one JSON value
+/// (IPC responses are not newline- or length-delimited).
+#[cfg(unix)]
+fn ipc_request(path: &str, request: &Value) -> Result<Value, Box<dyn Error>> {
+    use std::io::Write;
+    use std::os::unix::net::UnixStream;
+
+    let mut stream = UnixStream::connect(path)?;
+    stream.set_read_timeout(Some(Duration::from_secs(30)))?;
+    stream.write_all(request.to_string().as_bytes())?;
+    serde_json::Deserializer::from_reader(stream)
+        .into_iter::<Value>()
+        .next()
+        .ok_or_else(|| format!("IPC socket {} closed without a response", path))?
+        .map_err(Into::into)
+}
+
+#[cfg(not(unix))]
+fn ipc_request(path: &str, _request: &Value) -> Result<Value, Box<dyn Error>> {
+    Err(format!("IPC ({}) is only supported on Unix platforms", path).into())
+}
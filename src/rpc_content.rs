@@ -0,0 +1,176 @@
+use serde_json::{Map, Value, json};
+use std::error::Error;
+
+use crate::quantity::{be_bytes_to_decimal, hex_to_bytes, parse_u128};
+use crate::snapshot::SECTIONS;
+
+/// Convert a `txpool_content` JSON-RPC result into the snapshot layout the
+/// debug-dump parser produces, so every later step treats both the same.
+///
+/// RPC quantities are hex strings; they become exact JSON integers. Typed
+/// fields are renamed to their alloy (snake_case) names, and transaction
+/// types outside the Ethereum set are kept as unknown envelopes.
+pub fn to_snapshot(content: &Value) -> Result<Value, Box<dyn Error>> {
+    let mut snapshot = Map::new();
+    for section in SECTIONS {
+        let mut accounts = Map::new();
+        for (account, nonces) in content[section].as_object().into_iter().flatten() {
+            let nonces = nonces
+                .as_object()
+                .ok_or_else(|| format!("{} entry for {} is not an object", section, account))?;
+            let mut converted = Map::new();
+            for (nonce, tx) in nonces {
+                converted.insert(nonce.clone(), transaction(tx));
+            }
+            accounts.insert(account.to_ascii_lowercase(), Value::Object(converted));
+        }
+        snapshot.insert(section.to_string(), Value::Object(accounts));
+    }
+    Ok(Value::Object(snapshot))
+}
+
+/// Snapshot of `txpool_content` JSON read from a file or stdin: either the
+/// bare result or a complete JSON-RPC response, as `curl` prints it. An error
+/// response is reported as such.
+pub fn from_json(content: &Value) -> Result<Value, Box<dyn Error>> {
+    if let Some(error) = content.get("error") {
+        let message = error["message"].as_str().unwrap_or("no message");
+        return Err(format!("txpool_content request failed: {}", message).into());
+    }
+    let result = if content.get("jsonrpc").is_some() { &content["result"] } else { content };
+    if !SECTIONS.iter().any(|section| result.get(section).is_some()) {
+        return Err("JSON input is not a txpool_content result (no pending or queued section)".into());
+    }
+    to_snapshot(result)
+}
+
+/// Hex quantity as an exact JSON integer (`Null` when absent or malformed).
+fn quantity(value: Option<&Value>) -> Value {
+    value
+        .and_then(Value::as_str)
+        .and_then(|hex| {
+            let hex = hex.strip_prefix("0x")?;
+            let padded = if hex.len() % 2 == 1 { format!("0{}", hex) } else { hex.to_string() };
+            hex_to_bytes(&padded)
+        })
+        .and_then(|bytes| serde_json::from_str(&be_bytes_to_decimal(&bytes)).ok())
+        .unwrap_or(Value::Null)
+}
+
+fn transaction(rpc: &Value) -> Value {
+    let ty = rpc.get("type").and_then(Value::as_str).and_then(parse_u128).unwrap_or(0);
+    let field = |key: &str| rpc.get(key).cloned().unwrap_or(Value::Null);
+
+    let payload = if ty <= 4 {
+        let mut tx = Map::new();
+        if ty != 0 || rpc.get("chainId").is_some() {
+            tx.insert("chain_id".to_string(), quantity(rpc.get("chainId")));
+        }
+        tx.insert("nonce".to_string(), quantity(rpc.get("nonce")));
+        if ty < 2 {
+            tx.insert("gas_price".to_string(), quantity(rpc.get("gasPrice")));
+        }
+        tx.insert("gas_limit".to_string(), quantity(rpc.get("gas")));
+        if ty >= 2 {
+            tx.insert("max_fee_per_gas".to_string(), quantity(rpc.get("maxFeePerGas")));
+            tx.insert(
+                "max_priority_fee_per_gas".to_string(),
+                quantity(rpc.get("maxPriorityFeePerGas")),
+            );
+        }
+        let to = match rpc.get("to") {
+            Some(Value::String(to)) => json!(to.to_ascii_lowercase()),
+            _ => Value::Null,
+        };
+        tx.insert("to".to_string(), to);
+        tx.insert("value".to_string(), quantity(rpc.get("value")));
+        if ty >= 1 {
+            tx.insert("access_list".to_string(), access_list(rpc.get("accessList")));
+        }
+        if ty == 3 {
+            tx.insert("blob_versioned_hashes".to_string(), field("blobVersionedHashes"));
+            tx.insert("max_fee_per_blob_gas".to_string(), quantity(rpc.get("maxFeePerBlobGas")));
+        }
+        if ty == 4 {
+            tx.insert(
+                "authorization_list".to_string(),
+                authorization_list(rpc.get("authorizationList")),
+            );
+        }
+        tx.insert("input".to_string(), field("input"));
+        json!({
+            "tx": tx,
+            "signature": {
+                "y_parity": y_parity(rpc),
+                "r": quantity(rpc.get("r")),
+                "s": quantity(rpc.get("s")),
+            },
+            "hash": field("hash"),
+        })
+    } else {
+        json!({ "hash": field("hash"), "ty": ty as u64, "fields": rpc })
+    };
+
+    json!({
+        "inner": {
+            "inner": {
+                "inner": payload,
+                "signer": field("from"),
+            },
+            "block_hash": field("blockHash"),
+            "block_number": quantity(rpc.get("blockNumber")),
+            "transaction_index": quantity(rpc.get("transactionIndex")),
+            "effective_gas_price": Value::Null,
+        },
+        "other": { "inner": {} },
+    })
+}
+
+/// Signature parity from `yParity`, or recovered from a legacy `v`.
+fn y_parity(rpc: &Value) -> bool {
+    if let Some(parity) = rpc.get("yParity").and_then(Value::as_str).and_then(parse_u128) {
+        return parity == 1;
+    }
+    match rpc.get("v").and_then(Value::as_str).and_then(parse_u128) {
+        Some(v @ (27 | 28)) => v == 28,
+        Some(v) if v >= 35 => (v - 35) % 2 == 1,
+        Some(v) => v == 1,
+        None => false,
+    }
+}
+
+fn access_list(value: Option<&Value>) -> Value {
+    let items: Vec<Value> = value
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .map(|item| {
+            json!({
+                "address": item.get("address"),
+                "storage_keys": item.get("storageKeys").cloned().unwrap_or(json!([])),
+            })
+        })
+        .collect();
+    Value::Array(items)
+}
+
+fn authorization_list(value: Option<&Value>) -> Value {
+    let items: Vec<Value> = value
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .map(|item| {
+            json!({
+                "inner": {
+                    "chain_id": quantity(item.get("chainId")),
+                    "address": item.get("address"),
+                    "nonce": quantity(item.get("nonce")),
+                },
+                "y_parity": quantity(item.get("yParity")),
+                "r": quantity(item.get("r")),
+                "s": quantity(item.get("s")),
+            })
+        })
+        .collect();
+    Value::Array(items)
+}
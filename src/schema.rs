@@ -0,0 +1,501 @@
+use regex::Regex;
+use serde_json::{Map, Value, json};
+use std::collections::HashMap;
+use std::error::Error;
+
+/// JSON Schema (draft 2020-12) for the snapshot documents this tool writes.
+///
+/// Content snapshots keep the nested `inner` wrappers of the RPC types, so the
+/// schema only pins down the parts consumers rely on: the section/account/nonce
+/// layout and the signed transaction fields.
+pub fn snapshot_schema() -> Value {
+    json!({
+        "$schema": "https://json-schema.org/draft/2020-12/schema",
+        "$id": "https://github.com/sambacha/rust-txpool/schema/snapshot.json",
+        "title": "rust-txpool snapshot",
+        "type": "object",
+        "properties": {
+            "pending": { "$ref": "#/$defs/section" },
+            "queued": { "$ref": "#/$defs/section" },
+            "metadata": { "$ref": "#/$defs/metadata" }
+        },
+        "required": ["pending"],
+        "$defs": {
+            "address": {
+                "type": "string",
+                "pattern": "^0x[0-9a-fA-F]{40}$"
+            },
+            "hex": {
+                "type": "string",
+                "pattern": "^0x[0-9a-fA-F]*$"
+            },
+            "quantity": {
+                "type": "integer",
+                "minimum": 0
+            },
+            "metadata": {
+                "type": "object",
+                "properties": {
+                    "chain_id": { "anyOf": [{ "$ref": "#/$defs/quantity" }, { "type": "null" }] },
+                    "chain_id_counts": {
+                        "type": "object",
+                        "additionalProperties": { "$ref": "#/$defs/quantity" }
+                    },
+                    "l1_fee": { "type": "object" },
+                    "total_bytes": { "$ref": "#/$defs/quantity" },
+                    "sized_txs": { "$ref": "#/$defs/quantity" },
+                    "size_by_type": { "type": "object" },
+                    "pool_status": { "type": "object" },
+                    "base_fee": {
+                        "anyOf": [{ "type": "string", "pattern": "^[0-9]+$" }, { "type": "null" }]
+                    },
+                    "fee_buckets": {
+                        "type": "object",
+                        "additionalProperties": { "$ref": "#/$defs/quantity" }
+                    },
+                    "value_bands": { "type": "object" },
+                    "blob_market": { "type": "object" },
+                    "captured_at": {
+                        "type": "string",
+                        "pattern": "^[0-9]{4}-[0-9]{2}-[0-9]{2}T[0-9]{2}:[0-9]{2}:[0-9]{2}Z$"
+                    },
+                    "block_number": { "$ref": "#/$defs/quantity" },
+                    "node": { "type": "object" },
+                    "nodes": { "type": "object" },
+                    "spam": { "type": "object" },
+                    "state_validation": { "type": "object" },
+                    "contract_creations": { "type": "object" },
+                    "account_gas": { "type": "object" },
+                    "truncated": { "type": "boolean" },
+                    "truncation": { "type": "object" },
+                    "partial": { "type": "object" },
+                    "inclusion": { "type": "object" },
+                    "age": { "type": "object" },
+                    "activity": { "type": "object" },
+                    "parse_warnings": { "type": "object" },
+                    "movement": { "type": "object" }
+                }
+            },
+            "section": {
+                "type": "object",
+                "patternProperties": {
+                    "^0x[0-9a-fA-F]{40}$": { "$ref": "#/$defs/account" }
+                },
+                "additionalProperties": false
+            },
+            "account": {
+                "type": "object",
+                "patternProperties": {
+                    "^[0-9]+$": {
+                        "anyOf": [
+                            { "$ref": "#/$defs/contentTransaction" },
+                            { "$ref": "#/$defs/inspectSummary" }
+                        ]
+                    }
+                },
+                "additionalProperties": false
+            },
+            "inspectSummary": {
+                "type": "object",
+                "properties": {
+                    "to": { "anyOf": [{ "$ref": "#/$defs/address" }, { "type": "null" }] },
+                    "value": { "$ref": "#/$defs/quantity" },
+                    "gas": { "$ref": "#/$defs/quantity" },
+                    "gas_price": { "$ref": "#/$defs/quantity" },
+                    "creates_contract": { "type": "boolean" },
+                    "init_code_bytes": { "$ref": "#/$defs/quantity" },
+                    "contract_address": { "$ref": "#/$defs/address" },
+                    "section_since": { "$ref": "#/$defs/quantity" },
+                    "priority_fee_per_gas": { "type": "integer" },
+                    "fee_bucket": {
+                        "enum": ["underpriced", "0-1gwei", "1-2gwei", "2-5gwei", "5-10gwei", "10-50gwei", "50+gwei"]
+                    },
+                    "value_band": { "enum": ["zero", "dust", "retail", "whale"] }
+                },
+                "additionalProperties": false
+            },
+            "contentTransaction": {
+                "type": "object",
+                "properties": {
+                    "inner": {
+                        "type": "object",
+                        "properties": {
+                            "inner": {
+                                "type": "object",
+                                "properties": {
+                                    "inner": {
+                                        "anyOf": [
+                                            { "$ref": "#/$defs/signed" },
+                                            { "$ref": "#/$defs/unknownEnvelope" }
+                                        ]
+                                    },
+                                    "signer": { "$ref": "#/$defs/address" }
+                                },
+                                "required": ["inner", "signer"]
+                            },
+                            "block_hash": { "anyOf": [{ "$ref": "#/$defs/hex" }, { "type": "null" }] },
+                            "block_number": { "anyOf": [{ "$ref": "#/$defs/quantity" }, { "type": "null" }] }
+                        },
+                        "required": ["inner"]
+                    },
+                    "other": { "type": "object" },
+                    "l1_fee_estimate": { "$ref": "#/$defs/quantity" },
+                    "size_bytes": { "$ref": "#/$defs/quantity" },
+                    "creates_contract": { "type": "boolean" },
+                    "init_code_bytes": { "$ref": "#/$defs/quantity" },
+                    "contract_address": { "$ref": "#/$defs/address" },
+                    "tx_type": { "$ref": "#/$defs/quantity" },
+                    "tx_type_name": { "type": "string" },
+                    "priority_fee_per_gas": { "type": "integer" },
+                    "fee_bucket": {
+                        "enum": ["underpriced", "0-1gwei", "1-2gwei", "2-5gwei", "5-10gwei", "10-50gwei", "50+gwei"]
+                    },
+                    "value_band": { "enum": ["zero", "dust", "retail", "whale"] },
+                    "first_seen": { "$ref": "#/$defs/quantity" },
+                    "age_seconds": { "$ref": "#/$defs/quantity" },
+                    "included_in_block": { "$ref": "#/$defs/quantity" },
+                    "included_at": { "$ref": "#/$defs/quantity" },
+                    "inclusion_latency": { "$ref": "#/$defs/quantity" },
+                    "dropped_at": { "$ref": "#/$defs/quantity" },
+                    "section_since": { "$ref": "#/$defs/quantity" },
+                    "visibility": {
+                        "type": "object",
+                        "additionalProperties": { "type": "boolean" }
+                    },
+                    "state_invalid": { "enum": ["nonce_too_low", "insufficient_balance"] }
+                },
+                "required": ["inner"]
+            },
+            "signed": {
+                "type": "object",
+                "properties": {
+                    "tx": { "$ref": "#/$defs/txFields" },
+                    "signature": { "type": "object" },
+                    "hash": { "$ref": "#/$defs/hex" }
+                },
+                "required": ["tx"]
+            },
+            "unknownEnvelope": {
+                "type": "object",
+                "properties": {
+                    "hash": { "$ref": "#/$defs/hex" },
+                    "inner": {
+                        "type": "object",
+                        "properties": {
+                            "ty": { "$ref": "#/$defs/quantity" },
+                            "fields": { "type": "object" }
+                        },
+                        "required": ["ty", "fields"]
+                    }
+                },
+                "required": ["inner"]
+            },
+            "txFields": {
+                "type": "object",
+                "properties": {
+                    "chain_id": { "anyOf": [{ "$ref": "#/$defs/quantity" }, { "type": "null" }] },
+                    "nonce": { "$ref": "#/$defs/quantity" },
+                    "gas_limit": { "$ref": "#/$defs/quantity" },
+                    "gas_price": { "$ref": "#/$defs/quantity" },
+                    "max_fee_per_gas": { "$ref": "#/$defs/quantity" },
+                    "max_priority_fee_per_gas": { "$ref": "#/$defs/quantity" },
+                    "max_fee_per_blob_gas": { "$ref": "#/$defs/quantity" },
+                    "to": { "anyOf": [{ "$ref": "#/$defs/address" }, { "type": "null" }] },
+                    "value": { "$ref": "#/$defs/quantity" },
+                    "input": { "$ref": "#/$defs/hex" },
+                    "access_list": { "type": "array" },
+                    "blob_versioned_hashes": { "type": "array", "items": { "$ref": "#/$defs/hex" } },
+                    "authorization_list": { "type": "array" }
+                },
+                "required": ["nonce", "gas_limit", "value", "input"]
+            }
+        }
+    })
+}
+
+/// Validate `document` against `schema`, returning one message per violation.
+///
+/// Supports the keywords used by [`snapshot_schema`]: `type`, `properties`,
+/// `required`, `patternProperties`, `additionalProperties`, `items`, `anyOf`,
+/// `enum`, `pattern`, `minimum` and local `$ref`s into `$defs`.
+pub fn validate(schema: &Value, document: &Value) -> Result<Vec<String>, Box<dyn Error>> {
+    let mut validator = Validator {
+        root: schema,
+        regexes: HashMap::new(),
+        errors: Vec::new(),
+    };
+    validator.check(schema, document, "$")?;
+    Ok(validator.errors)
+}
+
+struct Validator<'a> {
+    root: &'a Value,
+    regexes: HashMap<&'a str, Regex>,
+    errors: Vec<String>,
+}
+
+impl<'a> Validator<'a> {
+    fn check(&mut self, schema: &'a Value, value: &Value, path: &str) -> Result<(), Box<dyn Error>> {
+        if let Some(reference) = schema.get("$ref").and_then(Value::as_str) {
+            let name = reference
+                .strip_prefix("#/$defs/")
+                .ok_or_else(|| format!("Unsupported $ref: {}", reference))?;
+            let target = self
+                .root
+                .get("$defs")
+                .and_then(|defs| defs.get(name))
+                .ok_or_else(|| format!("Unknown $ref: {}", reference))?;
+            return self.check(target, value, path);
+        }
+
+        if let Some(options) = schema.get("anyOf").and_then(Value::as_array) {
+            // Report the violations of the closest matching option.
+            let outer = std::mem::take(&mut self.errors);
+            let mut closest: Option<Vec<String>> = None;
+            for option in options {
+                self.check(option, value, path)?;
+                let errors = std::mem::take(&mut self.errors);
+                if errors.is_empty() {
+                    closest = None;
+                    break;
+                }
+                if closest.as_ref().is_none_or(|c| errors.len() < c.len()) {
+                    closest = Some(errors);
+                }
+            }
+            self.errors = outer;
+            if let Some(errors) = closest {
+                self.errors.extend(errors);
+                return Ok(());
+            }
+        }
+
+        if let Some(expected) = schema.get("type") {
+            let allowed: Vec<&str> = match expected {
+                Value::String(t) => vec![t.as_str()],
+                Value::Array(types) => types.iter().filter_map(Value::as_str).collect(),
+                _ => Vec::new(),
+            };
+            if !allowed.iter().any(|t| has_type(value, t)) {
+                self.errors.push(format!(
+                    "{}: expected {}, found {}",
+                    path,
+                    allowed.join(" or "),
+                    type_name(value)
+                ));
+                return Ok(());
+            }
+        }
+
+        if let Some(allowed) = schema.get("enum").and_then(Value::as_array)
+            && !allowed.contains(value)
+        {
+            self.errors.push(format!("{}: {} is not one of the allowed values", path, value));
+        }
+
+        if let (Some(pattern), Some(text)) = (schema.get("pattern").and_then(Value::as_str), value.as_str())
+            && !self.regex(pattern)?.is_match(text)
+        {
+            self.errors.push(format!("{}: {:?} does not match {}", path, text, pattern));
+        }
+
+        if let (Some(minimum), Some(number)) = (schema.get("minimum").and_then(Value::as_f64), value.as_f64())
+            && number < minimum
+        {
+            self.errors.push(format!("{}: {} is below minimum {}", path, number, minimum));
+        }
+
+        if let Some(items) = schema.get("items") {
+            for (index, item) in value.as_array().into_iter().flatten().enumerate() {
+                self.check(items, item, &format!("{}[{}]", path, index))?;
+            }
+        }
+
+        if let Some(object) = value.as_object() {
+            self.check_object(schema, object, path)?;
+        }
+
+        Ok(())
+    }
+
+    fn check_object(
+        &mut self,
+        schema: &'a Value,
+        object: &Map<String, Value>,
+        path: &str,
+    ) -> Result<(), Box<dyn Error>> {
+        for key in schema.get("required").and_then(Value::as_array).into_iter().flatten() {
+            if let Some(key) = key.as_str()
+                && !object.contains_key(key)
+            {
+                self.errors.push(format!("{}: missing required field {:?}", path, key));
+            }
+        }
+
+        let properties = schema.get("properties").and_then(Value::as_object);
+        let patterns = schema.get("patternProperties").and_then(Value::as_object);
+
+        for (key, child) in object {
+            let child_path = format!("{}.{}", path, key);
+            let mut known = false;
+
+            if let Some(sub) = properties.and_then(|p| p.get(key)) {
+                known = true;
+                self.check(sub, child, &child_path)?;
+            }
+            for (pattern, sub) in patterns.into_iter().flatten() {
+                if self.regex(pattern)?.is_match(key) {
+                    known = true;
+                    self.check(sub, child, &child_path)?;
+                }
+            }
+
+            if !known {
+                match schema.get("additionalProperties") {
+                    Some(Value::Bool(false)) => {
+                        self.errors.push(format!("{}: unexpected field", child_path));
+                    }
+                    Some(sub @ Value::Object(_)) => self.check(sub, child, &child_path)?,
+                    _ => {}
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn regex(&mut self, pattern: &'a str) -> Result<&Regex, regex::Error> {
+        if !self.regexes.contains_key(pattern) {
+            self.regexes.insert(pattern, Regex::new(pattern)?);
+        }
+        Ok(&self.regexes[pattern])
+    }
+}
+
+fn has_type(value: &Value, expected: &str) -> bool {
+    match expected {
+        "null" => value.is_null(),
+        "boolean" => value.is_boolean(),
+        "object" => value.is_object(),
+        "array" => value.is_array(),
+        "string" => value.is_string(),
+        "number" => value.is_number(),
+        "integer" => {
+            value.is_i64() || value.is_u64() || value.as_f64().is_some_and(|f| f.fract() == 0.0)
+        }
+        _ => false,
+    }
+}
+
+fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::input_format::InputFormat;
+    use crate::synth::{self, SynthFormat, SynthSpec};
+    use crate::unknown_fields::UnknownFields;
+
+    const ACCOUNT: &str = "0x09f1fd9d03f0a9b4553274161bbf84755d5bca46";
+
+    fn errors(document: Value) -> Vec<String> {
+        validate(&snapshot_schema(), &document).unwrap()
+    }
+
+    /// Violations of `summary` checked as an `inspectSummary` on its own, so
+    /// the `anyOf` with `contentTransaction` does not pick the other option.
+    fn summary_errors(summary: Value) -> Vec<String> {
+        let mut schema = snapshot_schema();
+        schema["$ref"] = json!("#/$defs/inspectSummary");
+        validate(&schema, &summary).unwrap()
+    }
+
+    #[test]
+    fn sample_snapshots_validate() {
+        let spec = SynthSpec::with_txs(200);
+        for format in [SynthFormat::Content, SynthFormat::Inspect] {
+            let dump = synth::dump(&spec, format);
+            let (snapshot, _) =
+                crate::parse_debug_format(&dump, InputFormat::Auto, UnknownFields::default(), None).unwrap();
+            assert_eq!(errors(snapshot), Vec::<String>::new());
+        }
+    }
+
+    #[test]
+    fn valid_summary_passes() {
+        let summary = json!({ "to": null, "value": 0, "gas": 21000, "gas_price": 1, "value_band": "zero" });
+        assert_eq!(summary_errors(summary.clone()), Vec::<String>::new());
+        assert_eq!(errors(json!({ "pending": { ACCOUNT: { "0": summary } } })), Vec::<String>::new());
+    }
+
+    #[test]
+    fn type_and_required() {
+        assert_eq!(errors(json!([])), ["$: expected object, found array"]);
+        assert_eq!(errors(json!({ "queued": {} })), [r#"$: missing required field "pending""#]);
+    }
+
+    #[test]
+    fn pattern_and_additional_properties() {
+        assert_eq!(
+            errors(json!({ "pending": { "0x1234": {} } })),
+            ["$.pending.0x1234: unexpected field"]
+        );
+        assert_eq!(
+            summary_errors(json!({ "to": "0x1234" })),
+            [r#"$.to: "0x1234" does not match ^0x[0-9a-fA-F]{40}$"#]
+        );
+        assert_eq!(
+            summary_errors(json!({ "gas": 1, "extra": 1 })),
+            ["$.extra: unexpected field"]
+        );
+        // additionalProperties given as a schema applies it to unlisted keys.
+        let document = json!({ "pending": {}, "metadata": { "fee_buckets": { "1-2gwei": "many" } } });
+        assert_eq!(
+            errors(document),
+            [r#"$.metadata.fee_buckets.1-2gwei: expected integer, found string"#]
+        );
+    }
+
+    #[test]
+    fn minimum_and_enum() {
+        assert_eq!(
+            summary_errors(json!({ "gas": -1 })),
+            ["$.gas: -1 is below minimum 0"]
+        );
+        assert_eq!(
+            summary_errors(json!({ "value_band": "huge" })),
+            [r#"$.value_band: "huge" is not one of the allowed values"#]
+        );
+    }
+
+    #[test]
+    fn items_and_any_of() {
+        let schema = json!({
+            "type": "array",
+            "items": { "anyOf": [{ "type": "string", "pattern": "^0x" }, { "type": "null" }] }
+        });
+        assert_eq!(validate(&schema, &json!(["0x01", null])).unwrap(), Vec::<String>::new());
+        // A failing anyOf reports the violations of its closest option.
+        assert_eq!(
+            validate(&schema, &json!(["0x01", "01", 1])).unwrap(),
+            [r#"$[1]: "01" does not match ^0x"#, "$[2]: expected string, found number"]
+        );
+    }
+
+    #[test]
+    fn unknown_refs_are_errors() {
+        let schema = json!({ "$ref": "#/$defs/missing" });
+        assert!(validate(&schema, &json!(null)).is_err());
+        let schema = json!({ "$ref": "other.json" });
+        assert!(validate(&schema, &json!(null)).is_err());
+    }
+}
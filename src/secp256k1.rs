@@ -0,0 +1,326 @@
+use std::cmp::Ordering;
+
+use crate::keccak::keccak256;
+
+/// 256-bit unsigned integer as little-endian 64-bit limbs.
+type U256 = [u64; 4];
+
+/// Field prime `p = 2^256 - 2^32 - 977`.
+const P: U256 = [
+    0xFFFF_FFFE_FFFF_FC2F,
+    0xFFFF_FFFF_FFFF_FFFF,
+    0xFFFF_FFFF_FFFF_FFFF,
+    0xFFFF_FFFF_FFFF_FFFF,
+];
+
+/// `2^256 mod p`, used to fold the high half of products.
+const P_FOLD: u64 = 0x1_0000_03D1;
+
+/// Group order.
+const N: U256 = [
+    0xBFD2_5E8C_D036_4141,
+    0xBAAE_DCE6_AF48_A03B,
+    0xFFFF_FFFF_FFFF_FFFE,
+    0xFFFF_FFFF_FFFF_FFFF,
+];
+
+const GX: U256 = [
+    0x59F2_815B_16F8_1798,
+    0x029B_FCDB_2DCE_28D9,
+    0x55A0_6295_CE87_0B07,
+    0x79BE_667E_F9DC_BBAC,
+];
+
+const GY: U256 = [
+    0x9C47_D08F_FB10_D4B8,
+    0xFD17_B448_A685_5419,
+    0x5DA4_FBFC_0E11_08A8,
+    0x483A_DA77_26A3_C465,
+];
+
+const ZERO: U256 = [0; 4];
+const ONE: U256 = [1, 0, 0, 0];
+
+/// Recover the Ethereum address that produced an ECDSA signature over
+/// `hash`. Returns `None` for signatures that are out of range or do not
+/// correspond to a curve point.
+///
+/// Recovery only handles public data, so the variable-time arithmetic in this
+/// file is fine here; signing with a private key goes through `k256`.
+pub fn recover_address(hash: &[u8; 32], r: &[u8; 32], s: &[u8; 32], y_odd: bool) -> Option<[u8; 20]> {
+    let r = from_be(r);
+    let s = from_be(s);
+    if r == ZERO || s == ZERO || cmp(&r, &N) != Ordering::Less || cmp(&s, &N) != Ordering::Less {
+        return None;
+    }
+
+    // R = (r, y) with y chosen by parity; p ≡ 3 (mod 4) so sqrt(a) = a^((p+1)/4).
+    let x = r;
+    let y_squared = add_mod(&mul_p(&mul_p(&x, &x), &x), &[7, 0, 0, 0], &P);
+    let mut y = pow_p(&y_squared, &sqrt_exponent());
+    if mul_p(&y, &y) != y_squared {
+        return None;
+    }
+    if (y[0] & 1 == 1) != y_odd {
+        y = sub_mod(&ZERO, &y, &P);
+    }
+
+    // Q = r^-1 (s·R - z·G)
+    let mut z = from_be(hash);
+    if cmp(&z, &N) != Ordering::Less {
+        z = sub(&z, &N).0;
+    }
+    let r_inv = pow_n(&r, &sub(&N, &[2, 0, 0, 0]).0);
+    let u1 = sub_mod(&ZERO, &mul_n(&z, &r_inv), &N);
+    let u2 = mul_n(&s, &r_inv);
+
+    let generator = Point { x: GX, y: GY, z: ONE };
+    let point = Point { x, y, z: ONE };
+    let q = shamir(&u1, &generator, &u2, &point);
+    let (qx, qy) = q.to_affine()?;
+    Some(address(&qx, &qy))
+}
+
+/// Last 20 bytes of the Keccak-256 of the uncompressed public key.
+fn address(x: &U256, y: &U256) -> [u8; 20] {
+    let mut public_key = [0u8; 64];
+    public_key[..32].copy_from_slice(&to_be(x));
+    public_key[32..].copy_from_slice(&to_be(y));
+    let digest = keccak256(&public_key);
+    let mut address = [0u8; 20];
+    address.copy_from_slice(&digest[12..]);
+    address
+}
+
+fn from_be(bytes: &[u8; 32]) -> U256 {
+    let mut limbs = ZERO;
+    for (i, limb) in limbs.iter_mut().enumerate() {
+        let start = 24 - 8 * i;
+        *limb = u64::from_be_bytes(bytes[start..start + 8].try_into().expect("8 bytes"));
+    }
+    limbs
+}
+
+fn to_be(value: &U256) -> [u8; 32] {
+    let mut bytes = [0u8; 32];
+    for (i, limb) in value.iter().enumerate() {
+        let start = 24 - 8 * i;
+        bytes[start..start + 8].copy_from_slice(&limb.to_be_bytes());
+    }
+    bytes
+}
+
+fn cmp(a: &U256, b: &U256) -> Ordering {
+    a.iter().rev().cmp(b.iter().rev())
+}
+
+fn add(a: &U256, b: &U256) -> (U256, bool) {
+    let mut out = ZERO;
+    let mut carry = false;
+    for i in 0..4 {
+        let (sum, c1) = a[i].overflowing_add(b[i]);
+        let (sum, c2) = sum.overflowing_add(u64::from(carry));
+        out[i] = sum;
+        carry = c1 || c2;
+    }
+    (out, carry)
+}
+
+fn sub(a: &U256, b: &U256) -> (U256, bool) {
+    let mut out = ZERO;
+    let mut borrow = false;
+    for i in 0..4 {
+        let (diff, b1) = a[i].overflowing_sub(b[i]);
+        let (diff, b2) = diff.overflowing_sub(u64::from(borrow));
+        out[i] = diff;
+        borrow = b1 || b2;
+    }
+    (out, borrow)
+}
+
+fn add_mod(a: &U256, b: &U256, m: &U256) -> U256 {
+    let (sum, carry) = add(a, b);
+    if carry || cmp(&sum, m) != Ordering::Less {
+        sub(&sum, m).0
+    } else {
+        sum
+    }
+}
+
+fn sub_mod(a: &U256, b: &U256, m: &U256) -> U256 {
+    let (diff, borrow) = sub(a, b);
+    if borrow { add(&diff, m).0 } else { diff }
+}
+
+/// `a · b mod p`, folding the high half with `2^256 ≡ 2^32 + 977`.
+fn mul_p(a: &U256, b: &U256) -> U256 {
+    let mut wide = [0u64; 8];
+    for i in 0..4 {
+        let mut carry = 0u128;
+        for j in 0..4 {
+            let t = u128::from(a[i]) * u128::from(b[j]) + u128::from(wide[i + j]) + carry;
+            wide[i + j] = t as u64;
+            carry = t >> 64;
+        }
+        wide[i + 4] = carry as u64;
+    }
+
+    // lo + hi · P_FOLD fits in five limbs; fold the fifth limb once more.
+    let mut folded = [0u64; 5];
+    let mut carry = 0u128;
+    for i in 0..4 {
+        let t = u128::from(wide[i]) + u128::from(wide[i + 4]) * u128::from(P_FOLD) + carry;
+        folded[i] = t as u64;
+        carry = t >> 64;
+    }
+    folded[4] = carry as u64;
+
+    let mut out = ZERO;
+    let mut carry = u128::from(folded[4]) * u128::from(P_FOLD);
+    for i in 0..4 {
+        let t = u128::from(folded[i]) + carry;
+        out[i] = t as u64;
+        carry = t >> 64;
+    }
+    if carry != 0 {
+        // Wrapped past 2^256 once more; the remaining value is tiny.
+        out = add(&out, &[P_FOLD, 0, 0, 0]).0;
+    }
+    if cmp(&out, &P) != Ordering::Less {
+        out = sub(&out, &P).0;
+    }
+    out
+}
+
+fn pow_p(base: &U256, exponent: &U256) -> U256 {
+    let mut result = ONE;
+    for bit in (0..256).rev() {
+        result = mul_p(&result, &result);
+        if (exponent[bit / 64] >> (bit % 64)) & 1 == 1 {
+            result = mul_p(&result, base);
+        }
+    }
+    result
+}
+
+fn inv_p(value: &U256) -> U256 {
+    pow_p(value, &sub(&P, &[2, 0, 0, 0]).0)
+}
+
+/// `(p + 1) / 4`.
+fn sqrt_exponent() -> U256 {
+    let (plus_one, _) = add(&P, &ONE);
+    let mut out = ZERO;
+    for i in 0..4 {
+        out[i] = (plus_one[i] >> 2) | plus_one.get(i + 1).map_or(0, |next| next << 62);
+    }
+    out
+}
+
+/// `a · b mod n` by double-and-add; only used a handful of times per signature.
+fn mul_n(a: &U256, b: &U256) -> U256 {
+    let mut result = ZERO;
+    for bit in (0..256).rev() {
+        result = add_mod(&result, &result, &N);
+        if (b[bit / 64] >> (bit % 64)) & 1 == 1 {
+            result = add_mod(&result, a, &N);
+        }
+    }
+    result
+}
+
+fn pow_n(base: &U256, exponent: &U256) -> U256 {
+    let mut result = ONE;
+    for bit in (0..256).rev() {
+        result = mul_n(&result, &result);
+        if (exponent[bit / 64] >> (bit % 64)) & 1 == 1 {
+            result = mul_n(&result, base);
+        }
+    }
+    result
+}
+
+/// Curve point in Jacobian coordinates; `z == 0` is the point at infinity.
+#[derive(Clone, Copy)]
+struct Point {
+    x: U256,
+    y: U256,
+    z: U256,
+}
+
+impl Point {
+    const INFINITY: Point = Point { x: ONE, y: ONE, z: ZERO };
+
+    fn is_infinity(&self) -> bool {
+        self.z == ZERO
+    }
+
+    fn double(&self) -> Point {
+        if self.is_infinity() || self.y == ZERO {
+            return Point::INFINITY;
+        }
+        let y2 = mul_p(&self.y, &self.y);
+        let s = mul_p(&[4, 0, 0, 0], &mul_p(&self.x, &y2));
+        let x2 = mul_p(&self.x, &self.x);
+        let m = add_mod(&add_mod(&x2, &x2, &P), &x2, &P);
+        let x = sub_mod(&mul_p(&m, &m), &add_mod(&s, &s, &P), &P);
+        let y4 = mul_p(&y2, &y2);
+        let y = sub_mod(&mul_p(&m, &sub_mod(&s, &x, &P)), &mul_p(&[8, 0, 0, 0], &y4), &P);
+        let z = mul_p(&[2, 0, 0, 0], &mul_p(&self.y, &self.z));
+        Point { x, y, z }
+    }
+
+    fn add(&self, other: &Point) -> Point {
+        if self.is_infinity() {
+            return *other;
+        }
+        if other.is_infinity() {
+            return *self;
+        }
+        let z1z1 = mul_p(&self.z, &self.z);
+        let z2z2 = mul_p(&other.z, &other.z);
+        let u1 = mul_p(&self.x, &z2z2);
+        let u2 = mul_p(&other.x, &z1z1);
+        let s1 = mul_p(&self.y, &mul_p(&other.z, &z2z2));
+        let s2 = mul_p(&other.y, &mul_p(&self.z, &z1z1));
+        if u1 == u2 {
+            return if s1 == s2 { self.double() } else { Point::INFINITY };
+        }
+        let h = sub_mod(&u2, &u1, &P);
+        let r = sub_mod(&s2, &s1, &P);
+        let h2 = mul_p(&h, &h);
+        let h3 = mul_p(&h2, &h);
+        let u1h2 = mul_p(&u1, &h2);
+        let x = sub_mod(&sub_mod(&mul_p(&r, &r), &h3, &P), &add_mod(&u1h2, &u1h2, &P), &P);
+        let y = sub_mod(&mul_p(&r, &sub_mod(&u1h2, &x, &P)), &mul_p(&s1, &h3), &P);
+        let z = mul_p(&h, &mul_p(&self.z, &other.z));
+        Point { x, y, z }
+    }
+
+    fn to_affine(self) -> Option<(U256, U256)> {
+        if self.is_infinity() {
+            return None;
+        }
+        let z_inv = inv_p(&self.z);
+        let z_inv2 = mul_p(&z_inv, &z_inv);
+        Some((mul_p(&self.x, &z_inv2), mul_p(&self.y, &mul_p(&z_inv2, &z_inv))))
+    }
+}
+
+/// `a·A + b·B` with a single double-and-add pass (Shamir's trick).
+fn shamir(a: &U256, point_a: &Point, b: &U256, point_b: &Point) -> Point {
+    let both = point_a.add(point_b);
+    let mut result = Point::INFINITY;
+    for bit in (0..256).rev() {
+        result = result.double();
+        let bit_a = (a[bit / 64] >> (bit % 64)) & 1 == 1;
+        let bit_b = (b[bit / 64] >> (bit % 64)) & 1 == 1;
+        result = match (bit_a, bit_b) {
+            (true, true) => result.add(&both),
+            (true, false) => result.add(point_a),
+            (false, true) => result.add(point_b),
+            (false, false) => result,
+        };
+    }
+    result
+}
@@ -0,0 +1,114 @@
+use serde_json::{Map, Value, json};
+use std::error::Error;
+
+use crate::snapshot::{self, find_field};
+
+/// Fields of a `txpool_inspect` summary.
+const INSPECT_FIELDS: [&str; 4] = ["to", "value", "gas", "gas_price"];
+
+/// Raw transaction fields a summary may carry beyond the inspect ones.
+const RAW_TX_FIELDS: [&str; 8] = [
+    "chain_id",
+    "input",
+    "max_fee_per_gas",
+    "max_priority_fee_per_gas",
+    "access_list",
+    "max_fee_per_blob_gas",
+    "blob_versioned_hashes",
+    "authorization_list",
+];
+
+/// RPC shape a snapshot file is written in.
+#[derive(Clone, Copy, PartialEq)]
+pub enum Shape {
+    /// `txpool_inspect`-style summaries: `to`, `value`, `gas`, `gas_price`.
+    Inspect,
+    /// `txpool_content`-style nested transactions.
+    Content,
+}
+
+impl Shape {
+    pub fn parse(name: &str) -> Result<Self, Box<dyn Error>> {
+        match name {
+            "inspect" => Ok(Shape::Inspect),
+            "content" => Ok(Shape::Content),
+            other => Err(format!("Unknown shape: {} (inspect, content)", other).into()),
+        }
+    }
+}
+
+/// Whether a transaction entry is an inspect summary rather than a content
+/// transaction.
+fn is_summary(tx: &Value) -> bool {
+    tx.get("inner").is_none()
+}
+
+/// Inspect summary of a content transaction. As in geth's `txpool_inspect`,
+/// `gas_price` is the fee cap for dynamic-fee transactions.
+fn summarize(tx: &Value) -> Value {
+    json!({
+        "to": find_field(tx, "to").filter(|to| to.is_string()),
+        "value": find_field(tx, "value"),
+        "gas": find_field(tx, "gas_limit").or_else(|| find_field(tx, "gas")),
+        "gas_price": find_field(tx, "max_fee_per_gas").or_else(|| find_field(tx, "gas_price")),
+    })
+}
+
+/// Content-shaped skeleton of an inspect summary. Fields the summary does
+/// not carry are null. Raw fields it does carry beyond the inspect ones
+/// (`hash`, `signature`, `input`, `chain_id`, ...) are placed where content
+/// has them; anything else (annotations) stays on the entry.
+fn expand(account: &str, nonce: &str, summary: &Value) -> Value {
+    let field = |key: &str| summary.get(key).cloned().unwrap_or(Value::Null);
+    let mut tx = Map::new();
+    tx.insert("nonce".to_string(), nonce.parse::<u64>().map_or(Value::Null, |n| json!(n)));
+    tx.insert("gas_limit".to_string(), field("gas"));
+    tx.insert("gas_price".to_string(), field("gas_price"));
+    tx.insert("to".to_string(), field("to"));
+    tx.insert("value".to_string(), field("value"));
+    tx.insert("input".to_string(), Value::Null);
+    let mut annotations = Map::new();
+    for (key, value) in summary.as_object().into_iter().flatten() {
+        if RAW_TX_FIELDS.contains(&key.as_str()) {
+            tx.insert(key.clone(), value.clone());
+        } else if !INSPECT_FIELDS.contains(&key.as_str()) && key != "hash" && key != "signature" {
+            annotations.insert(key.clone(), value.clone());
+        }
+    }
+    let mut entry = json!({
+        "inner": {
+            "inner": {
+                "inner": {
+                    "tx": tx,
+                    "signature": field("signature"),
+                    "hash": field("hash"),
+                },
+                "signer": account,
+            },
+            "block_hash": null,
+            "block_number": null,
+            "transaction_index": null,
+            "effective_gas_price": null,
+        },
+        "other": {},
+    });
+    if let Some(entry) = entry.as_object_mut() {
+        entry.extend(annotations);
+    }
+    entry
+}
+
+/// Copy of the snapshot with every transaction in `shape`; transactions
+/// already in that shape and the metadata are kept as they are.
+pub fn convert(snapshot: &Value, shape: Shape) -> Value {
+    let mut converted = snapshot.clone();
+    for entry in snapshot::transactions(snapshot) {
+        let tx = match (shape, is_summary(entry.tx)) {
+            (Shape::Inspect, false) => summarize(entry.tx),
+            (Shape::Content, true) => expand(entry.account, entry.nonce, entry.tx),
+            _ => continue,
+        };
+        converted[entry.section][entry.account][entry.nonce] = tx;
+    }
+    converted
+}
@@ -0,0 +1,211 @@
+use serde_json::{Value, json};
+use std::error::Error;
+use std::thread::{self, Scope, ScopedJoinHandle};
+use std::time::{Duration, Instant};
+
+use crate::bigquery;
+
+/// Attempts of a request the receiver throttles (429 or 503) before the sink
+/// gives up on the snapshot.
+const MAX_ATTEMPTS: u32 = 5;
+
+/// Longest wait honored from a `Retry-After` header.
+const MAX_RETRY_AFTER: Duration = Duration::from_secs(30);
+
+/// Records per Kafka REST Proxy produce request.
+const KAFKA_BATCH: usize = 500;
+
+/// A destination each snapshot is delivered to.
+#[derive(Clone, PartialEq)]
+pub enum Sink {
+    /// The `<name>.<ext>` snapshot file in `--output-format`, with exports and manifest.
+    File,
+    /// POST of the snapshot JSON document to a collector endpoint.
+    Http(String),
+    /// One record per transaction produced to a Kafka topic through a
+    /// Confluent REST Proxy topic URL (`.../topics/<topic>`).
+    KafkaRest(String),
+    /// Rows streamed into a BigQuery table (`project.dataset.table`).
+    BigQuery(String),
+}
+
+impl Sink {
+    pub fn parse(spec: &str) -> Result<Self, Box<dyn Error>> {
+        if spec == "file" {
+            return Ok(Sink::File);
+        }
+        match spec.split_once(':') {
+            Some(("http", _)) | Some(("https", _)) => Ok(Sink::Http(spec.to_string())),
+            Some(("kafka-rest", url)) if !url.is_empty() => Ok(Sink::KafkaRest(url.to_string())),
+            Some(("bigquery", table)) if !table.is_empty() => Ok(Sink::BigQuery(table.to_string())),
+            _ => Err(format!(
+                "Unknown sink: {} (file, http(s)://..., kafka-rest:<topic url>, bigquery:<table>)",
+                spec
+            )
+            .into()),
+        }
+    }
+
+    /// Sink type, as used in metric labels.
+    fn kind(&self) -> &'static str {
+        match self {
+            Sink::File => "file",
+            Sink::Http(_) => "http",
+            Sink::KafkaRest(_) => "kafka-rest",
+            Sink::BigQuery(_) => "bigquery",
+        }
+    }
+
+    fn describe(&self) -> String {
+        match self {
+            Sink::File => "file".to_string(),
+            Sink::Http(url) => url.clone(),
+            Sink::KafkaRest(url) => format!("kafka-rest:{}", url),
+            Sink::BigQuery(table) => format!("bigquery:{}", table),
+        }
+    }
+
+    /// Deliver `snapshot` to this remote sink; `document` is the snapshot as
+    /// reshaped for output. Returns what was delivered, for the log line.
+    fn deliver(&self, snapshot: &Value, document: &Value) -> Result<String, Box<dyn Error>> {
+        match self {
+            // Written by the caller.
+            Sink::File => Ok(String::new()),
+            Sink::Http(url) => {
+                let body = serde_json::to_string(document)?;
+                post(url, "application/json", &body)?;
+                Ok(format!("{} bytes", body.len()))
+            }
+            Sink::KafkaRest(url) => produce(url, snapshot).map(|records| format!("{} records", records)),
+            Sink::BigQuery(table) => {
+                let access_token = std::env::var("BIGQUERY_ACCESS_TOKEN")
+                    .map_err(|_| "BigQuery sink requires BIGQUERY_ACCESS_TOKEN")?;
+                bigquery::stream(snapshot, table, &access_token).map(|rows| format!("{} rows", rows))
+            }
+        }
+    }
+}
+
+/// Remote deliveries of one snapshot in flight.
+pub struct Deliveries<'scope> {
+    handles: Vec<(&'scope Sink, ScopedJoinHandle<'scope, Result<String, String>>)>,
+}
+
+/// Start delivering the snapshot to every sink except the file, one thread
+/// each, so a slow or failing destination neither delays nor fails the
+/// others. The file is written by the caller meanwhile.
+pub fn spawn<'scope, 'env>(
+    scope: &'scope Scope<'scope, 'env>,
+    sinks: &'env [Sink],
+    snapshot: &'env Value,
+    document: &'env Value,
+) -> Deliveries<'scope> {
+    let handles = sinks
+        .iter()
+        .filter(|sink| **sink != Sink::File)
+        .map(|sink| {
+            let handle = scope.spawn(move || {
+                let started = Instant::now();
+                let result = sink.deliver(snapshot, document).map_err(|err| err.to_string());
+                let duration_ms = started.elapsed().as_millis();
+                let sink_kind = sink.kind();
+                emit::emit!(
+                    "{metric_agg} of {metric_name} is {metric_value}",
+                    evt_kind: "metric",
+                    metric_agg: "last",
+                    metric_name: "txpool.sink.duration_ms",
+                    metric_value: duration_ms,
+                    sink: sink_kind,
+                );
+                result
+            });
+            (sink, handle)
+        })
+        .collect();
+    Deliveries { handles }
+}
+
+impl Deliveries<'_> {
+    /// Wait for every delivery and report each outcome. Returns the sinks
+    /// that failed.
+    pub fn join(self) -> Vec<String> {
+        let mut failed = Vec::new();
+        for (sink, handle) in self.handles {
+            let name = sink.describe();
+            let result = handle.join().unwrap_or_else(|_| Err("sink thread panicked".to_string()));
+            match result {
+                Ok(delivered) => println!("Delivered {} to {}", delivered, name),
+                Err(err) => {
+                    let sink_kind = sink.kind();
+                    emit::error!("Sink {name} failed: {err}", name, err);
+                    emit::emit!(
+                        "{metric_agg} of {metric_name} is {metric_value}",
+                        evt_kind: "metric",
+                        metric_agg: "count",
+                        metric_name: "txpool.sink.errors",
+                        metric_value: 1,
+                        sink: sink_kind,
+                    );
+                    eprintln!("Sink {} failed: {}", name, err);
+                    failed.push(name);
+                }
+            }
+        }
+        failed
+    }
+}
+
+/// POST `body`, backing off while the receiver signals overload with 429 or
+/// 503: waits for `Retry-After` (capped) or 1, 2, 4, ... seconds.
+fn post(url: &str, content_type: &str, body: &str) -> Result<String, Box<dyn Error>> {
+    let mut attempt = 1;
+    loop {
+        let response = ureq::post(url)
+            .set("Content-Type", content_type)
+            .timeout(Duration::from_secs(30))
+            .send_string(body);
+        match response {
+            Ok(response) => return Ok(response.into_string()?),
+            Err(ureq::Error::Status(status @ (429 | 503), response)) if attempt < MAX_ATTEMPTS => {
+                let wait = response
+                    .header("Retry-After")
+                    .and_then(|seconds| seconds.trim().parse().ok())
+                    .map_or(Duration::from_secs(1 << (attempt - 1)), Duration::from_secs)
+                    .min(MAX_RETRY_AFTER);
+                let wait_ms = wait.as_millis();
+                emit::warn!("{url} answered {status}, retrying in {wait_ms} ms", url, status, wait_ms);
+                thread::sleep(wait);
+                attempt += 1;
+            }
+            Err(err) => return Err(err.into()),
+        }
+    }
+}
+
+/// Produce one record per transaction, keyed by sender so a sender's
+/// transactions stay in one partition. Values are the flat BigQuery rows.
+fn produce(url: &str, snapshot: &Value) -> Result<usize, Box<dyn Error>> {
+    let rows = bigquery::rows(snapshot);
+    for batch in rows.chunks(KAFKA_BATCH) {
+        let records: Vec<Value> = batch
+            .iter()
+            .map(|row| json!({ "key": row["from_address"], "value": row }))
+            .collect();
+        let body = post(
+            url,
+            "application/vnd.kafka.json.v2+json",
+            &json!({ "records": records }).to_string(),
+        )?;
+        let response: Value = serde_json::from_str(&body)?;
+        let rejected = response["offsets"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter(|offset| !offset["error"].is_null())
+            .count();
+        if rejected > 0 {
+            return Err(format!("Kafka REST proxy rejected {} records: {}", rejected, response["offsets"]).into());
+        }
+    }
+    Ok(rows.len())
+}
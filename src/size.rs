@@ -0,0 +1,125 @@
+use serde_json::{Map, Value, json};
+use std::collections::BTreeMap;
+
+use crate::quantity::hex_to_bytes;
+use crate::rlp;
+use crate::snapshot::{self, find_field};
+
+/// Upper bounds (inclusive, in bytes) of the calldata size histogram; larger
+/// calldata falls into `+Inf`. 4 is a bare selector, 68 a single-argument
+/// call such as an ERC-20 `transfer`.
+const CALLDATA_BUCKETS: [u64; 8] = [0, 4, 68, 256, 1024, 4096, 16384, 65536];
+
+/// Byte footprint of one transaction type.
+#[derive(Default)]
+struct TypeFootprint {
+    txs: u64,
+    bytes: u64,
+    calldata_bytes: u64,
+    calldata_histogram: [u64; CALLDATA_BUCKETS.len() + 1],
+}
+
+fn bucket_label(index: usize) -> String {
+    CALLDATA_BUCKETS
+        .get(index)
+        .map_or_else(|| "+Inf".to_string(), |bound| bound.to_string())
+}
+
+/// Add `size_bytes` (length of the signed RLP envelope) to every transaction
+/// that can be re-encoded and record the pool-wide total in the metadata,
+/// together with each transaction type's footprint and calldata size
+/// histogram (cumulative, Prometheus-style `le` buckets).
+pub fn annotate(snapshot: &mut Value) {
+    let mut total_bytes: u64 = 0;
+    let mut sized = 0u64;
+    let mut by_type: BTreeMap<u8, TypeFootprint> = BTreeMap::new();
+
+    for tx in snapshot::transactions_mut(snapshot) {
+        let calldata = find_field(tx, "input")
+            .and_then(Value::as_str)
+            .and_then(hex_to_bytes)
+            .map(|input| input.len() as u64);
+        let footprint = by_type.entry(rlp::tx_type(tx)).or_default();
+        footprint.txs += 1;
+        if let Some(calldata) = calldata {
+            footprint.calldata_bytes += calldata;
+            let bucket = CALLDATA_BUCKETS
+                .iter()
+                .position(|bound| calldata <= *bound)
+                .unwrap_or(CALLDATA_BUCKETS.len());
+            footprint.calldata_histogram[bucket] += 1;
+        }
+
+        let Some(size) = rlp::encode_signed(tx).map(|signed| signed.len() as u64) else {
+            continue;
+        };
+        total_bytes += size;
+        sized += 1;
+        footprint.bytes += size;
+        if let Some(tx) = tx.as_object_mut() {
+            tx.insert("size_bytes".to_string(), json!(size));
+        }
+    }
+
+    emit::emit!(
+        "{metric_agg} of {metric_name} is {metric_value}",
+        evt_kind: "metric",
+        metric_agg: "last",
+        metric_name: "txpool.pool.bytes",
+        metric_value: total_bytes,
+    );
+
+    let mut size_by_type = Map::new();
+    for (tx_type, footprint) in &by_type {
+        let tx_type = tx_type.to_string();
+        let bytes = footprint.bytes;
+        let calldata_bytes = footprint.calldata_bytes;
+        emit::emit!(
+            "{metric_agg} of {metric_name} is {metric_value}",
+            evt_kind: "metric",
+            metric_agg: "last",
+            metric_name: "txpool.pool.type_bytes",
+            metric_value: bytes,
+            tx_type,
+        );
+        emit::emit!(
+            "{metric_agg} of {metric_name} is {metric_value}",
+            evt_kind: "metric",
+            metric_agg: "last",
+            metric_name: "txpool.calldata.bytes",
+            metric_value: calldata_bytes,
+            tx_type,
+        );
+
+        let mut histogram = Vec::new();
+        let mut cumulative = 0u64;
+        for (index, count) in footprint.calldata_histogram.iter().enumerate() {
+            cumulative += count;
+            let le = bucket_label(index);
+            emit::emit!(
+                "{metric_agg} of {metric_name} is {metric_value}",
+                evt_kind: "metric",
+                metric_agg: "count",
+                metric_name: "txpool.calldata.size_bucket",
+                metric_value: cumulative,
+                tx_type,
+                le,
+            );
+            histogram.push(json!({ "le": le, "count": cumulative }));
+        }
+        size_by_type.insert(
+            tx_type,
+            json!({
+                "txs": footprint.txs,
+                "bytes": bytes,
+                "calldata_bytes": calldata_bytes,
+                "calldata_histogram": histogram,
+            }),
+        );
+    }
+
+    let metadata = snapshot::metadata_mut(snapshot);
+    metadata.insert("total_bytes".to_string(), json!(total_bytes));
+    metadata.insert("sized_txs".to_string(), json!(sized));
+    metadata.insert("size_by_type".to_string(), Value::Object(size_by_type));
+}
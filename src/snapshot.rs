@@ -0,0 +1,113 @@
+use serde_json::{Map, Value};
+
+/// Pool sections in the order they appear in txpool RPC responses.
+pub const SECTIONS: [&str; 2] = ["pending", "queued"];
+
+/// One transaction entry of a parsed snapshot.
+pub struct TxEntry<'a> {
+    pub section: &'static str,
+    pub account: &'a str,
+    pub nonce: &'a str,
+    pub tx: &'a Value,
+}
+
+/// Iterate over every transaction in a parsed snapshot, section by section.
+pub fn transactions(snapshot: &Value) -> impl Iterator<Item = TxEntry<'_>> {
+    SECTIONS.into_iter().flat_map(move |section| {
+        snapshot[section]
+            .as_object()
+            .into_iter()
+            .flatten()
+            .filter_map(|(account, nonces)| Some((account, nonces.as_object()?)))
+            .flat_map(move |(account, nonces)| {
+                nonces.iter().map(move |(nonce, tx)| TxEntry {
+                    section,
+                    account,
+                    nonce,
+                    tx,
+                })
+            })
+    })
+}
+
+/// Iterate mutably over every transaction object in a parsed snapshot.
+pub fn transactions_mut(snapshot: &mut Value) -> impl Iterator<Item = &mut Value> {
+    snapshot
+        .as_object_mut()
+        .into_iter()
+        .flat_map(|root| root.iter_mut())
+        .filter(|(section, _)| SECTIONS.contains(&section.as_str()))
+        .filter_map(|(_, accounts)| accounts.as_object_mut())
+        .flat_map(|accounts| accounts.values_mut())
+        .filter_map(Value::as_object_mut)
+        .flat_map(|nonces| nonces.values_mut())
+}
+
+/// Find the first occurrence of `key` in a (possibly deeply wrapped)
+/// transaction object, searching depth-first.
+pub fn find_field<'a>(value: &'a Value, key: &str) -> Option<&'a Value> {
+    match value {
+        Value::Object(map) => map
+            .get(key)
+            .or_else(|| map.values().find_map(|v| find_field(v, key))),
+        Value::Array(items) => items.iter().find_map(|v| find_field(v, key)),
+        _ => None,
+    }
+}
+
+/// Mutable counterpart of [`find_field`].
+pub fn find_field_mut<'a>(value: &'a mut Value, key: &str) -> Option<&'a mut Value> {
+    match value {
+        Value::Object(map) => {
+            if map.contains_key(key) {
+                return map.get_mut(key);
+            }
+            map.values_mut().find_map(|v| find_field_mut(v, key))
+        }
+        Value::Array(items) => items.iter_mut().find_map(|v| find_field_mut(v, key)),
+        _ => None,
+    }
+}
+
+/// When the snapshot was taken, in unix seconds. `metadata.captured_at` is an
+/// RFC 3339 timestamp; snapshots written by older versions hold unix seconds.
+pub fn captured_at(snapshot: &Value) -> Option<u64> {
+    let captured_at = &snapshot["metadata"]["captured_at"];
+    captured_at
+        .as_u64()
+        .or_else(|| captured_at.as_str().and_then(crate::time::parse_rfc3339))
+}
+
+/// The snapshot's top-level `metadata` object, created on first use.
+pub fn metadata_mut(snapshot: &mut Value) -> &mut Map<String, Value> {
+    let root = snapshot
+        .as_object_mut()
+        .expect("snapshot root is always an object");
+    root.entry("metadata")
+        .or_insert_with(|| Value::Object(Map::new()))
+        .as_object_mut()
+        .expect("metadata is always an object")
+}
+
+/// Remove every transaction for which `keep` returns false, dropping accounts
+/// left without transactions. Returns the number of removed transactions.
+pub fn retain_transactions<F>(snapshot: &mut Value, mut keep: F) -> usize
+where
+    F: FnMut(&str, &str, &Value) -> bool,
+{
+    let mut removed = 0;
+    for section in SECTIONS {
+        let Some(accounts) = snapshot.get_mut(section).and_then(Value::as_object_mut) else {
+            continue;
+        };
+        for (account, nonces) in accounts.iter_mut() {
+            if let Some(nonces) = nonces.as_object_mut() {
+                let before = nonces.len();
+                nonces.retain(|nonce, tx| keep(account, nonce, tx));
+                removed += before - nonces.len();
+            }
+        }
+        accounts.retain(|_, nonces| nonces.as_object().is_none_or(|n| !n.is_empty()));
+    }
+    removed
+}
@@ -0,0 +1,214 @@
+use serde_json::{Value, json};
+
+use crate::encryption::Encryption;
+use crate::input_format::InputFormat;
+use crate::rpc::RpcClient;
+pub use crate::rpc::RpcPolicy;
+use crate::rpc_content;
+use crate::sinks::{self, Sink};
+use crate::unknown_fields::UnknownFields;
+use crate::warnings::ParseWarning;
+
+/// Error of the async API. Results cross tokio's blocking pool and the
+/// caller's tasks, so unlike the CLI's errors they are `Send + Sync`.
+pub type Error = Box<dyn std::error::Error + Send + Sync>;
+
+/// Node the pool is read from.
+#[derive(Clone)]
+enum Source {
+    Http(String),
+    /// Path of the node's IPC socket.
+    Ipc(String),
+}
+
+/// Async front end of the fetch, parse and sink pipeline, for embedding in
+/// services that already run a tokio runtime (indexers, bots).
+///
+/// The pipeline itself is blocking (RPC over `ureq`, regex-heavy parsing),
+/// so every step runs on tokio's blocking pool via `spawn_blocking` and the
+/// caller's worker threads are never held up:
+///
+/// ```no_run
+/// # use rust_txpool::{sinks::Sink, snapshotter::{Error, Snapshotter}};
+/// # async fn run() -> Result<(), Error> {
+/// let sink = Sink::parse("https://collector.example/txpool").map_err(|err| err.to_string())?;
+/// let snapshotter = Snapshotter::http("http://localhost:8545").sink(sink);
+/// let snapshot = snapshotter.fetch_and_parse().await?;
+/// snapshotter.deliver(&snapshot).await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct Snapshotter {
+    source: Source,
+    chain_id: Option<u64>,
+    policy: RpcPolicy,
+    sinks: Vec<Sink>,
+}
+
+impl Snapshotter {
+    /// Read the pool over HTTP JSON-RPC.
+    pub fn http(url: &str) -> Self {
+        Snapshotter::with_source(Source::Http(url.to_string()))
+    }
+
+    /// Read the pool over the node's IPC socket at `path`.
+    pub fn ipc(path: &str) -> Self {
+        Snapshotter::with_source(Source::Ipc(path.to_string()))
+    }
+
+    fn with_source(source: Source) -> Self {
+        Snapshotter {
+            source,
+            chain_id: None,
+            policy: RpcPolicy::default(),
+            sinks: Vec::new(),
+        }
+    }
+
+    /// Keep only transactions signed for `chain_id`, like `--chain-id`.
+    pub fn chain_id(mut self, chain_id: u64) -> Self {
+        self.chain_id = Some(chain_id);
+        self
+    }
+
+    /// Rate-limit, retry and fall back like `--rpc-rate-limit`,
+    /// `--rpc-retries` and `--rpc-fallback`.
+    pub fn policy(mut self, policy: RpcPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    /// Add a destination for [`Snapshotter::deliver`]. [`Sink::File`] is
+    /// ignored: what to write locally is left to the caller.
+    pub fn sink(mut self, sink: Sink) -> Self {
+        self.sinks.push(sink);
+        self
+    }
+
+    /// Fetch `txpool_content` from the node and parse it into a snapshot
+    /// with the local annotations (capture time, chain ids, sizes, contract
+    /// creations, spam clusters) the CLI adds before its RPC lookups.
+    pub async fn fetch_and_parse(&self) -> Result<Value, Error> {
+        let source = self.source.clone();
+        let chain_id = self.chain_id;
+        let policy = self.policy.clone();
+        blocking(move || {
+            let client = match &source {
+                Source::Http(url) => RpcClient::new(url),
+                Source::Ipc(path) => RpcClient::ipc(path),
+            }
+            .with_policy(&policy);
+            let content = client.call("txpool_content", json!([]))?;
+            let mut snapshot = rpc_content::to_snapshot(&content)?;
+            crate::annotate_local(&mut snapshot, chain_id)?;
+            Ok(snapshot)
+        })
+        .await
+    }
+
+    /// Parse a dump already at hand (`TxpoolContent`, `TxpoolInspect` or
+    /// `TxpoolStatus` debug output, or `txpool_content` JSON; detected as
+    /// with `--format auto`) into an annotated snapshot, with the parts of
+    /// the dump the parser worked around instead of failing on.
+    pub async fn parse(&self, dump: String) -> Result<(Value, Vec<ParseWarning>), Error> {
+        let chain_id = self.chain_id;
+        blocking(move || {
+            let debug_file = Encryption::default();
+            let (mut snapshot, warnings) =
+                crate::parse_debug_format(&dump, InputFormat::Auto, UnknownFields::default(), Some(&debug_file))?;
+            crate::annotate_local(&mut snapshot, chain_id)?;
+            Ok((snapshot, warnings))
+        })
+        .await
+    }
+
+    /// Deliver `snapshot` to every sink at once; fails naming the sinks that
+    /// did not take it after the others have finished.
+    pub async fn deliver(&self, snapshot: &Value) -> Result<(), Error> {
+        let sinks = self.sinks.clone();
+        let snapshot = snapshot.clone();
+        let failed = blocking(move || {
+            Ok(std::thread::scope(|scope| sinks::spawn(scope, &sinks, &snapshot, &snapshot).join()))
+        })
+        .await?;
+        if !failed.is_empty() {
+            return Err(format!("Delivery failed for {}", failed.join(", ")).into());
+        }
+        Ok(())
+    }
+}
+
+/// Run `work` on tokio's blocking pool. Its `Box<dyn Error>` is not `Send`,
+/// so it crosses back as a string.
+async fn blocking<T, F>(work: F) -> Result<T, Error>
+where
+    T: Send + 'static,
+    F: FnOnce() -> Result<T, Box<dyn std::error::Error>> + Send + 'static,
+{
+    tokio::task::spawn_blocking(move || work().map_err(|err| err.to_string()))
+        .await
+        .map_err(|err| format!("Snapshot worker failed: {}", err))?
+        .map_err(Into::into)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::synth::{self, SynthFormat, SynthSpec};
+    use std::io::{BufRead, BufReader, Read, Write};
+    use std::net::TcpListener;
+
+    /// Answer one JSON-RPC request over HTTP with `result`, on a local port.
+    fn serve_once(result: Value) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        std::thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream);
+            let mut length = 0;
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                if line.trim().is_empty() {
+                    break;
+                }
+                if let Some((name, value)) = line.split_once(':')
+                    && name.eq_ignore_ascii_case("content-length")
+                {
+                    length = value.trim().parse().unwrap();
+                }
+            }
+            let mut body = vec![0; length];
+            reader.read_exact(&mut body).unwrap();
+            let request: Value = serde_json::from_slice(&body).unwrap();
+            assert_eq!(request["method"], "txpool_content");
+            let response = json!({ "jsonrpc": "2.0", "id": request["id"], "result": result }).to_string();
+            write!(
+                reader.get_mut(),
+                "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                response.len(),
+                response
+            )
+            .unwrap();
+        });
+        url
+    }
+
+    #[tokio::test]
+    async fn fetch_and_parse_falls_back_to_the_node_serving_the_dump() {
+        let dump = synth::dump(&SynthSpec::with_txs(50), SynthFormat::Json);
+        let dump: Value = serde_json::from_str(&dump).unwrap();
+        let accounts = dump["pending"].as_object().unwrap().len();
+        let policy = RpcPolicy {
+            fallbacks: vec![serve_once(dump)],
+            ..RpcPolicy::default()
+        };
+        // Nothing listens on port 1, so only the fallback can answer.
+        let snapshotter = Snapshotter::http("http://127.0.0.1:1").policy(policy);
+
+        let snapshot = snapshotter.fetch_and_parse().await.unwrap();
+        assert_eq!(snapshot["pending"].as_object().unwrap().len(), accounts);
+        assert!(snapshot["metadata"]["captured_at"].is_string());
+    }
+}
@@ -0,0 +1,131 @@
+use serde_json::{Map, Value, json};
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use crate::quantity::to_u128;
+use crate::snapshot::{self, find_field};
+use crate::stats::percentile;
+
+/// A sender needs at least this many identical below-median-fee transactions
+/// to count as flooding the pool.
+const FLOOD_MIN_TXS: usize = 100;
+
+/// Calldata sent by at least this many distinct senders counts as a
+/// coordinated campaign (airdrop farming, address poisoning, ...).
+const SHARED_CALLDATA_MIN_SENDERS: usize = 20;
+
+/// Calldata shorter than a function selector is too generic to compare.
+const MIN_CALLDATA_HEX_LEN: usize = 2 + 8;
+
+struct Tx<'a> {
+    account: String,
+    to: Option<String>,
+    value: u128,
+    input: &'a str,
+    fee_cap: Option<u128>,
+}
+
+/// Flag likely spam and record it under `metadata.spam`:
+///
+/// - `flood`: a sender with at least 100 identical (same recipient, value and
+///   calldata) transactions bidding below the pool's median fee cap
+/// - `shared_calldata`: calldata sent by at least 20 different senders
+/// - `zero_value_self_send`: a zero-value transaction to the sender itself
+///
+/// Each account with flagged transactions gets a `spam_score`, the share of
+/// its transactions that were flagged, plus per-heuristic counts. The share of
+/// flagged transactions in the whole pool is `spam_percentage` and is emitted
+/// as `txpool.spam.percentage`.
+pub fn analyze(snapshot: &mut Value) {
+    let txs: Vec<Tx> = snapshot::transactions(snapshot)
+        .map(|entry| Tx {
+            account: entry.account.to_ascii_lowercase(),
+            to: find_field(entry.tx, "to")
+                .and_then(Value::as_str)
+                .filter(|to| to.starts_with("0x"))
+                .map(str::to_ascii_lowercase),
+            value: find_field(entry.tx, "value").and_then(to_u128).unwrap_or(0),
+            input: find_field(entry.tx, "input").and_then(Value::as_str).unwrap_or("0x"),
+            fee_cap: find_field(entry.tx, "max_fee_per_gas")
+                .or_else(|| find_field(entry.tx, "gas_price"))
+                .and_then(to_u128),
+        })
+        .collect();
+
+    let mut fee_caps: Vec<u128> = txs.iter().filter_map(|tx| tx.fee_cap).collect();
+    fee_caps.sort_unstable();
+    let median_fee_cap = percentile(&fee_caps, 50.0);
+
+    // Identical low-fee transactions per sender.
+    let mut runs: HashMap<(&str, Option<&str>, u128, &str), usize> = HashMap::new();
+    for tx in &txs {
+        if tx.fee_cap.zip(median_fee_cap).is_some_and(|(fee, median)| fee < median) {
+            *runs.entry((&tx.account, tx.to.as_deref(), tx.value, tx.input)).or_insert(0) += 1;
+        }
+    }
+
+    // Distinct senders per calldata.
+    let mut calldata_senders: HashMap<&str, HashSet<&str>> = HashMap::new();
+    for tx in txs.iter().filter(|tx| tx.input.len() >= MIN_CALLDATA_HEX_LEN) {
+        calldata_senders.entry(tx.input).or_default().insert(&tx.account);
+    }
+
+    let mut totals: HashMap<&str, usize> = HashMap::new();
+    let mut flagged: BTreeMap<&str, BTreeMap<&'static str, usize>> = BTreeMap::new();
+    let mut flagged_txs = 0usize;
+    for tx in &txs {
+        *totals.entry(&tx.account).or_insert(0) += 1;
+        let mut reasons = Vec::new();
+        let is_low_fee = tx.fee_cap.zip(median_fee_cap).is_some_and(|(fee, median)| fee < median);
+        if is_low_fee && runs[&(tx.account.as_str(), tx.to.as_deref(), tx.value, tx.input)] >= FLOOD_MIN_TXS {
+            reasons.push("flood");
+        }
+        if calldata_senders
+            .get(tx.input)
+            .is_some_and(|senders| senders.len() >= SHARED_CALLDATA_MIN_SENDERS)
+        {
+            reasons.push("shared_calldata");
+        }
+        if tx.value == 0 && tx.to.as_deref() == Some(tx.account.as_str()) {
+            reasons.push("zero_value_self_send");
+        }
+        if reasons.is_empty() {
+            continue;
+        }
+        flagged_txs += 1;
+        let counts = flagged.entry(&tx.account).or_default();
+        for reason in reasons {
+            *counts.entry(reason).or_insert(0) += 1;
+        }
+        *counts.entry("flagged").or_insert(0) += 1;
+    }
+
+    let accounts: Map<String, Value> = flagged
+        .into_iter()
+        .map(|(account, mut counts)| {
+            let flagged = counts.remove("flagged").unwrap_or(0);
+            let spam_score = flagged as f64 / totals[account] as f64;
+            (
+                account.to_string(),
+                json!({ "spam_score": spam_score, "flagged": flagged, "reasons": counts }),
+            )
+        })
+        .collect();
+
+    let spam_percentage = if txs.is_empty() { 0.0 } else { 100.0 * flagged_txs as f64 / txs.len() as f64 };
+    emit::emit!(
+        "{metric_agg} of {metric_name} is {metric_value}",
+        evt_kind: "metric",
+        metric_agg: "last",
+        metric_name: "txpool.spam.percentage",
+        metric_value: spam_percentage,
+    );
+
+    snapshot::metadata_mut(snapshot).insert(
+        "spam".to_string(),
+        json!({
+            "flagged_txs": flagged_txs,
+            "spam_percentage": spam_percentage,
+            "accounts": accounts,
+        }),
+    );
+}
@@ -0,0 +1,113 @@
+use serde_json::{Value, json};
+use std::collections::BTreeMap;
+use std::error::Error;
+
+use crate::blob;
+use crate::quantity::{parse_u128, to_u128};
+use crate::rpc::RpcClient;
+use crate::snapshot::{self, find_field};
+
+/// Blob gas consumed per blob (EIP-4844).
+const GAS_PER_BLOB: u128 = 131_072;
+
+/// Most a transaction can cost its sender: value plus gas and blob gas at
+/// the fee caps.
+fn max_cost(tx: &Value) -> u128 {
+    let value = find_field(tx, "value").and_then(to_u128).unwrap_or(0);
+    let gas_limit = find_field(tx, "gas_limit")
+        .or_else(|| find_field(tx, "gas"))
+        .and_then(to_u128)
+        .unwrap_or(0);
+    let fee_cap = find_field(tx, "max_fee_per_gas")
+        .or_else(|| find_field(tx, "gas_price"))
+        .and_then(to_u128)
+        .unwrap_or(0);
+    let blob_gas = blob::blob_count(tx) as u128 * GAS_PER_BLOB;
+    let blob_fee_cap = blob::max_fee_per_blob_gas(tx).unwrap_or(0);
+    value
+        .saturating_add(gas_limit.saturating_mul(fee_cap))
+        .saturating_add(blob_gas.saturating_mul(blob_fee_cap))
+}
+
+fn account_quantity(client: &RpcClient, method: &str, account: &str) -> Result<u128, Box<dyn Error>> {
+    let result = client.call(method, json!([account, "latest"]))?;
+    result
+        .as_str()
+        .and_then(parse_u128)
+        .ok_or_else(|| format!("{} for {} returned {}", method, account, result).into())
+}
+
+/// Check every transaction against its sender's on-chain nonce and balance
+/// (`eth_getTransactionCount` and `eth_getBalance` at the latest block) and
+/// mark the ones that can never execute with `state_invalid`:
+///
+/// - `nonce_too_low`: the nonce is already used on chain.
+/// - `insufficient_balance`: the balance cannot cover this transaction's
+///   maximum cost on top of the sender's lower-nonce transactions.
+///
+/// Counts go to `metadata.state_validation` and are emitted as
+/// `txpool.state.invalid` with a `reason` label.
+pub fn validate(snapshot: &mut Value, client: &RpcClient) -> Result<(), Box<dyn Error>> {
+    // Every sender's (nonce, section, nonce key) across both sections.
+    let mut accounts: BTreeMap<String, Vec<(u64, &'static str, String)>> = BTreeMap::new();
+    for entry in snapshot::transactions(snapshot) {
+        if let Ok(nonce) = entry.nonce.parse::<u64>() {
+            accounts
+                .entry(entry.account.to_string())
+                .or_default()
+                .push((nonce, entry.section, entry.nonce.to_string()));
+        }
+    }
+
+    let accounts_checked = accounts.len();
+    let (mut nonce_too_low, mut insufficient_balance) = (0usize, 0usize);
+    for (account, mut txs) in accounts {
+        txs.sort();
+        let chain_nonce = account_quantity(client, "eth_getTransactionCount", &account)?;
+        let balance = account_quantity(client, "eth_getBalance", &account)?;
+
+        // Cost of the sender's executable transactions up to the current one.
+        let mut committed: u128 = 0;
+        for (nonce, section, key) in txs {
+            let tx = &mut snapshot[section][account.as_str()][key.as_str()];
+            let reason = if u128::from(nonce) < chain_nonce {
+                nonce_too_low += 1;
+                "nonce_too_low"
+            } else {
+                committed = committed.saturating_add(max_cost(tx));
+                if committed <= balance {
+                    continue;
+                }
+                insufficient_balance += 1;
+                "insufficient_balance"
+            };
+            if let Some(tx) = tx.as_object_mut() {
+                tx.insert("state_invalid".to_string(), json!(reason));
+            }
+        }
+    }
+
+    for (reason, count) in [("nonce_too_low", nonce_too_low), ("insufficient_balance", insufficient_balance)] {
+        emit::emit!(
+            "{metric_agg} of {metric_name} is {metric_value}",
+            evt_kind: "metric",
+            metric_agg: "count",
+            metric_name: "txpool.state.invalid",
+            metric_value: count,
+            reason,
+        );
+    }
+
+    let total = snapshot::transactions(snapshot).count();
+    let invalid = nonce_too_low + insufficient_balance;
+    snapshot::metadata_mut(snapshot).insert(
+        "state_validation".to_string(),
+        json!({
+            "accounts_checked": accounts_checked,
+            "nonce_too_low": nonce_too_low,
+            "insufficient_balance": insufficient_balance,
+            "invalid_share": if total == 0 { 0.0 } else { invalid as f64 / total as f64 },
+        }),
+    );
+    Ok(())
+}
@@ -0,0 +1,125 @@
+use serde_json::Value;
+use std::collections::HashSet;
+use std::io::IsTerminal;
+
+use crate::quantity::{format_units, to_u128};
+use crate::snapshot::{self, find_field};
+
+const BOLD: &str = "\x1b[1m";
+const GREEN: &str = "\x1b[32m";
+const CYAN: &str = "\x1b[36m";
+const DIM: &str = "\x1b[2m";
+const RESET: &str = "\x1b[0m";
+
+/// Parse warnings listed under the summary; the rest are only counted.
+const MAX_WARNINGS_SHOWN: usize = 5;
+
+/// Whether the summary should use ANSI colors: never with `--no-color` or a
+/// non-empty `NO_COLOR` (https://no-color.org), and only when stderr is a terminal.
+pub fn use_color(no_color: bool) -> bool {
+    !no_color
+        && std::env::var_os("NO_COLOR").is_none_or(|value| value.is_empty())
+        && std::io::stderr().is_terminal()
+}
+
+/// Short human-readable report of a finished conversion, printed to stderr.
+pub fn print(snapshot: &Value, output: &str, duration_ms: u128, color: bool) {
+    let paint = |style: &str, text: String| {
+        if color {
+            format!("{}{}{}", style, text, RESET)
+        } else {
+            text
+        }
+    };
+
+    let mut txs = 0;
+    let mut accounts = HashSet::new();
+    let mut top_gas_price = None;
+    for entry in snapshot::transactions(snapshot) {
+        txs += 1;
+        accounts.insert(entry.account.to_ascii_lowercase());
+        let price = find_field(entry.tx, "max_fee_per_gas")
+            .or_else(|| find_field(entry.tx, "gas_price"))
+            .and_then(to_u128);
+        top_gas_price = top_gas_price.max(price);
+    }
+
+    let top_gas_price = top_gas_price
+        .map(|wei| format!("{} gwei", format_units(wei, 9, 2)))
+        .unwrap_or_else(|| "-".to_string());
+    eprintln!("{}", paint(&format!("{}{}", BOLD, GREEN), "✔ Converted txpool snapshot".to_string()));
+    eprintln!("  {:<15}{}", "transactions", paint(BOLD, txs.to_string()));
+    eprintln!("  {:<15}{}", "accounts", paint(BOLD, accounts.len().to_string()));
+    eprintln!("  {:<15}{}", "top gas price", paint(BOLD, top_gas_price));
+    if let Some(creations) = snapshot["metadata"]["contract_creations"]["count"].as_u64().filter(|n| *n > 0) {
+        eprintln!("  {:<15}{}", "creations", paint(BOLD, creations.to_string()));
+    }
+    if let Some(counts) = snapshot["metadata"]["value_bands"]["counts"].as_object()
+        && !counts.is_empty()
+    {
+        let bands: Vec<String> = ["whale", "retail", "dust", "zero"]
+            .iter()
+            .filter_map(|band| Some(format!("{} {}", counts.get(*band)?.as_u64()?, band)))
+            .collect();
+        eprintln!("  {:<15}{}", "value bands", paint(BOLD, bands.join(", ")));
+    }
+    let blob_market = &snapshot["metadata"]["blob_market"];
+    if let (Some(senders), Some(over_limit)) =
+        (blob_market["senders"].as_array(), blob_market["over_limit_senders"].as_u64())
+    {
+        eprintln!(
+            "  {:<15}{}",
+            "blob senders",
+            paint(BOLD, format!("{} ({} over the per-sender limit)", senders.len(), over_limit))
+        );
+    }
+    let age = &snapshot["metadata"]["age"];
+    if let (Some(p50), Some(p90)) = (age["p50"].as_u64(), age["p90"].as_u64()) {
+        eprintln!("  {:<15}{}", "pool age", paint(BOLD, format!("p50 {} s, p90 {} s", p50, p90)));
+    }
+    let activity = &snapshot["metadata"]["activity"];
+    if let Some(counts) = activity["counts"].as_object() {
+        let count = |class: &str| counts.get(class).and_then(Value::as_u64).unwrap_or(0);
+        eprintln!(
+            "  {:<15}{}",
+            "senders",
+            paint(
+                BOLD,
+                format!(
+                    "{} persistent, {} occasional, {} new (last {} blocks)",
+                    count("persistent"),
+                    count("occasional"),
+                    count("newcomer"),
+                    activity["blocks"]
+                )
+            )
+        );
+    }
+    eprintln!("  {:<15}{}", "output", paint(CYAN, output.to_string()));
+    eprintln!("  {:<15}{}", "parse time", paint(DIM, format!("{} ms", duration_ms)));
+
+    let warnings = &snapshot["metadata"]["parse_warnings"];
+    if let Some(count) = warnings["count"].as_u64() {
+        let kinds: Vec<String> = warnings["kinds"]
+            .as_object()
+            .into_iter()
+            .flatten()
+            .map(|(kind, n)| format!("{} {}", n, kind))
+            .collect();
+        eprintln!("  {:<15}{}", "warnings", paint(BOLD, format!("{} ({})", count, kinds.join(", "))));
+        let items = warnings["items"].as_array().map_or(&[][..], Vec::as_slice);
+        for item in items.iter().take(MAX_WARNINGS_SHOWN) {
+            let line = format!(
+                "{} at {}: {}",
+                item["kind"].as_str().unwrap_or("?"),
+                item["location"].as_str().unwrap_or("?"),
+                item["raw"].as_str().unwrap_or("")
+            );
+            eprintln!("    {}", paint(DIM, line));
+        }
+        if count > MAX_WARNINGS_SHOWN as u64 {
+            let more = count - MAX_WARNINGS_SHOWN as u64;
+            eprintln!("    {}", paint(DIM, format!("... and {} more in metadata.parse_warnings", more)));
+        }
+    }
+}
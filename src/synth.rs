@@ -0,0 +1,464 @@
+use serde_json::{Map, Value, json};
+use std::error::Error;
+use std::fmt::Write;
+
+/// Transaction types the generator can produce, by EIP-2718 type number.
+pub const TYPE_NAMES: [&str; 5] = ["legacy", "eip2930", "eip1559", "eip4844", "eip7702"];
+
+/// How priority fees are spread over `SynthSpec::tip_gwei`.
+#[derive(Clone, Copy, PartialEq)]
+pub enum FeeDistribution {
+    Uniform,
+    /// Most transactions near the low end with a thin tail of high bidders,
+    /// closer to a real pool.
+    LongTail,
+}
+
+impl FeeDistribution {
+    pub fn parse(name: &str) -> Result<Self, Box<dyn Error>> {
+        match name {
+            "uniform" => Ok(FeeDistribution::Uniform),
+            "long-tail" => Ok(FeeDistribution::LongTail),
+            other => Err(format!("Unknown fee distribution: {} (uniform, long-tail)", other).into()),
+        }
+    }
+}
+
+/// Layout of a generated dump.
+#[derive(Clone, Copy, PartialEq)]
+pub enum SynthFormat {
+    /// `TxpoolContent` debug dump.
+    Content,
+    /// `TxpoolInspect` debug dump.
+    Inspect,
+    /// geth `txpool_content` JSON result.
+    Json,
+}
+
+impl SynthFormat {
+    pub fn parse(name: &str) -> Result<Self, Box<dyn Error>> {
+        match name {
+            "content" => Ok(SynthFormat::Content),
+            "inspect" => Ok(SynthFormat::Inspect),
+            "json" => Ok(SynthFormat::Json),
+            other => Err(format!("Unknown generate format: {} (content, inspect, json)", other).into()),
+        }
+    }
+}
+
+/// Largest `--tip-gwei` bound: fee caps add up to 60 gwei on top of the tip
+/// and are generated in wei, which must fit in a `u64`.
+pub const MAX_TIP_GWEI: u64 = u64::MAX / 1_000_000_000 - 60;
+
+/// Shape of a synthetic pool dump.
+pub struct SynthSpec {
+    pub txs: usize,
+    pub txs_per_account: usize,
+    pub seed: u64,
+    /// Relative weight of each type in [`TYPE_NAMES`] order.
+    pub type_weights: [u64; 5],
+    /// Priority fee range in gwei (inclusive).
+    pub tip_gwei: (u64, u64),
+    pub fee_distribution: FeeDistribution,
+    /// Share of accounts (0 to 1) with a nonce gap; their transactions after
+    /// the gap are `queued`.
+    pub gap_rate: f64,
+}
+
+impl SynthSpec {
+    pub fn with_txs(txs: usize) -> Self {
+        SynthSpec {
+            txs,
+            txs_per_account: 8,
+            seed: 0x5eed,
+            type_weights: [1, 0, 3, 0, 0],
+            tip_gwei: (1, 5),
+            fee_distribution: FeeDistribution::Uniform,
+            gap_rate: 0.0,
+        }
+    }
+
+    /// Set type weights from `name=weight,...` (names from [`TYPE_NAMES`]);
+    /// unlisted types get weight 0.
+    pub fn set_type_mix(&mut self, mix: &str) -> Result<(), Box<dyn Error>> {
+        let mut weights = [0; 5];
+        for part in mix.split(',') {
+            let (name, weight) = part
+                .split_once('=')
+                .ok_or_else(|| format!("Invalid type mix entry {} (expected name=weight)", part))?;
+            let index = TYPE_NAMES
+                .iter()
+                .position(|known| *known == name.trim())
+                .ok_or_else(|| format!("Unknown transaction type {} ({})", name, TYPE_NAMES.join(", ")))?;
+            weights[index] = weight
+                .trim()
+                .parse()
+                .map_err(|_| format!("Invalid weight for {}: {}", name, weight))?;
+        }
+        if weights.iter().all(|weight| *weight == 0) {
+            return Err("Type mix needs at least one non-zero weight".into());
+        }
+        self.type_weights = weights;
+        Ok(())
+    }
+}
+
+/// Small deterministic PRNG (splitmix64) so generated dumps are reproducible.
+pub struct Rng(u64);
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Rng(seed)
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    pub fn range(&mut self, low: u64, high: u64) -> u64 {
+        low + self.next_u64() % (high - low + 1)
+    }
+
+    /// Uniform in `[0, 1)`.
+    pub fn unit(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    pub fn hex(&mut self, bytes: usize) -> String {
+        let mut out = String::with_capacity(bytes * 2 + 2);
+        out.push_str("0x");
+        for _ in 0..bytes.div_ceil(8) {
+            let _ = write!(out, "{:016x}", self.next_u64());
+        }
+        out.truncate(bytes * 2 + 2);
+        out
+    }
+
+    /// Index drawn with probability proportional to `weights`.
+    fn weighted(&mut self, weights: &[u64]) -> usize {
+        let total: u64 = weights.iter().sum();
+        let mut pick = self.next_u64() % total;
+        for (index, weight) in weights.iter().enumerate() {
+            if pick < *weight {
+                return index;
+            }
+            pick -= weight;
+        }
+        unreachable!("pick is below the total weight")
+    }
+}
+
+/// One generated transaction, rendered into each dump layout.
+struct SynthTx {
+    sender: String,
+    nonce: u64,
+    queued: bool,
+    ty: u8,
+    gas_limit: u64,
+    max_fee: u64,
+    priority: u64,
+    to: String,
+    value: u64,
+    input: String,
+    blob_hashes: Vec<String>,
+    max_fee_per_blob_gas: u64,
+    /// Delegation target of a set-code transaction's single authorization.
+    delegate: String,
+    y_parity: bool,
+    r: u64,
+    s: u64,
+    hash: String,
+}
+
+/// Draw the transactions of `spec`, grouped by sender in nonce order.
+fn generate(spec: &SynthSpec) -> Vec<SynthTx> {
+    let mut rng = Rng::new(spec.seed);
+    let per_account = spec.txs_per_account.max(1);
+    let mut txs = Vec::with_capacity(spec.txs);
+
+    let mut remaining = spec.txs;
+    while remaining > 0 {
+        let sender = rng.hex(20);
+        let count = remaining.min(per_account);
+        // Nonces from `gap_at` on skip 1 to 3 missing nonces.
+        let (gap_at, gap) = if count > 1 && rng.unit() < spec.gap_rate {
+            (rng.range(1, count as u64 - 1), rng.range(1, 3))
+        } else {
+            (u64::MAX, 0)
+        };
+        for index in 0..count as u64 {
+            let queued = index >= gap_at;
+            let nonce = if queued { index + gap } else { index };
+            txs.push(draw_tx(&mut rng, spec, &sender, nonce, queued));
+        }
+        remaining -= count;
+    }
+    txs
+}
+
+fn draw_tx(rng: &mut Rng, spec: &SynthSpec, sender: &str, nonce: u64, queued: bool) -> SynthTx {
+    let ty = rng.weighted(&spec.type_weights) as u8;
+    let gas_limit = [21_000, 65_000, 150_000, 300_000][rng.range(0, 3) as usize];
+    let (low, high) = spec.tip_gwei;
+    let spread = match spec.fee_distribution {
+        FeeDistribution::Uniform => rng.unit(),
+        FeeDistribution::LongTail => rng.unit().powi(4),
+    };
+    let priority = (low as f64 + (high - low) as f64 * spread).round() as u64 * 1_000_000_000;
+    let max_fee = priority + rng.range(5, 60) * 1_000_000_000;
+    let value = rng.range(0, 10_000) * 100_000_000_000_000;
+    let input = if gas_limit == 21_000 {
+        "0x".to_string()
+    } else {
+        let words = rng.range(1, 4) as usize;
+        rng.hex(4 + 32 * words)
+    };
+    let blob_hashes = if ty == 3 {
+        (0..rng.range(1, 6)).map(|_| format!("0x01{}", &rng.hex(31)[2..])).collect()
+    } else {
+        Vec::new()
+    };
+    let max_fee_per_blob_gas = if ty == 3 { rng.range(1, 100) * 1_000_000 } else { 0 };
+    let delegate = if ty == 4 { rng.hex(20) } else { String::new() };
+
+    SynthTx {
+        sender: sender.to_string(),
+        nonce,
+        queued,
+        ty,
+        gas_limit,
+        max_fee,
+        priority,
+        to: rng.hex(20),
+        value,
+        input,
+        blob_hashes,
+        max_fee_per_blob_gas,
+        delegate,
+        y_parity: rng.range(0, 1) == 1,
+        r: rng.next_u64(),
+        s: rng.next_u64(),
+        hash: rng.hex(32),
+    }
+}
+
+/// Generate a dump of `spec` in `format`.
+pub fn dump(spec: &SynthSpec, format: SynthFormat) -> String {
+    let txs = generate(spec);
+    match format {
+        SynthFormat::Content => render_debug(&txs, "TxpoolContent", write_tx),
+        SynthFormat::Inspect => render_debug(&txs, "TxpoolInspect", write_summary),
+        SynthFormat::Json => {
+            let mut sections = json!({ "pending": {}, "queued": {} });
+            for tx in &txs {
+                let section = if tx.queued { "queued" } else { "pending" };
+                sections[section]
+                    .as_object_mut()
+                    .expect("sections created above")
+                    .entry(tx.sender.clone())
+                    .or_insert_with(|| json!({}))[tx.nonce.to_string()] = rpc_tx(tx);
+            }
+            let mut out = serde_json::to_string_pretty(&sections).expect("JSON values always serialize");
+            out.push('\n');
+            out
+        }
+    }
+}
+
+/// `generate` subcommand: write the dump to `output`, or to stdout.
+pub fn run(spec: &SynthSpec, format: SynthFormat, output: Option<&str>) -> Result<(), Box<dyn Error>> {
+    let dump = dump(spec, format);
+    match output {
+        Some(path) => {
+            std::fs::write(path, &dump)?;
+            eprintln!("Generated {} transactions ({} bytes) into {}", spec.txs, dump.len(), path);
+        }
+        None => std::io::Write::write_all(&mut std::io::stdout().lock(), dump.as_bytes())?,
+    }
+    Ok(())
+}
+
+/// Generate a `TxpoolContent` dump in the same pretty `Debug` layout that
+/// `cast tx-pool content` prints.
+pub fn content_dump(spec: &SynthSpec) -> String {
+    dump(spec, SynthFormat::Content)
+}
+
+fn render_debug(txs: &[SynthTx], name: &str, write: fn(&mut String, &SynthTx)) -> String {
+    let mut out = String::with_capacity(txs.len() * 2_600);
+    let _ = writeln!(out, "{} {{", name);
+    for (section, queued) in [("pending", false), ("queued", true)] {
+        let _ = writeln!(out, "    {}: {{", section);
+        let in_section = txs.iter().filter(|tx| tx.queued == queued).collect::<Vec<_>>();
+        for account in in_section.chunk_by(|a, b| a.sender == b.sender) {
+            let _ = writeln!(out, "        {}: {{", account[0].sender);
+            for tx in account {
+                write(&mut out, tx);
+            }
+            out.push_str("        },\n");
+        }
+        out.push_str("    },\n");
+    }
+    out.push_str("}\n");
+    out
+}
+
+fn write_tx(out: &mut String, tx: &SynthTx) {
+    let pad = |depth: usize| " ".repeat(depth * 4);
+    let variant = ["Legacy", "Eip2930", "Eip1559", "Eip4844", "Eip7702"][tx.ty as usize];
+
+    let _ = writeln!(out, "{}\"{}\": AnyRpcTransaction(", pad(3), tx.nonce);
+    let _ = writeln!(out, "{}WithOtherFields {{", pad(4));
+    let _ = writeln!(out, "{}inner: Transaction {{", pad(5));
+    let _ = writeln!(out, "{}inner: Recovered {{", pad(6));
+    let _ = writeln!(out, "{}inner: Ethereum(", pad(7));
+    let _ = writeln!(out, "{}{}(", pad(8), variant);
+    let _ = writeln!(out, "{}Signed {{", pad(9));
+    let _ = writeln!(out, "{}tx: Tx{} {{", pad(10), variant);
+    if tx.ty == 0 {
+        let _ = writeln!(out, "{}chain_id: Some(\n{}1,\n{}),", pad(11), pad(12), pad(11));
+    } else {
+        let _ = writeln!(out, "{}chain_id: 1,", pad(11));
+    }
+    let _ = writeln!(out, "{}nonce: {},", pad(11), tx.nonce);
+    if tx.ty < 2 {
+        let _ = writeln!(out, "{}gas_price: {},", pad(11), tx.max_fee);
+        let _ = writeln!(out, "{}gas_limit: {},", pad(11), tx.gas_limit);
+    } else {
+        let _ = writeln!(out, "{}gas_limit: {},", pad(11), tx.gas_limit);
+        let _ = writeln!(out, "{}max_fee_per_gas: {},", pad(11), tx.max_fee);
+        let _ = writeln!(out, "{}max_priority_fee_per_gas: {},", pad(11), tx.priority);
+    }
+    if tx.ty >= 3 {
+        let _ = writeln!(out, "{}to: {},", pad(11), tx.to);
+    } else {
+        let _ = writeln!(out, "{}to: Call(\n{}{},\n{}),", pad(11), pad(12), tx.to, pad(11));
+    }
+    let _ = writeln!(out, "{}value: {},", pad(11), tx.value);
+    if tx.ty >= 1 {
+        let _ = writeln!(out, "{}access_list: AccessList(\n{}[],\n{}),", pad(11), pad(12), pad(11));
+    }
+    if tx.ty == 3 {
+        let _ = writeln!(out, "{}blob_versioned_hashes: [", pad(11));
+        for hash in &tx.blob_hashes {
+            let _ = writeln!(out, "{}{},", pad(12), hash);
+        }
+        let _ = writeln!(out, "{}],", pad(11));
+        let _ = writeln!(out, "{}max_fee_per_blob_gas: {},", pad(11), tx.max_fee_per_blob_gas);
+    }
+    if tx.ty == 4 {
+        let _ = writeln!(out, "{}authorization_list: [", pad(11));
+        let _ = writeln!(out, "{}SignedAuthorization {{", pad(12));
+        let _ = writeln!(out, "{}inner: Authorization {{", pad(13));
+        let _ = writeln!(out, "{}chain_id: 1,", pad(14));
+        let _ = writeln!(out, "{}address: {},", pad(14), tx.delegate);
+        let _ = writeln!(out, "{}nonce: {},", pad(14), tx.nonce + 1);
+        let _ = writeln!(out, "{}}},", pad(13));
+        let _ = writeln!(out, "{}y_parity: {},", pad(13), u8::from(tx.y_parity));
+        let _ = writeln!(out, "{}r: {},", pad(13), tx.r);
+        let _ = writeln!(out, "{}s: {},", pad(13), tx.s);
+        let _ = writeln!(out, "{}}},", pad(12));
+        let _ = writeln!(out, "{}],", pad(11));
+    }
+    let _ = writeln!(out, "{}input: {},", pad(11), tx.input);
+    let _ = writeln!(out, "{}}},", pad(10));
+    let _ = writeln!(out, "{}signature: PrimitiveSignature {{", pad(10));
+    let _ = writeln!(out, "{}y_parity: {},", pad(11), tx.y_parity);
+    let _ = writeln!(out, "{}r: {},", pad(11), tx.r);
+    let _ = writeln!(out, "{}s: {},", pad(11), tx.s);
+    let _ = writeln!(out, "{}}},", pad(10));
+    let _ = writeln!(out, "{}hash: OnceLock(\n{}{},\n{}),", pad(10), pad(11), tx.hash, pad(10));
+    let _ = writeln!(out, "{}}},", pad(9));
+    let _ = writeln!(out, "{}),", pad(8));
+    let _ = writeln!(out, "{}),", pad(7));
+    let _ = writeln!(out, "{}signer: {},", pad(7), tx.sender);
+    let _ = writeln!(out, "{}}},", pad(6));
+    for field in ["block_hash", "block_number", "transaction_index", "effective_gas_price"] {
+        let _ = writeln!(out, "{}{}: None,", pad(6), field);
+    }
+    let _ = writeln!(out, "{}}},", pad(5));
+    let _ = writeln!(out, "{}other: OtherFields {{\n{}inner: {{}},\n{}}},", pad(5), pad(6), pad(5));
+    let _ = writeln!(out, "{}}},", pad(4));
+    let _ = writeln!(out, "{}),", pad(3));
+}
+
+/// `txpool_inspect` summary; geth reports the fee cap as `gas_price`.
+fn write_summary(out: &mut String, tx: &SynthTx) {
+    let pad = |depth: usize| " ".repeat(depth * 4);
+    let _ = writeln!(out, "{}\"{}\": TxpoolInspectSummary {{", pad(3), tx.nonce);
+    let _ = writeln!(out, "{}to: Some({}),", pad(4), &tx.to[2..]);
+    let _ = writeln!(out, "{}value: {},", pad(4), tx.value);
+    let _ = writeln!(out, "{}gas: {},", pad(4), tx.gas_limit);
+    let _ = writeln!(out, "{}gas_price: {},", pad(4), tx.max_fee);
+    let _ = writeln!(out, "{}}},", pad(3));
+}
+
+/// The transaction as geth returns it from `txpool_content`.
+fn rpc_tx(tx: &SynthTx) -> Value {
+    let quantity = |value: u64| json!(format!("{:#x}", value));
+    let mut rpc = Map::new();
+    rpc.insert("type".to_string(), quantity(u64::from(tx.ty)));
+    rpc.insert("hash".to_string(), json!(tx.hash));
+    rpc.insert("from".to_string(), json!(tx.sender));
+    rpc.insert("nonce".to_string(), quantity(tx.nonce));
+    rpc.insert("gas".to_string(), quantity(tx.gas_limit));
+    rpc.insert("to".to_string(), json!(tx.to));
+    rpc.insert("value".to_string(), quantity(tx.value));
+    rpc.insert("input".to_string(), json!(tx.input));
+    rpc.insert("chainId".to_string(), quantity(1));
+    rpc.insert("gasPrice".to_string(), quantity(tx.max_fee));
+    if tx.ty >= 2 {
+        rpc.insert("maxFeePerGas".to_string(), quantity(tx.max_fee));
+        rpc.insert("maxPriorityFeePerGas".to_string(), quantity(tx.priority));
+    }
+    if tx.ty >= 1 {
+        rpc.insert("accessList".to_string(), json!([]));
+    }
+    if tx.ty == 3 {
+        rpc.insert("blobVersionedHashes".to_string(), json!(tx.blob_hashes));
+        rpc.insert("maxFeePerBlobGas".to_string(), quantity(tx.max_fee_per_blob_gas));
+    }
+    if tx.ty == 4 {
+        rpc.insert(
+            "authorizationList".to_string(),
+            json!([{
+                "chainId": quantity(1),
+                "address": tx.delegate,
+                "nonce": quantity(tx.nonce + 1),
+                "yParity": quantity(u64::from(tx.y_parity)),
+                "r": quantity(tx.r),
+                "s": quantity(tx.s),
+            }]),
+        );
+    }
+    if tx.ty == 0 {
+        rpc.insert("v".to_string(), quantity(37 + u64::from(tx.y_parity)));
+    } else {
+        rpc.insert("yParity".to_string(), quantity(u64::from(tx.y_parity)));
+    }
+    rpc.insert("r".to_string(), quantity(tx.r));
+    rpc.insert("s".to_string(), quantity(tx.s));
+    rpc.insert("blockHash".to_string(), Value::Null);
+    rpc.insert("blockNumber".to_string(), Value::Null);
+    rpc.insert("transactionIndex".to_string(), Value::Null);
+    Value::Object(rpc)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn largest_tip_range_fits_in_wei() {
+        let mut spec = SynthSpec::with_txs(200);
+        spec.tip_gwei = (MAX_TIP_GWEI, MAX_TIP_GWEI);
+        for tx in generate(&spec) {
+            assert_eq!(tx.priority, MAX_TIP_GWEI * 1_000_000_000);
+            assert!(tx.max_fee > tx.priority);
+        }
+    }
+}
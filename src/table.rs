@@ -0,0 +1,121 @@
+use serde_json::Value;
+use std::error::Error;
+use std::io::{IsTerminal, Write};
+use std::process::{Command, Stdio};
+
+use crate::quantity::{format_units, to_u128};
+use crate::rlp;
+use crate::snapshot::{self, find_field};
+
+const HEADERS: [&str; 6] = ["sender", "nonce", "to", "gwei", "value", "type"];
+
+/// Columns whose values are right-aligned.
+const NUMERIC: [bool; 6] = [false, true, false, true, true, false];
+
+/// Pager used when `PAGER` is unset; `-F` exits straight away when the table
+/// fits on one screen and `-S` keeps rows on a single line.
+const DEFAULT_PAGER: &str = "less -FRSX";
+
+fn type_name(tx_type: u8) -> String {
+    match tx_type {
+        0 => "legacy".to_string(),
+        1 => "2930".to_string(),
+        2 => "1559".to_string(),
+        3 => "4844".to_string(),
+        4 => "7702".to_string(),
+        other => other.to_string(),
+    }
+}
+
+fn row(tx: &Value, account: &str, nonce: &str) -> [String; 6] {
+    let to = find_field(tx, "to")
+        .and_then(Value::as_str)
+        .map(|to| if to.starts_with("0x") { to.to_ascii_lowercase() } else { "(create)".to_string() })
+        .unwrap_or_default();
+    // The fee cap is what the sender bids; legacy transactions only have a gas price.
+    let gwei = find_field(tx, "max_fee_per_gas")
+        .or_else(|| find_field(tx, "gas_price"))
+        .and_then(to_u128)
+        .map(|wei| format_units(wei, 9, 2))
+        .unwrap_or_default();
+    let value = find_field(tx, "value")
+        .and_then(to_u128)
+        .map(|wei| format_units(wei, 18, 6))
+        .unwrap_or_default();
+    [
+        account.to_ascii_lowercase(),
+        nonce.to_string(),
+        to,
+        gwei,
+        value,
+        type_name(rlp::tx_type(tx)),
+    ]
+}
+
+/// Aligned plain-text table of every transaction, one row each, with `gwei`
+/// the bid per gas and `value` in ether.
+pub fn to_table(snapshot: &Value) -> String {
+    let rows: Vec<[String; 6]> = snapshot::transactions(snapshot)
+        .map(|entry| row(entry.tx, entry.account, entry.nonce))
+        .collect();
+
+    let mut widths = HEADERS.map(str::len);
+    for row in &rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.len());
+        }
+    }
+
+    let format_row = |cells: &[String]| -> String {
+        cells
+            .iter()
+            .zip(widths.iter().zip(NUMERIC))
+            .map(|(cell, (&width, numeric))| {
+                if numeric {
+                    format!("{:>width$}", cell)
+                } else {
+                    format!("{:<width$}", cell)
+                }
+            })
+            .collect::<Vec<_>>()
+            .join("  ")
+            .trim_end()
+            .to_string()
+    };
+
+    let mut out = format_row(&HEADERS.map(str::to_string));
+    out.push('\n');
+    out.push_str(&format_row(&widths.map(|width| "-".repeat(width))));
+    out.push('\n');
+    for row in &rows {
+        out.push_str(&format_row(row));
+        out.push('\n');
+    }
+    out.push_str(&format!("{} transactions\n", rows.len()));
+    out
+}
+
+/// Print `table` to stdout, through `$PAGER` when stdout is a terminal.
+/// Falls back to printing directly if the pager cannot be started.
+pub fn page(table: &str) -> Result<(), Box<dyn Error>> {
+    let mut stdout = std::io::stdout();
+    if stdout.is_terminal() {
+        let pager = std::env::var("PAGER")
+            .ok()
+            .filter(|pager| !pager.trim().is_empty())
+            .unwrap_or_else(|| DEFAULT_PAGER.to_string());
+        let mut words = pager.split_whitespace();
+        if let Some(program) = words.next()
+            && let Ok(mut child) = Command::new(program).args(words).stdin(Stdio::piped()).spawn()
+        {
+            if let Some(mut stdin) = child.stdin.take() {
+                // The user quitting the pager early closes the pipe; that is not an error.
+                let _ = stdin.write_all(table.as_bytes());
+            }
+            child.wait()?;
+            return Ok(());
+        }
+    }
+    stdout.write_all(table.as_bytes())?;
+    Ok(())
+}
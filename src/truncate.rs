@@ -0,0 +1,254 @@
+use serde_json::{Value, json};
+use std::collections::HashSet;
+
+/// Caps on how much of a dump is parsed.
+#[derive(Clone, Copy, Default)]
+pub struct Limits {
+    /// Parse at most this many distinct accounts, in dump order.
+    pub max_accounts: Option<usize>,
+    /// Parse at most this many transactions per account (the lowest nonces).
+    pub max_txs_per_account: Option<usize>,
+}
+
+impl Limits {
+    pub fn is_set(&self) -> bool {
+        self.max_accounts.is_some() || self.max_txs_per_account.is_some()
+    }
+}
+
+/// What [`apply`] left out.
+#[derive(Default)]
+pub struct Truncation {
+    pub skipped_accounts: usize,
+    pub skipped_txs: usize,
+    /// `--max-input-bytes` when the input was longer and cut by [`close_partial`].
+    pub input_cut_at: Option<usize>,
+    /// `--timeout` passed while parsing; the accounts not parsed by then are
+    /// counted in `skipped_accounts`.
+    pub parse_timed_out: bool,
+}
+
+impl Truncation {
+    /// `metadata.truncated` and `metadata.truncation` of the parsed snapshot.
+    pub fn to_metadata(&self, limits: &Limits) -> (Value, Value) {
+        let truncated = self.skipped_accounts > 0
+            || self.skipped_txs > 0
+            || self.input_cut_at.is_some()
+            || self.parse_timed_out;
+        let details = json!({
+            "max_accounts": limits.max_accounts,
+            "max_txs_per_account": limits.max_txs_per_account,
+            "skipped_accounts": self.skipped_accounts,
+            "skipped_txs": self.skipped_txs,
+            "input_cut_at_bytes": self.input_cut_at,
+            "parse_timed_out": self.parse_timed_out,
+        });
+        (json!(truncated), details)
+    }
+}
+
+pub fn indent(line: &str) -> usize {
+    line.len() - line.trim_start().len()
+}
+
+/// Address of an account header line such as `0xabc…: {` (content dumps)
+/// or `abc…: {` (inspect dumps), lowercased.
+pub fn account_header(line: &str) -> Option<String> {
+    let address = line.trim().strip_suffix(": {")?;
+    let hex = address.strip_prefix("0x").unwrap_or(address);
+    (hex.len() == 40 && hex.bytes().all(|b| b.is_ascii_hexdigit())).then(|| hex.to_ascii_lowercase())
+}
+
+/// Nonce of a transaction entry line such as `"12": AnyRpcTransaction(`.
+fn entry_nonce(line: &str) -> Option<u64> {
+    let rest = line.trim_start().strip_prefix('"')?;
+    rest[..rest.find('"')?].parse().ok()
+}
+
+/// Cut accounts and transactions beyond `limits` out of a pretty-printed
+/// debug dump before it is parsed, so a quick look at a huge dump only pays
+/// for what it keeps. Blocks are found by indentation: an account runs from
+/// its header to the next line indented no deeper, and each transaction from
+/// its `"<nonce>":` line to the next one. Accounts are kept in dump order;
+/// within an account the lowest nonces are kept.
+pub fn apply(input: &str, limits: &Limits) -> (String, Truncation) {
+    let lines: Vec<&str> = input.split_inclusive('\n').collect();
+    let mut out = String::with_capacity(input.len());
+    let mut truncation = Truncation::default();
+    let mut accounts: HashSet<String> = HashSet::new();
+
+    let mut i = 0;
+    while i < lines.len() {
+        let Some(address) = account_header(lines[i]) else {
+            out.push_str(lines[i]);
+            i += 1;
+            continue;
+        };
+        let depth = indent(lines[i]);
+        let end = (i + 1..lines.len())
+            .find(|&j| !lines[j].trim().is_empty() && indent(lines[j]) <= depth)
+            .unwrap_or(lines.len());
+
+        // Start line and nonce of each transaction entry in the account body.
+        let body = &lines[i + 1..end];
+        let entry_depth = body.iter().find(|line| !line.trim().is_empty()).map(|line| indent(line));
+        let entries: Vec<(usize, u64)> = body
+            .iter()
+            .enumerate()
+            .filter(|(_, line)| Some(indent(line)) == entry_depth)
+            .filter_map(|(index, line)| Some((index, entry_nonce(line)?)))
+            .collect();
+
+        let is_new = !accounts.contains(&address);
+        if is_new && limits.max_accounts.is_some_and(|max| accounts.len() >= max) {
+            truncation.skipped_accounts += 1;
+            truncation.skipped_txs += entries.len();
+            i = end + 1;
+            continue;
+        }
+        accounts.insert(address);
+
+        let mut nonces: Vec<u64> = entries.iter().map(|(_, nonce)| *nonce).collect();
+        nonces.sort_unstable();
+        let keep = limits.max_txs_per_account.unwrap_or(usize::MAX).min(nonces.len());
+        let cutoff = nonces.get(keep).copied();
+        truncation.skipped_txs += nonces.len() - keep;
+
+        out.push_str(lines[i]);
+        let first_entry = entries.first().map_or(body.len(), |(index, _)| *index);
+        body[..first_entry].iter().for_each(|line| out.push_str(line));
+        for (n, (start, nonce)) in entries.iter().enumerate() {
+            let stop = entries.get(n + 1).map_or(body.len(), |(next, _)| *next);
+            if cutoff.is_none_or(|cutoff| *nonce < cutoff) {
+                body[*start..stop].iter().for_each(|line| out.push_str(line));
+            }
+        }
+        if let Some(closer) = lines.get(end) {
+            out.push_str(closer);
+        }
+        i = end + 1;
+    }
+
+    emit::emit!(
+        "{metric_agg} of {metric_name} is {metric_value}",
+        evt_kind: "metric",
+        metric_agg: "count",
+        metric_name: "txpool.parse.skipped_txs",
+        metric_value: truncation.skipped_txs,
+    );
+    (out, truncation)
+}
+
+/// Closing delimiter of a pretty-printed `Debug` line that opens a block.
+fn opener(line: &str) -> Option<char> {
+    match line.trim_end().chars().last()? {
+        '{' => Some('}'),
+        '(' => Some(')'),
+        '[' => Some(']'),
+        _ => None,
+    }
+}
+
+/// Turn the first bytes of a longer debug dump into a dump that parses: drop
+/// the incomplete last line and the account it belongs to, then close every
+/// block still open, innermost first, the way `{:#?}` would have. What came
+/// after the cut is unknown, so nothing is counted as skipped.
+pub fn close_partial(prefix: &str) -> String {
+    let mut lines: Vec<&str> = prefix.split_inclusive('\n').collect();
+    if lines.last().is_some_and(|line| !line.ends_with('\n')) {
+        lines.pop();
+    }
+
+    // (line index, indentation, closer, is an account header) of open blocks.
+    let mut open: Vec<(usize, usize, char, bool)> = Vec::new();
+    for (index, line) in lines.iter().enumerate() {
+        let depth = indent(line);
+        let trimmed = line.trim();
+        if let Some(&(_, open_depth, closer, _)) = open.last()
+            && depth == open_depth
+            && trimmed.starts_with(closer)
+        {
+            open.pop();
+            continue;
+        }
+        if let Some(closer) = opener(line) {
+            open.push((index, depth, closer, account_header(line).is_some()));
+        }
+    }
+
+    // An account still open at the cut is incomplete; leave it out.
+    let keep = match open.iter().position(|(_, _, _, is_account)| *is_account) {
+        Some(position) => {
+            let start = open[position].0;
+            open.truncate(position);
+            start
+        }
+        None => lines.len(),
+    };
+
+    let mut out: String = lines[..keep].concat();
+    for (position, (_, depth, closer, _)) in open.iter().enumerate().rev() {
+        out.push_str(&" ".repeat(*depth));
+        out.push(*closer);
+        if position > 0 {
+            out.push(',');
+        }
+        out.push('\n');
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FIRST_ACCOUNT: &str = "TxpoolContent {
+    pending: {
+        0x1111111111111111111111111111111111111111: {
+            \"0\": Tx {
+                nonce: 0,
+            },
+            \"1\": Tx {
+                nonce: 1,
+            },
+        },
+";
+
+    const SECOND_ACCOUNT: &str = "        0x2222222222222222222222222222222222222222: {
+            \"5\": Tx {
+                nonce: 5,
+            },
+        },
+";
+
+    fn dump() -> String {
+        format!("{}{}    }},\n    queued: {{}},\n}}\n", FIRST_ACCOUNT, SECOND_ACCOUNT)
+    }
+
+    #[test]
+    fn max_accounts_cuts_at_an_account_boundary() {
+        let limits = Limits { max_accounts: Some(1), max_txs_per_account: None };
+        let (kept, truncation) = apply(&dump(), &limits);
+        assert_eq!(kept, format!("{}    }},\n    queued: {{}},\n}}\n", FIRST_ACCOUNT));
+        assert_eq!((truncation.skipped_accounts, truncation.skipped_txs), (1, 1));
+    }
+
+    #[test]
+    fn max_txs_per_account_keeps_the_lowest_nonces() {
+        let limits = Limits { max_accounts: None, max_txs_per_account: Some(1) };
+        let (kept, truncation) = apply(&dump(), &limits);
+        assert!(kept.contains("\"0\": Tx") && !kept.contains("\"1\": Tx") && kept.contains("\"5\": Tx"));
+        assert_eq!((truncation.skipped_accounts, truncation.skipped_txs), (0, 1));
+    }
+
+    #[test]
+    fn close_partial_drops_the_account_at_the_cut() {
+        let closed = format!("{}    }},\n}}\n", FIRST_ACCOUNT);
+        let dump = dump();
+        // Cut in the middle of the second account's transaction
+        let cut = FIRST_ACCOUNT.len() + SECOND_ACCOUNT.find("nonce: 5").unwrap();
+        assert_eq!(close_partial(&dump[..cut]), closed);
+        // Cut exactly after the first account
+        assert_eq!(close_partial(&dump[..FIRST_ACCOUNT.len()]), closed);
+    }
+}
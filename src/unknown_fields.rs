@@ -0,0 +1,94 @@
+use regex::Regex;
+use serde_json::{Map, Value};
+use std::collections::{BTreeMap, BTreeSet};
+use std::error::Error;
+
+use crate::snapshot;
+
+/// What to do with field names the parser does not know.
+#[derive(Clone, Copy, Default, PartialEq)]
+pub enum UnknownFields {
+    /// Quote them like known fields and keep them where they are.
+    #[default]
+    Preserve,
+    /// Quote them and move them into an `extra` object next to the known fields.
+    Extra,
+    /// Fail with the list of unknown fields instead of writing any output.
+    Strict,
+}
+
+impl UnknownFields {
+    pub fn parse(name: &str) -> Result<Self, Box<dyn Error>> {
+        match name {
+            "preserve" => Ok(UnknownFields::Preserve),
+            "extra" => Ok(UnknownFields::Extra),
+            "strict" => Ok(UnknownFields::Strict),
+            other => Err(format!("Unknown --unknown-fields mode: {} (preserve, extra, strict)", other).into()),
+        }
+    }
+}
+
+/// Quote every remaining bare `name:` key in a partly cleaned dump, after the
+/// known fields have been quoted. Returns the rewritten text and the unknown
+/// field names, or an error listing them in [`UnknownFields::Strict`] mode.
+/// Each unknown name is counted in `txpool.field.unknown`.
+pub fn quote(cleaned: &str, mode: UnknownFields) -> Result<(String, BTreeSet<String>), Box<dyn Error>> {
+    let key = Regex::new(r"([{,(\s])([a-z_][a-zA-Z0-9_]*)\s*:")?;
+
+    let mut counts: BTreeMap<String, i64> = BTreeMap::new();
+    for caps in key.captures_iter(cleaned) {
+        *counts.entry(caps[2].to_string()).or_insert(0) += 1;
+    }
+    for (field, count) in &counts {
+        emit::emit!(
+            "{metric_agg} of {metric_name} is {metric_value}",
+            evt_kind: "metric",
+            metric_agg: "count",
+            metric_name: "txpool.field.unknown",
+            metric_value: count,
+            field,
+        );
+    }
+
+    if mode == UnknownFields::Strict && !counts.is_empty() {
+        let fields: Vec<&str> = counts.keys().map(String::as_str).collect();
+        return Err(format!(
+            "Dump contains fields outside the allowlist: {} (use --unknown-fields preserve or extra to keep them)",
+            fields.join(", ")
+        )
+        .into());
+    }
+
+    let quoted = key.replace_all(cleaned, "$1\"$2\":").to_string();
+    Ok((quoted, counts.into_keys().collect()))
+}
+
+/// Move `unknown` fields of every transaction object, at any depth, into an
+/// `extra` object on the same level.
+pub fn move_to_extra(snapshot: &mut Value, unknown: &BTreeSet<String>) {
+    if unknown.is_empty() {
+        return;
+    }
+    for tx in snapshot::transactions_mut(snapshot) {
+        move_nested(tx, unknown);
+    }
+}
+
+fn move_nested(value: &mut Value, unknown: &BTreeSet<String>) {
+    match value {
+        Value::Object(map) => {
+            let mut extra = Map::new();
+            for field in unknown {
+                if let Some(moved) = map.remove(field) {
+                    extra.insert(field.clone(), moved);
+                }
+            }
+            map.values_mut().for_each(|nested| move_nested(nested, unknown));
+            if !extra.is_empty() {
+                map.insert("extra".to_string(), Value::Object(extra));
+            }
+        }
+        Value::Array(items) => items.iter_mut().for_each(|item| move_nested(item, unknown)),
+        _ => {}
+    }
+}
@@ -0,0 +1,259 @@
+use serde_json::Value;
+use std::collections::{BTreeMap, BTreeSet};
+use std::error::Error;
+
+use crate::snapshot::{self, find_field};
+
+/// Invariants that must survive the cleanup heuristics unchanged.
+#[derive(Default)]
+struct Invariants {
+    /// Nonces per account, keyed by lowercase address without `0x`.
+    nonces: BTreeMap<String, BTreeSet<String>>,
+    tx_count: usize,
+    total_value: DecimalSum,
+    /// Transactions whose value is not a decimal integer.
+    unreadable_values: Vec<String>,
+}
+
+/// Sum of decimal integers of any size, kept as little-endian digits so U256
+/// values never overflow or round.
+#[derive(Default, PartialEq)]
+struct DecimalSum(Vec<u8>);
+
+impl DecimalSum {
+    /// Add `digits`, or return false if it is not a decimal integer.
+    fn add(&mut self, digits: &str) -> bool {
+        if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+            return false;
+        }
+        let mut carry = 0;
+        for (index, digit) in digits.bytes().rev().map(|b| b - b'0').enumerate() {
+            if index == self.0.len() {
+                self.0.push(0);
+            }
+            let sum = self.0[index] + digit + carry;
+            self.0[index] = sum % 10;
+            carry = sum / 10;
+        }
+        let mut index = digits.len();
+        while carry > 0 {
+            if index == self.0.len() {
+                self.0.push(0);
+            }
+            let sum = self.0[index] + carry;
+            self.0[index] = sum % 10;
+            carry = sum / 10;
+            index += 1;
+        }
+        while self.0.last() == Some(&0) {
+            self.0.pop();
+        }
+        true
+    }
+}
+
+impl std::fmt::Display for DecimalSum {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.0.is_empty() {
+            return f.write_str("0");
+        }
+        let digits: String = self.0.iter().rev().map(|d| char::from(b'0' + d)).collect();
+        f.write_str(&digits)
+    }
+}
+
+/// Re-serialize the parsed snapshot and compare it against invariants derived
+/// straight from the raw input, failing if anything was lost or invented.
+pub fn validate(input: &str, parsed: &Value) -> Result<(), Box<dyn Error>> {
+    let round_tripped: Value = serde_json::from_str(&serde_json::to_string(parsed)?)?;
+    let expected = scan_input(input)?;
+    let actual = from_snapshot(&round_tripped);
+
+    let mut mismatches = Vec::new();
+    if expected.tx_count != actual.tx_count {
+        mismatches.push(format!(
+            "tx count: input has {}, snapshot has {}",
+            expected.tx_count, actual.tx_count
+        ));
+    }
+    if expected.total_value != actual.total_value {
+        mismatches.push(format!(
+            "total value: input has {}, snapshot has {}",
+            expected.total_value, actual.total_value
+        ));
+    }
+    for tx in &actual.unreadable_values {
+        mismatches.push(format!("value of {} in snapshot is not a decimal integer", tx));
+    }
+
+    let accounts: BTreeSet<&String> = expected.nonces.keys().chain(actual.nonces.keys()).collect();
+    for account in accounts {
+        let empty = BTreeSet::new();
+        let want = expected.nonces.get(account).unwrap_or(&empty);
+        let got = actual.nonces.get(account).unwrap_or(&empty);
+        if want != got {
+            let missing: Vec<&String> = want.difference(got).collect();
+            let extra: Vec<&String> = got.difference(want).collect();
+            mismatches.push(format!(
+                "account 0x{}: {} txs in input, {} in snapshot (missing nonces {:?}, unexpected nonces {:?})",
+                account,
+                want.len(),
+                got.len(),
+                missing,
+                extra
+            ));
+        }
+    }
+
+    let mismatch_count = mismatches.len();
+    emit::emit!(
+        "{metric_agg} of {metric_name} is {metric_value}",
+        evt_kind: "metric",
+        metric_agg: "count",
+        metric_name: "txpool.validate.mismatches",
+        metric_value: mismatch_count,
+    );
+
+    if mismatches.is_empty() {
+        let tx_count = actual.tx_count;
+        emit::info!("Validation passed for {tx_count} transactions", tx_count);
+        return Ok(());
+    }
+
+    for mismatch in &mismatches {
+        emit::error!("Validation mismatch: {mismatch}", mismatch);
+        eprintln!("Validation mismatch: {}", mismatch);
+    }
+    Err(format!("Validation failed with {} mismatches", mismatch_count).into())
+}
+
+/// Independent line scanner over the raw debug text. It deliberately shares
+/// nothing with the parser beyond the shape of the `Debug` output.
+fn scan_input(input: &str) -> Result<Invariants, Box<dyn Error>> {
+    let mut invariants = Invariants::default();
+    let mut account: Option<String> = None;
+    let mut in_tx = false;
+
+    for line in input.lines() {
+        let trimmed = line.trim();
+
+        if let Some(key) = trimmed.strip_suffix(": {")
+            && is_address(key)
+        {
+            account = Some(normalize_address(key));
+            in_tx = false;
+            continue;
+        }
+
+        if let Some(rest) = trimmed.strip_prefix('"')
+            && let Some((nonce, _)) = rest.split_once("\":")
+            && let Some(account) = &account
+            && !nonce.is_empty()
+            && nonce.bytes().all(|b| b.is_ascii_digit())
+        {
+            invariants
+                .nonces
+                .entry(account.clone())
+                .or_default()
+                .insert(nonce.to_string());
+            invariants.tx_count += 1;
+            in_tx = true;
+            continue;
+        }
+
+        if in_tx && let Some(value) = trimmed.strip_prefix("value: ") {
+            let digits = value.trim_end_matches(',').replace('_', "");
+            if !invariants.total_value.add(&digits) {
+                return Err(format!("Validation scanner could not read value: {}", value).into());
+            }
+            in_tx = false;
+        }
+    }
+
+    Ok(invariants)
+}
+
+fn from_snapshot(snapshot: &Value) -> Invariants {
+    let mut invariants = Invariants::default();
+
+    for entry in snapshot::transactions(snapshot) {
+        invariants
+            .nonces
+            .entry(normalize_address(entry.account))
+            .or_default()
+            .insert(entry.nonce.to_string());
+        invariants.tx_count += 1;
+
+        if let Some(value) = find_field(entry.tx, "value") {
+            let value = match value {
+                Value::Number(n) => n.to_string(),
+                other => other.as_str().unwrap_or_default().to_string(),
+            };
+            // Anything that is not an exact integer is reported as a
+            // mismatch, not skipped.
+            if !invariants.total_value.add(&value) {
+                invariants
+                    .unreadable_values
+                    .push(format!("{} nonce {}", entry.account, entry.nonce));
+            }
+        }
+    }
+
+    invariants
+}
+
+fn is_address(key: &str) -> bool {
+    let hex = key.strip_prefix("0x").unwrap_or(key);
+    hex.len() == 40 && hex.bytes().all(|b| b.is_ascii_hexdigit())
+}
+
+fn normalize_address(address: &str) -> String {
+    address.strip_prefix("0x").unwrap_or(address).to_ascii_lowercase()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::input_format::InputFormat;
+    use crate::synth::{self, SynthSpec};
+    use crate::unknown_fields::UnknownFields;
+
+    fn parse(dump: &str) -> Value {
+        crate::parse_debug_format(dump, InputFormat::Auto, UnknownFields::default(), None)
+            .unwrap()
+            .0
+    }
+
+    #[test]
+    fn dropped_account_is_caught() {
+        let dump = synth::content_dump(&SynthSpec::with_txs(100));
+        let mut parsed = parse(&dump);
+        assert!(validate(&dump, &parsed).is_ok());
+
+        let pending = parsed["pending"].as_object_mut().unwrap();
+        let account = pending.keys().next().unwrap().clone();
+        pending.remove(&account);
+        assert!(validate(&dump, &parsed).is_err());
+    }
+
+    #[test]
+    fn values_beyond_u128_are_summed_exactly() {
+        let dump = synth::content_dump(&SynthSpec::with_txs(20));
+        let start = dump.find("value: ").unwrap() + "value: ".len();
+        let end = start + dump[start..].find(',').unwrap();
+        // 2^256 - 1, the largest U256.
+        let max = "115792089237316195423570985008687907853269984665640564039457584007913129639935";
+        let dump = format!("{}{}{}", &dump[..start], max, &dump[end..]);
+        let parsed = parse(&dump);
+        assert!(validate(&dump, &parsed).is_ok());
+
+        let mut expected = DecimalSum::default();
+        assert!(expected.add(max));
+        assert!(expected.add("1"));
+        assert_eq!(
+            expected.to_string(),
+            "115792089237316195423570985008687907853269984665640564039457584007913129639936"
+        );
+        assert!(!expected.add("0x10"));
+    }
+}
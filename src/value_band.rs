@@ -0,0 +1,76 @@
+use serde_json::{Value, json};
+use std::collections::BTreeMap;
+
+use crate::quantity::{format_units, to_u128};
+use crate::snapshot::{self, find_field};
+
+/// Value thresholds (wei) of the `value_band` labels.
+#[derive(Clone, Copy)]
+pub struct ValueBands {
+    /// Transfers below this are `dust`: 0.01 ETH by default.
+    pub dust_below: u128,
+    /// Transfers of at least this are `whale`: 100 ETH by default.
+    pub whale_from: u128,
+}
+
+impl Default for ValueBands {
+    fn default() -> Self {
+        ValueBands {
+            dust_below: 10_000_000_000_000_000,
+            whale_from: 100_000_000_000_000_000_000,
+        }
+    }
+}
+
+impl ValueBands {
+    /// Band of a transfer of `value` wei. Transactions moving no ETH (most
+    /// contract calls) are `zero` rather than dust.
+    pub fn band(&self, value: u128) -> &'static str {
+        if value == 0 {
+            "zero"
+        } else if value < self.dust_below {
+            "dust"
+        } else if value >= self.whale_from {
+            "whale"
+        } else {
+            "retail"
+        }
+    }
+}
+
+/// Label every transaction with a `value` by its `value_band` (`zero`,
+/// `dust`, `retail` or `whale`). Counts per band and the thresholds go to
+/// `metadata.value_bands` and `txpool.value.band_txs`.
+pub fn annotate(snapshot: &mut Value, bands: &ValueBands) {
+    let mut counts: BTreeMap<&'static str, usize> = BTreeMap::new();
+    for tx in snapshot::transactions_mut(snapshot) {
+        let Some(value) = find_field(tx, "value").and_then(to_u128) else {
+            continue;
+        };
+        let band = bands.band(value);
+        *counts.entry(band).or_insert(0) += 1;
+        if let Some(tx) = tx.as_object_mut() {
+            tx.insert("value_band".to_string(), json!(band));
+        }
+    }
+
+    for (value_band, count) in &counts {
+        emit::emit!(
+            "{metric_agg} of {metric_name} is {metric_value}",
+            evt_kind: "metric",
+            metric_agg: "last",
+            metric_name: "txpool.value.band_txs",
+            metric_value: count,
+            value_band,
+        );
+    }
+
+    snapshot::metadata_mut(snapshot).insert(
+        "value_bands".to_string(),
+        json!({
+            "dust_below_eth": format_units(bands.dust_below, 18, 6),
+            "whale_from_eth": format_units(bands.whale_from, 18, 6),
+            "counts": counts,
+        }),
+    );
+}
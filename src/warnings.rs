@@ -0,0 +1,127 @@
+use regex::Regex;
+use serde_json::{Value, json};
+use std::collections::BTreeMap;
+
+use crate::snapshot;
+
+/// Longest excerpt of the input kept with a warning.
+const MAX_RAW_CHARS: usize = 200;
+
+/// Warnings kept in `metadata.parse_warnings` and emitted as events; the
+/// rest are only counted.
+const MAX_RECORDED: usize = 100;
+
+/// What the parser worked around instead of failing.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum WarningKind {
+    /// A `Name {` or `Name(` wrapper outside the known list, removed like
+    /// the known ones.
+    UnknownWrapper,
+    /// A line the parser has no field for, left out of the snapshot.
+    DroppedField,
+    /// A number too large for its field, left out of the snapshot.
+    NumericOverflow,
+    /// An account block that failed to parse (or panicked the parser) on its
+    /// own, left out of the snapshot.
+    AccountFailed,
+}
+
+impl WarningKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            WarningKind::UnknownWrapper => "unknown_wrapper",
+            WarningKind::DroppedField => "dropped_field",
+            WarningKind::NumericOverflow => "numeric_overflow",
+            WarningKind::AccountFailed => "account_failed",
+        }
+    }
+}
+
+/// Something in a dump the parser absorbed, with where it was and the text
+/// it was found in.
+#[derive(Clone, Debug)]
+pub struct ParseWarning {
+    pub kind: WarningKind,
+    /// Line of the input, prefixed with the account and nonce when known.
+    pub location: String,
+    /// The input line (the error for a failed account), trimmed and cut to
+    /// 200 characters.
+    pub raw: String,
+}
+
+impl ParseWarning {
+    pub fn new(kind: WarningKind, location: String, raw: &str) -> Self {
+        ParseWarning {
+            kind,
+            location,
+            raw: raw.trim().chars().take(MAX_RAW_CHARS).collect(),
+        }
+    }
+
+    /// Warning for the first `name {` or `name(` wrapper in `input`.
+    pub(crate) fn unknown_wrapper(input: &str, name: &str) -> Self {
+        let first = Regex::new(&format!(r"\b{}(\s*\{{|\()", regex::escape(name)))
+            .ok()
+            .and_then(|wrapper| wrapper.find(input))
+            .map_or(0, |found| found.start());
+        let line_start = input[..first].rfind('\n').map_or(0, |newline| newline + 1);
+        let line_end = input[first..].find('\n').map_or(input.len(), |newline| first + newline);
+        let line = input[..first].matches('\n').count() + 1;
+        ParseWarning::new(WarningKind::UnknownWrapper, format!("line {}", line), &input[line_start..line_end])
+    }
+
+    pub fn to_json(&self) -> Value {
+        json!({
+            "kind": self.kind.as_str(),
+            "location": self.location,
+            "raw": self.raw,
+        })
+    }
+}
+
+/// Emit each warning as an event and the counts per kind as
+/// `txpool.parse.warnings`.
+pub fn report(warnings: &[ParseWarning]) {
+    for warning in warnings.iter().take(MAX_RECORDED) {
+        let kind = warning.kind.as_str();
+        let location = &warning.location;
+        let raw = &warning.raw;
+        emit::warn!("Parse warning {kind} at {location}: {raw}", kind, location, raw);
+    }
+    for (kind, metric_value) in counts(warnings) {
+        emit::emit!(
+            "{metric_agg} of {metric_name} is {metric_value}",
+            evt_kind: "metric",
+            metric_agg: "count",
+            metric_name: "txpool.parse.warnings",
+            metric_value,
+            kind,
+        );
+    }
+}
+
+/// [`report`] the warnings and keep them in `metadata.parse_warnings`
+/// (`count`, `kinds` and the first 100 as `items`) for the summary.
+pub fn record(snapshot: &mut Value, warnings: &[ParseWarning]) {
+    if warnings.is_empty() {
+        return;
+    }
+    report(warnings);
+    let items: Vec<Value> = warnings.iter().take(MAX_RECORDED).map(ParseWarning::to_json).collect();
+    snapshot::metadata_mut(snapshot).insert(
+        "parse_warnings".to_string(),
+        json!({
+            "count": warnings.len(),
+            "kinds": counts(warnings),
+            "items": items,
+        }),
+    );
+}
+
+fn counts(warnings: &[ParseWarning]) -> BTreeMap<&'static str, usize> {
+    let mut counts = BTreeMap::new();
+    for warning in warnings {
+        *counts.entry(warning.kind.as_str()).or_insert(0) += 1;
+    }
+    counts
+}
@@ -0,0 +1,81 @@
+use notify::{EventKind, RecursiveMode, Watcher};
+use std::collections::HashMap;
+use std::error::Error;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use std::time::{Duration, Instant};
+
+use crate::budget;
+use crate::cli::ConvertOptions;
+
+/// How long a file must go without further writes before it is converted, so
+/// dumps still being written are not picked up half-way.
+const QUIET_PERIOD: Duration = Duration::from_millis(500);
+
+/// Extensions of files this tool writes; they are never treated as dumps.
+const OUTPUT_EXTENSIONS: [&str; 6] = ["json", "avro", "pb", "cbor", "csv", "ndjson"];
+
+/// Whether `path` looks like a dump rather than one of our outputs or an
+/// editor/partial-download temporary file.
+fn is_dump(path: &Path) -> bool {
+    let Some(name) = path.file_name().and_then(|name| name.to_str()) else {
+        return false;
+    };
+    let extension = path.extension().and_then(|ext| ext.to_str()).unwrap_or("");
+    !name.starts_with('.')
+        && !name.ends_with('~')
+        && !matches!(extension, "tmp" | "part" | "swp")
+        && !OUTPUT_EXTENSIONS.contains(&extension)
+}
+
+/// Watch `dir` and convert every new or rewritten dump file, writing the
+/// outputs next to it (`pool.txt` becomes `pool.json`, `pool.builder.json`, ...).
+/// A dump that fails to convert is reported and skipped; the watcher keeps running.
+pub fn run(dir: &str, options: &ConvertOptions) -> Result<(), Box<dyn Error>> {
+    let (sender, events) = mpsc::channel();
+    let mut watcher = notify::recommended_watcher(sender)?;
+    watcher.watch(Path::new(dir), RecursiveMode::NonRecursive)?;
+    emit::info!("Watching {dir} for txpool dumps", dir);
+    println!("Watching {} for txpool dumps", dir);
+
+    let mut changed: HashMap<PathBuf, Instant> = HashMap::new();
+    loop {
+        match events.recv_timeout(QUIET_PERIOD / 2) {
+            Ok(event) => {
+                let event = event?;
+                if matches!(event.kind, EventKind::Create(_) | EventKind::Modify(_)) {
+                    for path in event.paths.into_iter().filter(|path| is_dump(path)) {
+                        changed.insert(path, Instant::now());
+                    }
+                }
+            }
+            Err(mpsc::RecvTimeoutError::Timeout) => {}
+            Err(mpsc::RecvTimeoutError::Disconnected) => return Ok(()),
+        }
+
+        let ready: Vec<PathBuf> = changed
+            .iter()
+            .filter(|(_, last_write)| last_write.elapsed() >= QUIET_PERIOD)
+            .map(|(path, _)| path.clone())
+            .collect();
+        for path in ready {
+            changed.remove(&path);
+            if !path.is_file() {
+                continue;
+            }
+            if let Err(err) = convert_file(&path, options) {
+                let file = path.display().to_string();
+                emit::error!("Failed to convert {file}: {err}", file, err: err.to_string());
+                eprintln!("Failed to convert {}: {}", file, err);
+            }
+        }
+    }
+}
+
+fn convert_file(path: &Path, options: &ConvertOptions) -> Result<(), Box<dyn Error>> {
+    let input = budget::read(fs::File::open(path)?, options.max_input_bytes)?;
+    let stem = path.with_extension("").display().to_string();
+    // Each dump gets fresh options so RPC-derived fees are looked up again.
+    crate::convert_dump(input, &mut options.clone(), Some(stem))
+}